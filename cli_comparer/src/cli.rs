@@ -1,7 +1,9 @@
 //! Взаимодействие с аргументами командной строки.
 
+use crate::result::CompareOptions;
 use clap::{Parser, ValueEnum};
 use parser::YPFormatSupported;
+use parser::analytics::SortKey;
 use std::path::PathBuf;
 use std::process::exit;
 
@@ -24,6 +26,37 @@ struct Args {
     /// The format of the second file (from the supported types).
     #[clap(long, value_enum, value_name = "format2")]
     second_file_format: FileFormat,
+
+    /// Treat a zero FROM_USER_ID or TO_USER_ID as a wildcard matching any value on the other
+    /// side (for sources that do not record the sender or the recipient).
+    #[clap(long = "zero-user-wildcard")]
+    zero_user_wildcard: bool,
+
+    /// The algorithm used to match records of the two files.
+    #[clap(long = "diff-algo", value_enum, default_value_t = DiffAlgo::Positional)]
+    diff_algo: DiffAlgo,
+
+    /// Compare the files as multisets of records, ignoring their order: only records present
+    /// in one file but not in the other are reported.
+    #[clap(long, conflicts_with_all = ["diff_algo", "zero_user_wildcard"])]
+    unordered: bool,
+
+    /// Sort the records of both files by the given field (ascending, ties keep the file order)
+    /// before comparing them.
+    #[clap(long = "sort-by", value_enum, value_name = "FIELD")]
+    sort_by: Option<SortBy>,
+
+    /// Only count mismatched records, comparing the files pairwise by position as they are read
+    /// (CSV and BIN files are not loaded into memory entirely).
+    #[clap(
+        long = "count-only",
+        conflicts_with_all = ["diff_algo", "unordered", "zero_user_wildcard", "sort_by", "show_diff"]
+    )]
+    count_only: bool,
+
+    /// Print every mismatch with the differing fields, and the extra records of the longer file.
+    #[clap(long = "show-diff")]
+    show_diff: bool,
 }
 
 #[derive(Copy, Clone, Debug, ValueEnum)]
@@ -48,6 +81,39 @@ impl FileFormat {
     }
 }
 
+/// Алгоритм сопоставления записей двух наборов.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum DiffAlgo {
+    /// Records are compared pairwise by their position.
+    #[default]
+    Positional,
+    /// Minimal edit script (longest common subsequence): an inserted or deleted record is
+    /// reported once instead of shifting all the following records. Very large differing
+    /// ranges fall back to positional comparison.
+    Lcs,
+}
+
+/// Поле для предварительной сортировки записей обоих файлов.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum SortBy {
+    /// Transaction identifier (TX_ID).
+    TxId,
+    /// Transaction time (TIMESTAMP).
+    Timestamp,
+    /// Signed transaction amount (AMOUNT).
+    Amount,
+}
+
+impl SortBy {
+    pub fn to_sort_key(self) -> SortKey {
+        match self {
+            SortBy::TxId => SortKey::TxId,
+            SortBy::Timestamp => SortKey::Timestamp,
+            SortBy::Amount => SortKey::Amount,
+        }
+    }
+}
+
 /// Структура для задачи сравнения данных.
 pub struct ComparerTask {
     /// Путь к первому файлу.
@@ -58,6 +124,18 @@ pub struct ComparerTask {
     pub first_format: FileFormat,
     /// Формат данных во втором файле (из предустановленных).
     pub second_format: FileFormat,
+    /// Поле, по которому записи обоих файлов сортируются перед сравнением, если задано.
+    pub sort_by: Option<SortKey>,
+    /// Настройки сравнения.
+    pub options: CompareOptions,
+    /// Выводить подробный отчёт о расхождениях, см. [`CompareResult::details`].
+    ///
+    /// [`CompareResult::details`]: crate::result::CompareResult::details
+    pub show_diff: bool,
+    /// Только подсчитать расхождения потоковым сравнением, см. [`compare_streams`].
+    ///
+    /// [`compare_streams`]: crate::result::compare_streams
+    pub count_only: bool,
 }
 
 impl ComparerTask {
@@ -105,6 +183,14 @@ pub fn cli_parse() -> ComparerTask {
         second_file: args.second_file,
         first_format: args.first_file_format,
         second_format: args.second_file_format,
+        sort_by: args.sort_by.map(SortBy::to_sort_key),
+        options: CompareOptions {
+            zero_user_wildcard: args.zero_user_wildcard,
+            diff_algo: args.diff_algo,
+            unordered: args.unordered,
+        },
+        show_diff: args.show_diff,
+        count_only: args.count_only,
     };
 
     if let Some(message) = compare_task.validate() {
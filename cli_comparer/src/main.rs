@@ -36,38 +36,48 @@
 #![warn(missing_docs)]
 
 use crate::cli::{ComparerTask, cli_parse};
-use crate::tools::open_file;
+use crate::result::{CompareResult, compare_streams};
+use crate::tools::{open_file, transaction_stream};
+use parser::analytics::{SortOrder, sort_transactions};
 use parser::errors::ParseError;
-use parser::models::YPBankTransaction;
 use std::process::exit;
 
 mod cli;
+mod result;
 mod tools;
 
 fn main() {
     let task = cli_parse();
     println!("Thanks. Let's go...");
 
-    let result = execute_compare_task(&task).unwrap_or_else(|err| {
-        eprintln!("ERROR: {}", err);
-        exit(1);
-    });
-
     let filenames = task
         .get_filenames()
         .unwrap_or_else(|| ("unknow".to_string(), "unknow".to_string()));
 
-    if result == 0 {
+    if task.count_only {
+        let mismatched = execute_count_only(&task).unwrap_or_else(|err| {
+            eprintln!("ERROR: {}", err);
+            exit(1);
+        });
         println!(
-            "The transaction records in '{}' and '{}' are IDENTICAL",
-            filenames.0, filenames.1
+            "Number of mismatched elements in '{}' and '{}': {}",
+            filenames.0, filenames.1, mismatched
         );
-    } else {
-        println!(
-            "The transaction records in '{}' and '{}' are NOT IDENTICAL",
-            filenames.0, filenames.1
-        );
-        println!("Number of mismatched elements: {}", result);
+        return;
+    }
+
+    let result = execute_compare_task(&task).unwrap_or_else(|err| {
+        eprintln!("ERROR: {}", err);
+        exit(1);
+    });
+
+    println!(
+        "The transaction records in '{}' and '{}' are {}",
+        filenames.0, filenames.1, result
+    );
+
+    if task.show_diff && !result.identical {
+        println!("{}", result.details());
     }
 }
 
@@ -80,35 +90,158 @@ fn main() {
 ///
 /// ## Returns
 ///
-/// Возвращает при удачной обработке число `u64` — количество несовпадающих структур (от 0 и более).
-/// При ошибках [`ParseError`].
-fn execute_compare_task(comparer_task: &ComparerTask) -> Result<u64, ParseError> {
+/// Возвращает при удачной обработке [`CompareResult`] с количеством несовпадающих структур
+/// и перечнем расхождений. При ошибках [`ParseError`].
+fn execute_compare_task(comparer_task: &ComparerTask) -> Result<CompareResult, ParseError> {
     let mut file1 = open_file(&comparer_task.first_file)?;
     let mut file2 = open_file(&comparer_task.second_file)?;
 
-    let left_side = comparer_task
+    let mut left_side = comparer_task
         .first_format
         .to_parsers_fmt()
         .to_transaction(&mut file1)?;
 
-    let right_side = comparer_task
+    let mut right_side = comparer_task
         .second_format
         .to_parsers_fmt()
         .to_transaction(&mut file2)?;
 
-    Ok(compare_sides(&left_side, &right_side))
+    if let Some(key) = comparer_task.sort_by {
+        sort_transactions(&mut left_side, key, SortOrder::Ascending);
+        sort_transactions(&mut right_side, key, SortOrder::Ascending);
+    }
+
+    Ok(CompareResult::from_sides_with(
+        &left_side,
+        &right_side,
+        &comparer_task.options,
+    ))
 }
 
-fn compare_sides(left: &[YPBankTransaction], right: &[YPBankTransaction]) -> u64 {
-    let length = left.len().min(right.len());
-    let counter = left
-        .iter()
-        .zip(right.iter())
-        .take(length)
-        .filter(|(l, r)| l != r)
-        .count() as u64;
+/// Потоковое сравнение файлов без построения наборов целиком: только число расхождений.
+///
+/// См. [`compare_streams`].
+fn execute_count_only(comparer_task: &ComparerTask) -> Result<u64, ParseError> {
+    let left = transaction_stream(&comparer_task.first_file, comparer_task.first_format)?;
+    let right = transaction_stream(&comparer_task.second_file, comparer_task.second_format)?;
+
+    compare_streams(left, right)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::FileFormat;
+    use crate::result::CompareOptions;
+    use parser::analytics::SortKey;
+    use parser::diff::RecordDiff;
+    use std::fs;
+
+    const CSV_DATA: &str = "TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION\n\
+                            1,DEPOSIT,0,1001,500,1633046400,SUCCESS,\"Salary\"\n\
+                            2,TRANSFER,1001,1002,200,1633046401,PENDING,\"Rent\"\n";
+
+    const TXT_DATA: &str = "# Record 1 (DEPOSIT)\n\
+                            TX_ID: 1\nTX_TYPE: DEPOSIT\nFROM_USER_ID: 0\nTO_USER_ID: 1001\n\
+                            AMOUNT: 500\nTIMESTAMP: 1633046400\nSTATUS: SUCCESS\n\
+                            DESCRIPTION: \"Salary\"\n";
+
+    #[test]
+    fn test_execute_compare_task_result() {
+        // Arrange
+        let dir = std::env::temp_dir();
+        let first_file = dir.join(format!("cli_comparer_{}_left.csv", std::process::id()));
+        let second_file = dir.join(format!("cli_comparer_{}_right.txt", std::process::id()));
+        fs::write(&first_file, CSV_DATA).unwrap();
+        fs::write(&second_file, TXT_DATA).unwrap();
+        let task = ComparerTask {
+            first_file: first_file.clone(),
+            second_file: second_file.clone(),
+            first_format: FileFormat::Csv,
+            second_format: FileFormat::Txt,
+            sort_by: None,
+            options: CompareOptions::default(),
+            show_diff: false,
+            count_only: false,
+        };
+
+        // Act
+        let result = execute_compare_task(&task);
+        fs::remove_file(first_file).unwrap();
+        fs::remove_file(second_file).unwrap();
 
-    let len_different = left.len().abs_diff(right.len()) as u64;
+        // Assert
+        let result = result.unwrap();
+        assert!(!result.identical);
+        assert_eq!(result.mismatched, 1);
+        assert_eq!(result.left_len, 2);
+        assert_eq!(result.right_len, 1);
+        assert!(matches!(
+            result.diffs.as_slice(),
+            [RecordDiff::Missing { index: 1, .. }]
+        ));
+    }
+
+    #[test]
+    fn test_execute_compare_task_sort_by_tx_id() {
+        // Arrange
+        let reversed = "TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION\n\
+                        2,TRANSFER,1001,1002,200,1633046401,PENDING,\"Rent\"\n\
+                        1,DEPOSIT,0,1001,500,1633046400,SUCCESS,\"Salary\"\n";
+        let dir = std::env::temp_dir();
+        let first_file = dir.join(format!("cli_comparer_{}_sorted.csv", std::process::id()));
+        let second_file = dir.join(format!("cli_comparer_{}_reversed.csv", std::process::id()));
+        fs::write(&first_file, CSV_DATA).unwrap();
+        fs::write(&second_file, reversed).unwrap();
+        let mut task = ComparerTask {
+            first_file: first_file.clone(),
+            second_file: second_file.clone(),
+            first_format: FileFormat::Csv,
+            second_format: FileFormat::Csv,
+            sort_by: None,
+            options: CompareOptions::default(),
+            show_diff: false,
+            count_only: false,
+        };
+
+        // Act
+        let unsorted = execute_compare_task(&task);
+        task.sort_by = Some(SortKey::TxId);
+        let sorted = execute_compare_task(&task);
+        fs::remove_file(first_file).unwrap();
+        fs::remove_file(second_file).unwrap();
+
+        // Assert
+        assert_eq!(unsorted.unwrap().mismatched, 2);
+        assert!(sorted.unwrap().identical);
+    }
+
+    #[test]
+    fn test_execute_count_only_matches_full_compare() {
+        // Arrange
+        let dir = std::env::temp_dir();
+        let first_file = dir.join(format!("cli_comparer_{}_count.csv", std::process::id()));
+        let second_file = dir.join(format!("cli_comparer_{}_count.txt", std::process::id()));
+        fs::write(&first_file, CSV_DATA).unwrap();
+        fs::write(&second_file, TXT_DATA).unwrap();
+        let task = ComparerTask {
+            first_file: first_file.clone(),
+            second_file: second_file.clone(),
+            first_format: FileFormat::Csv,
+            second_format: FileFormat::Txt,
+            sort_by: None,
+            options: CompareOptions::default(),
+            show_diff: false,
+            count_only: true,
+        };
 
-    counter + len_different
+        // Act
+        let counted = execute_count_only(&task);
+        let full = execute_compare_task(&task);
+        fs::remove_file(first_file).unwrap();
+        fs::remove_file(second_file).unwrap();
+
+        // Assert
+        assert_eq!(counted.unwrap(), full.unwrap().mismatched);
+    }
 }
@@ -0,0 +1,763 @@
+//! Структурированный результат сравнения двух наборов транзакций.
+
+use crate::cli::DiffAlgo;
+use parser::diff::{RecordDiff, changed_fields, diff_records, record_fields};
+use parser::errors::ParseError;
+use parser::models::YPBankTransaction;
+use parser::transactions_hash;
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+
+/// Сколько расхождений выводится в текстовом представлении результата.
+const DISPLAY_DIFFS: usize = 10;
+
+/// Наибольший размер таблицы [`diff_lcs`] в ячейках (`u32`, около 64 МиБ). Изменившаяся
+/// середина большего размера сравнивается попарно по позиции.
+const LCS_MAX_CELLS: usize = 1 << 24;
+
+/// Результат сравнения двух наборов транзакций.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompareResult {
+    /// Наборы совпадают полностью.
+    pub identical: bool,
+    /// Количество несовпадающих записей с учётом разницы в длине наборов.
+    pub mismatched: u64,
+    /// Количество записей в первом наборе.
+    pub left_len: usize,
+    /// Количество записей во втором наборе.
+    pub right_len: usize,
+    /// Расхождения по позициям, см. [`diff_records`].
+    ///
+    /// При сравнении [`DiffAlgo::Lcs`] `index` у [`RecordDiff::Missing`] и
+    /// [`RecordDiff::Changed`] — позиция в первом наборе, у [`RecordDiff::Unexpected`] —
+    /// во втором.
+    pub diffs: Vec<RecordDiff>,
+}
+
+/// Настройки сравнения наборов транзакций.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CompareOptions {
+    /// Служебный идентификатор пользователя `0` (`FROM_USER_ID`, `TO_USER_ID`) совпадает
+    /// с любым значением другой стороны. Полезно, если один из источников не хранит
+    /// отправителя или получателя.
+    pub zero_user_wildcard: bool,
+    /// Алгоритм сопоставления записей.
+    pub diff_algo: DiffAlgo,
+    /// Порядок записей не учитывается: наборы сравниваются как мультимножества, см.
+    /// [`diff_unordered`]. Алгоритм `diff_algo` и `zero_user_wildcard` при этом не применяются.
+    pub unordered: bool,
+}
+
+impl CompareResult {
+    /// Сравнивает наборы с учётом `options`: попарно по позиции, для [`DiffAlgo::Lcs`] —
+    /// минимальным набором вставок, удалений и замен, а при `unordered` — без учёта порядка.
+    ///
+    /// Если наборы совпадают по длине и хешу содержимого, подробное сравнение не выполняется.
+    pub fn from_sides_with(
+        left: &[YPBankTransaction],
+        right: &[YPBankTransaction],
+        options: &CompareOptions,
+    ) -> Self {
+        let diffs = if is_identical_by_hash(left, right) {
+            Vec::new()
+        } else if options.unordered {
+            diff_unordered(left, right)
+        } else if options.diff_algo == DiffAlgo::Lcs {
+            diff_lcs(left, right, options.zero_user_wildcard)
+        } else if options.zero_user_wildcard {
+            diff_with_wildcards(left, right)
+        } else {
+            diff_records(left, right)
+        };
+
+        Self {
+            identical: diffs.is_empty(),
+            mismatched: diffs.len() as u64,
+            left_len: left.len(),
+            right_len: right.len(),
+            diffs,
+        }
+    }
+
+    /// Подробный отчёт обо всех расхождениях.
+    ///
+    /// Для изменённых записей перечисляются отличающиеся поля со значениями обеих сторон,
+    /// для отсутствующих и лишних — все поля записи.
+    pub fn details(&self) -> String {
+        let mut lines = Vec::new();
+        for diff in &self.diffs {
+            match diff {
+                RecordDiff::Changed {
+                    index,
+                    expected,
+                    actual,
+                } => {
+                    lines.push(format!("#{index}: changed"));
+                    lines.extend(
+                        changed_fields(expected, actual)
+                            .into_iter()
+                            .map(|(field, left, right)| {
+                                format!("    {field}: {left:?} -> {right:?}")
+                            }),
+                    );
+                }
+                RecordDiff::Missing { index, expected } => {
+                    lines.push(format!("#{index}: only in the first file"));
+                    lines.push(format!("    {}", format_record(expected)));
+                }
+                RecordDiff::Unexpected { index, actual } => {
+                    lines.push(format!("#{index}: only in the second file"));
+                    lines.push(format!("    {}", format_record(actual)));
+                }
+            }
+        }
+
+        lines.join("\n")
+    }
+}
+
+/// Запись одной строкой `FIELD=value` через запятую.
+fn format_record(record: &YPBankTransaction) -> String {
+    record_fields(record)
+        .iter()
+        .map(|(field, value)| format!("{field}={value:?}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl Display for CompareResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.identical {
+            return write!(f, "IDENTICAL");
+        }
+
+        writeln!(f, "NOT IDENTICAL")?;
+        writeln!(
+            f,
+            "Records: {} in the first file, {} in the second",
+            self.left_len, self.right_len
+        )?;
+        write!(f, "Number of mismatched elements: {}", self.mismatched)?;
+
+        for diff in self.diffs.iter().take(DISPLAY_DIFFS) {
+            match diff {
+                RecordDiff::Changed { index, .. } => write!(f, "\n  #{index}: changed")?,
+                RecordDiff::Missing { index, .. } => {
+                    write!(f, "\n  #{index}: missing in the second file")?
+                }
+                RecordDiff::Unexpected { index, .. } => {
+                    write!(f, "\n  #{index}: missing in the first file")?
+                }
+            }
+        }
+        if self.diffs.len() > DISPLAY_DIFFS {
+            write!(f, "\n  ... and {} more", self.diffs.len() - DISPLAY_DIFFS)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Сравнивает два потока записей попарно по позиции, не загружая их в память целиком.
+///
+/// Возвращает количество несовпадающих записей с учётом разницы в длине потоков — то же, что
+/// [`CompareResult::mismatched`] при позиционном сравнении. Записи сравниваются как в
+/// [`diff_records`]. Первая ошибка разбора любой из сторон прерывает сравнение и возвращается.
+pub fn compare_streams<I, J>(left: I, right: J) -> Result<u64, ParseError>
+where
+    I: IntoIterator<Item = Result<YPBankTransaction, ParseError>>,
+    J: IntoIterator<Item = Result<YPBankTransaction, ParseError>>,
+{
+    let mut left = left.into_iter();
+    let mut right = right.into_iter();
+    let mut mismatched = 0;
+
+    loop {
+        match (left.next().transpose()?, right.next().transpose()?) {
+            (Some(l), Some(r)) => {
+                if !records_match(&l, &r, false) {
+                    mismatched += 1;
+                }
+            }
+            (Some(_), None) | (None, Some(_)) => mismatched += 1,
+            (None, None) => return Ok(mismatched),
+        }
+    }
+}
+
+/// Сравнение, в котором нулевые идентификаторы пользователей совпадают с любым значением.
+///
+/// Перед сравнением в паре записей нулевой идентификатор одной стороны заменяется значением
+/// другой. В расхождениях возвращаются исходные записи.
+fn diff_with_wildcards(left: &[YPBankTransaction], right: &[YPBankTransaction]) -> Vec<RecordDiff> {
+    let mut left_matched = left.to_vec();
+    let mut right_matched = right.to_vec();
+    for (l, r) in left_matched.iter_mut().zip(right_matched.iter_mut()) {
+        match_wildcard(&mut l.from_user_id, &mut r.from_user_id);
+        match_wildcard(&mut l.to_user_id, &mut r.to_user_id);
+    }
+
+    diff_records(&left_matched, &right_matched)
+        .into_iter()
+        .map(|diff| match diff {
+            RecordDiff::Changed { index, .. } => RecordDiff::Changed {
+                index,
+                expected: left[index].clone(),
+                actual: right[index].clone(),
+            },
+            other => other,
+        })
+        .collect()
+}
+
+/// Минимальный набор правок, превращающий `left` в `right` (наибольшая общая подпоследовательность).
+///
+/// Совпадающие начало и конец наборов отбрасываются заранее, поэтому таблица размером
+/// `n * m` строится только для изменившейся середины. Удаление, за которым может следовать
+/// вставка в той же позиции, сообщается одной заменой [`RecordDiff::Changed`].
+///
+/// Если таблица для середины превышает [`LCS_MAX_CELLS`], середина сравнивается попарно по
+/// позиции, см. [`diff_positional_range`].
+fn diff_lcs(
+    left: &[YPBankTransaction],
+    right: &[YPBankTransaction],
+    zero_user_wildcard: bool,
+) -> Vec<RecordDiff> {
+    diff_lcs_limited(left, right, zero_user_wildcard, LCS_MAX_CELLS)
+}
+
+/// [`diff_lcs`] с ограничением размера таблицы `max_cells`.
+fn diff_lcs_limited(
+    left: &[YPBankTransaction],
+    right: &[YPBankTransaction],
+    zero_user_wildcard: bool,
+    max_cells: usize,
+) -> Vec<RecordDiff> {
+    let same =
+        |l: &YPBankTransaction, r: &YPBankTransaction| records_match(l, r, zero_user_wildcard);
+
+    let prefix = left
+        .iter()
+        .zip(right)
+        .take_while(|(l, r)| same(l, r))
+        .count();
+    let suffix = left[prefix..]
+        .iter()
+        .rev()
+        .zip(right[prefix..].iter().rev())
+        .take_while(|(l, r)| same(l, r))
+        .count();
+    let left_mid = &left[prefix..left.len() - suffix];
+    let right_mid = &right[prefix..right.len() - suffix];
+    let (n, m) = (left_mid.len(), right_mid.len());
+
+    let cells = (n + 1)
+        .checked_mul(m + 1)
+        .filter(|&cells| cells <= max_cells);
+    if cells.is_none() {
+        return diff_positional_range(left_mid, right_mid, prefix, same);
+    }
+
+    // lcs[i * (m + 1) + j] — длина общей подпоследовательности left_mid[i..] и right_mid[j..].
+    let width = m + 1;
+    let mut lcs = vec![0u32; (n + 1) * width];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i * width + j] = if same(&left_mid[i], &right_mid[j]) {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut diffs = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        let current = lcs[i * width + j];
+        if i < n && j < m && same(&left_mid[i], &right_mid[j]) {
+            i += 1;
+            j += 1;
+        } else if i < n && j < m && lcs[(i + 1) * width + j + 1] == current {
+            diffs.push(RecordDiff::Changed {
+                index: prefix + i,
+                expected: left_mid[i].clone(),
+                actual: right_mid[j].clone(),
+            });
+            i += 1;
+            j += 1;
+        } else if i < n && (j == m || lcs[(i + 1) * width + j] >= lcs[i * width + j + 1]) {
+            diffs.push(RecordDiff::Missing {
+                index: prefix + i,
+                expected: left_mid[i].clone(),
+            });
+            i += 1;
+        } else {
+            diffs.push(RecordDiff::Unexpected {
+                index: prefix + j,
+                actual: right_mid[j].clone(),
+            });
+            j += 1;
+        }
+    }
+
+    diffs
+}
+
+/// Попарное сравнение по позиции фрагментов наборов, начинающихся с позиции `offset`.
+///
+/// Индексы расхождений отсчитываются от начала исходных наборов.
+fn diff_positional_range(
+    left: &[YPBankTransaction],
+    right: &[YPBankTransaction],
+    offset: usize,
+    same: impl Fn(&YPBankTransaction, &YPBankTransaction) -> bool,
+) -> Vec<RecordDiff> {
+    let mut diffs = Vec::new();
+    for index in 0..left.len().max(right.len()) {
+        match (left.get(index), right.get(index)) {
+            (Some(l), Some(r)) if same(l, r) => {}
+            (Some(l), Some(r)) => diffs.push(RecordDiff::Changed {
+                index: offset + index,
+                expected: l.clone(),
+                actual: r.clone(),
+            }),
+            (Some(l), None) => diffs.push(RecordDiff::Missing {
+                index: offset + index,
+                expected: l.clone(),
+            }),
+            (None, Some(r)) => diffs.push(RecordDiff::Unexpected {
+                index: offset + index,
+                actual: r.clone(),
+            }),
+            (None, None) => {}
+        }
+    }
+
+    diffs
+}
+
+/// Сравнение наборов как мультимножеств записей.
+///
+/// Позиции записей обеих сторон сортируются по содержимому (в каноническом виде, как
+/// в [`records_match`]), после чего отсортированные списки проходятся слиянием. Записи
+/// без пары сообщаются как [`RecordDiff::Missing`] с позицией в первом наборе и
+/// [`RecordDiff::Unexpected`] с позицией во втором, каждая группа по возрастанию позиции.
+fn diff_unordered(left: &[YPBankTransaction], right: &[YPBankTransaction]) -> Vec<RecordDiff> {
+    let sorted = |records: &[YPBankTransaction]| {
+        let mut indices: Vec<usize> = (0..records.len()).collect();
+        indices.sort_by(|&a, &b| canonical_key(&records[a]).cmp(&canonical_key(&records[b])));
+        indices
+    };
+    let (left_sorted, right_sorted) = (sorted(left), sorted(right));
+
+    let mut missing = Vec::new();
+    let mut unexpected = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < left_sorted.len() || j < right_sorted.len() {
+        let order = match (left_sorted.get(i), right_sorted.get(j)) {
+            (Some(&l), Some(&r)) => canonical_key(&left[l]).cmp(&canonical_key(&right[r])),
+            (Some(_), None) => Ordering::Less,
+            _ => Ordering::Greater,
+        };
+        match order {
+            Ordering::Less => {
+                missing.push(left_sorted[i]);
+                i += 1;
+            }
+            Ordering::Greater => {
+                unexpected.push(right_sorted[j]);
+                j += 1;
+            }
+            Ordering::Equal => {
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    missing.sort_unstable();
+    unexpected.sort_unstable();
+
+    missing
+        .into_iter()
+        .map(|index| RecordDiff::Missing {
+            index,
+            expected: left[index].clone(),
+        })
+        .chain(unexpected.into_iter().map(|index| RecordDiff::Unexpected {
+            index,
+            actual: right[index].clone(),
+        }))
+        .collect()
+}
+
+/// Ключ упорядочивания записи, в котором отсутствующее и пустое описание равнозначны.
+fn canonical_key(record: &YPBankTransaction) -> impl Ord + '_ {
+    (
+        record.tx_id,
+        &record.tx_type,
+        record.from_user_id,
+        record.to_user_id,
+        record.amount,
+        record.timestamp,
+        &record.status,
+        record.description.as_deref().unwrap_or_default(),
+    )
+}
+
+/// Сравнивает записи в том же каноническом виде, что и [`diff_records`]: отсутствующее
+/// и пустое описание равнозначны.
+fn records_match(
+    left: &YPBankTransaction,
+    right: &YPBankTransaction,
+    zero_user_wildcard: bool,
+) -> bool {
+    let users_match = |l: u64, r: u64| l == r || (zero_user_wildcard && (l == 0 || r == 0));
+
+    left.tx_id == right.tx_id
+        && left.tx_type == right.tx_type
+        && users_match(left.from_user_id, right.from_user_id)
+        && users_match(left.to_user_id, right.to_user_id)
+        && left.amount == right.amount
+        && left.timestamp == right.timestamp
+        && left.status == right.status
+        && left.description.as_deref().unwrap_or_default()
+            == right.description.as_deref().unwrap_or_default()
+}
+
+/// Заменяет нулевой идентификатор одной стороны значением другой.
+fn match_wildcard(left: &mut u64, right: &mut u64) {
+    if *left == 0 {
+        *left = *right;
+    } else if *right == 0 {
+        *right = *left;
+    }
+}
+
+/// Быстрая проверка идентичности наборов по длине и хешу содержимого.
+fn is_identical_by_hash(left: &[YPBankTransaction], right: &[YPBankTransaction]) -> bool {
+    left.len() == right.len() && transactions_hash(left) == transactions_hash(right)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parser::models::{TxStatus, TxType};
+    use parser::testing::transaction;
+
+    fn create_transactions(count: u64) -> Vec<YPBankTransaction> {
+        (0..count)
+            .map(|tx_id| YPBankTransaction {
+                timestamp: 1633046400 + tx_id,
+                description: Some(format!("Record number {tx_id}")),
+                ..transaction(tx_id, TxType::Transfer, -100)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_compare_identical_uses_fast_path() {
+        // Arrange
+        let left = create_transactions(100);
+        let right = left.clone();
+
+        // Act
+        let result = CompareResult::from_sides_with(&left, &right, &CompareOptions::default());
+
+        // Assert
+        assert!(is_identical_by_hash(&left, &right));
+        assert!(result.identical);
+        assert_eq!(result.mismatched, 0);
+        assert_eq!(result.to_string(), "IDENTICAL");
+    }
+
+    #[test]
+    fn test_compare_different_counts_mismatches() {
+        // Arrange
+        let left = create_transactions(10);
+        let mut right = create_transactions(12);
+        right[3].status = TxStatus::Failure;
+        right[7].amount = -200;
+
+        // Act
+        let result = CompareResult::from_sides_with(&left, &right, &CompareOptions::default());
+
+        // Assert
+        assert!(!is_identical_by_hash(&left, &right));
+        assert!(!result.identical);
+        assert_eq!(result.mismatched, 4);
+        assert!(
+            result
+                .to_string()
+                .contains("Number of mismatched elements: 4")
+        );
+    }
+
+    #[test]
+    fn test_details_lists_fields_and_extra_records() {
+        // Arrange
+        let left = create_transactions(2);
+        let mut right = create_transactions(3);
+        right[1].amount = -200;
+        right[1].status = TxStatus::Failure;
+
+        // Act
+        let result = CompareResult::from_sides_with(&left, &right, &CompareOptions::default());
+
+        // Assert
+        assert_eq!(
+            result.details(),
+            "#1: changed\n\
+             \x20   AMOUNT: \"-100\" -> \"-200\"\n\
+             \x20   STATUS: \"SUCCESS\" -> \"FAILURE\"\n\
+             #2: only in the second file\n\
+             \x20   TX_ID=\"2\", TX_TYPE=\"TRANSFER\", FROM_USER_ID=\"1001\", TO_USER_ID=\"1002\", \
+             AMOUNT=\"-100\", TIMESTAMP=\"1633046402\", STATUS=\"SUCCESS\", \
+             DESCRIPTION=\"Record number 2\""
+        );
+    }
+
+    #[test]
+    fn test_details_missing_record() {
+        // Arrange
+        let left = create_transactions(3);
+        let right = create_transactions(2);
+
+        // Act
+        let result = CompareResult::from_sides_with(&left, &right, &CompareOptions::default());
+
+        // Assert
+        let details = result.details();
+        assert!(details.starts_with("#2: only in the first file\n    TX_ID=\"2\""));
+        assert_eq!(details.lines().count(), 2);
+    }
+
+    #[test]
+    fn test_compare_zero_user_wildcard() {
+        // Arrange
+        let deposit = YPBankTransaction {
+            tx_type: TxType::Deposit,
+            from_user_id: 0,
+            ..create_transactions(1).remove(0)
+        };
+        let left = vec![deposit.clone()];
+        let right = vec![YPBankTransaction {
+            from_user_id: 1001,
+            ..deposit
+        }];
+        let options = CompareOptions {
+            zero_user_wildcard: true,
+            ..CompareOptions::default()
+        };
+
+        // Act
+        let strict = CompareResult::from_sides_with(&left, &right, &CompareOptions::default());
+        let wildcard = CompareResult::from_sides_with(&left, &right, &options);
+
+        // Assert
+        assert!(!strict.identical);
+        assert!(wildcard.identical);
+        assert_eq!(wildcard.to_string(), "IDENTICAL");
+    }
+
+    #[test]
+    fn test_compare_zero_user_wildcard_keeps_other_mismatches() {
+        // Arrange
+        let left = create_transactions(3);
+        let mut right = create_transactions(4);
+        right[0].from_user_id = 0;
+        right[1].to_user_id = 0;
+        right[1].amount = -500;
+        let options = CompareOptions {
+            zero_user_wildcard: true,
+            ..CompareOptions::default()
+        };
+
+        // Act
+        let result = CompareResult::from_sides_with(&left, &right, &options);
+
+        // Assert
+        assert_eq!(result.mismatched, 2);
+        assert!(matches!(
+            &result.diffs[0],
+            RecordDiff::Changed { index: 1, actual, .. } if actual.to_user_id == 0
+        ));
+        assert!(matches!(
+            result.diffs[1],
+            RecordDiff::Unexpected { index: 3, .. }
+        ));
+    }
+
+    #[test]
+    fn test_compare_unordered_shuffled_is_identical() {
+        // Arrange
+        let mut left = create_transactions(10);
+        left[3].description = Some(String::new());
+        let mut right = left.clone();
+        right[3].description = None;
+        right.reverse();
+        right.swap(2, 7);
+        let options = CompareOptions {
+            unordered: true,
+            ..CompareOptions::default()
+        };
+
+        // Act
+        let positional = CompareResult::from_sides_with(&left, &right, &CompareOptions::default());
+        let unordered = CompareResult::from_sides_with(&left, &right, &options);
+
+        // Assert
+        assert!(!positional.identical);
+        assert!(unordered.identical);
+        assert_eq!(unordered.mismatched, 0);
+    }
+
+    #[test]
+    fn test_compare_unordered_counts_unmatched_records() {
+        // Arrange
+        let left = create_transactions(5);
+        let mut right = left.clone();
+        right.rotate_left(2);
+        right[0].amount = -500;
+        right.push(left[1].clone());
+        let options = CompareOptions {
+            unordered: true,
+            ..CompareOptions::default()
+        };
+
+        // Act
+        let result = CompareResult::from_sides_with(&left, &right, &options);
+
+        // Assert
+        assert_eq!(result.mismatched, 3);
+        assert!(matches!(
+            result.diffs.as_slice(),
+            [
+                RecordDiff::Missing { index: 2, .. },
+                RecordDiff::Unexpected { index: 0, .. },
+                RecordDiff::Unexpected { index: 5, .. },
+            ]
+        ));
+    }
+
+    #[test]
+    fn test_compare_lcs_reports_single_insertion() {
+        // Arrange
+        let left = create_transactions(20);
+        let mut right = left.clone();
+        right.insert(0, create_transactions(100).remove(99));
+        let options = CompareOptions {
+            diff_algo: DiffAlgo::Lcs,
+            ..CompareOptions::default()
+        };
+
+        // Act
+        let positional = CompareResult::from_sides_with(&left, &right, &CompareOptions::default());
+        let lcs = CompareResult::from_sides_with(&left, &right, &options);
+
+        // Assert
+        assert_eq!(positional.mismatched, 21);
+        assert_eq!(lcs.mismatched, 1);
+        assert!(matches!(
+            &lcs.diffs[0],
+            RecordDiff::Unexpected { index: 0, actual } if actual.tx_id == 99
+        ));
+    }
+
+    #[test]
+    fn test_compare_lcs_changes_and_deletions() {
+        // Arrange
+        let left = create_transactions(10);
+        let mut right = left.clone();
+        right[2].amount = -500;
+        right.remove(6);
+        let options = CompareOptions {
+            diff_algo: DiffAlgo::Lcs,
+            ..CompareOptions::default()
+        };
+
+        // Act
+        let result = CompareResult::from_sides_with(&left, &right, &options);
+
+        // Assert
+        assert_eq!(result.mismatched, 2);
+        assert!(matches!(
+            result.diffs[0],
+            RecordDiff::Changed { index: 2, .. }
+        ));
+        assert!(matches!(
+            result.diffs[1],
+            RecordDiff::Missing { index: 6, .. }
+        ));
+    }
+
+    #[test]
+    fn test_diff_lcs_falls_back_to_positional_above_limit() {
+        // Arrange
+        let left = create_transactions(10);
+        let mut right = left.clone();
+        right[1].amount = -500;
+        right.insert(5, create_transactions(100).remove(99));
+        right[9].status = TxStatus::Failure;
+
+        // Act
+        let lcs = diff_lcs_limited(&left, &right, false, usize::MAX);
+        let fallback = diff_lcs_limited(&left, &right, false, 16);
+
+        // Assert
+        assert_eq!(lcs.len(), 3);
+        assert_eq!(fallback.len(), 6);
+        assert!(matches!(fallback[0], RecordDiff::Changed { index: 1, .. }));
+        assert!(matches!(
+            fallback.last(),
+            Some(RecordDiff::Unexpected { index: 9, .. })
+        ));
+    }
+
+    #[test]
+    fn test_compare_streams_counts_mismatches() {
+        // Arrange
+        let left = create_transactions(10);
+        let mut right = create_transactions(12);
+        right[3].status = TxStatus::Failure;
+        right[7].amount = -200;
+
+        // Act
+        let same = compare_streams(
+            left.clone().into_iter().map(Ok),
+            left.clone().into_iter().map(Ok),
+        );
+        let longer_right = compare_streams(
+            left.clone().into_iter().map(Ok),
+            right.clone().into_iter().map(Ok),
+        );
+        let longer_left = compare_streams(
+            right.into_iter().map(Ok),
+            left.iter().take(5).cloned().map(Ok),
+        );
+
+        // Assert
+        assert_eq!(same.unwrap(), 0);
+        assert_eq!(longer_right.unwrap(), 4);
+        assert_eq!(longer_left.unwrap(), 8);
+    }
+
+    #[test]
+    fn test_compare_streams_propagates_first_error() {
+        // Arrange
+        let left = create_transactions(3);
+        let mut right: Vec<Result<YPBankTransaction, ParseError>> =
+            left.iter().cloned().map(Ok).collect();
+        right[1] = Err(ParseError::parse_err("Ошибка разбора", 2, 0));
+        right.push(Err(ParseError::empty_data()));
+
+        // Act
+        let result = compare_streams(left.into_iter().map(Ok), right);
+
+        // Assert
+        assert!(matches!(
+            result,
+            Err(ParseError::ParseError { line: 2, .. })
+        ));
+    }
+}
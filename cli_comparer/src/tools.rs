@@ -1,9 +1,14 @@
 //! Вспомогательный модуль утилит, персональных для приложения.
 
+use crate::cli::FileFormat;
 use parser::errors::ParseError;
+use parser::models::{YPBankBinFormat, YPBankCsvFormat, YPBankTransaction};
 use std::fs::File;
 use std::path::PathBuf;
 
+/// Поток транзакций, читаемых из файла по одной.
+pub type TransactionStream = Box<dyn Iterator<Item = Result<YPBankTransaction, ParseError>>>;
+
 /// Обёртка для метода [`File::open`], которая открывает файл и возвращает объект [`File`].
 ///
 /// При ошибках возвращает [`ParseError`].
@@ -12,3 +17,32 @@ pub fn open_file(filepath: &PathBuf) -> Result<File, ParseError> {
         ParseError::io_error(err, format!("Failure to open file: {}", filepath.display()))
     })
 }
+
+/// Открывает файл и возвращает поток его транзакций.
+///
+/// Форматы `csv` и `bin` читаются по одной записи. Для `txt` потокового чтения нет, поэтому
+/// файл разбирается целиком, а поток проходит по уже прочитанным записям.
+pub fn transaction_stream(
+    filepath: &PathBuf,
+    format: FileFormat,
+) -> Result<TransactionStream, ParseError> {
+    let mut file = open_file(filepath)?;
+
+    Ok(match format {
+        FileFormat::Csv => Box::new(
+            YPBankCsvFormat::read_iter(file)
+                .map(|record| record.and_then(YPBankTransaction::try_from)),
+        ),
+        FileFormat::Bin => Box::new(
+            YPBankBinFormat::read_iter(file)
+                .map(|record| record.and_then(YPBankTransaction::try_from)),
+        ),
+        FileFormat::Txt => Box::new(
+            format
+                .to_parsers_fmt()
+                .to_transaction(&mut file)?
+                .into_iter()
+                .map(Ok),
+        ),
+    })
+}
@@ -2,6 +2,8 @@
 
 use clap::{Parser, ValueEnum};
 use parser::YPFormatSupported;
+use parser::models::{TxStatus, YPBankTransaction};
+use regex::Regex;
 use std::env;
 use std::ffi::OsStr;
 use std::fmt::Display;
@@ -11,13 +13,15 @@ use std::process::exit;
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
 struct Args {
-    /// The path to the data file.
-    #[clap(short, value_name = "INPUT_FILE")]
-    input_file: PathBuf,
+    /// The path to the data file. May be repeated: records of all input files are concatenated
+    /// in the given order into one output file.
+    #[clap(short, value_name = "INPUT_FILE", required = true)]
+    input_file: Vec<PathBuf>,
 
-    /// The format of the source file (from the supported types).
-    #[clap(long, value_enum)]
-    input_format: FileFormat,
+    /// The format of the source file (from the supported types). Repeat it once per input file,
+    /// in the same order, or pass it once to use the same format for all input files.
+    #[clap(long, value_enum, required = true)]
+    input_format: Vec<FileFormat>,
 
     /// The target format of the data file.
     #[clap(long, value_enum)]
@@ -36,6 +40,17 @@ struct Args {
     /// format is not allowed. Otherwise, only a console warning will be issued.
     #[clap(short = 's', long = "strict-target-ext")]
     strict_target_ext: bool,
+
+    /// Replace every match of the regular expression PATTERN in transaction descriptions with
+    /// REPLACEMENT during conversion (e.g. to redact card numbers). Records are scrubbed as they
+    /// stream through; for the bin output they are collected in memory first, because the bin
+    /// file header declares the number of records.
+    #[clap(long = "scrub-description", num_args = 2, value_names = ["PATTERN", "REPLACEMENT"])]
+    scrub_description: Option<Vec<String>>,
+
+    /// Keep only transactions with the given STATUS (SUCCESS, FAILURE or PENDING).
+    #[clap(long = "filter-status", value_name = "STATUS", value_parser = parse_status)]
+    filter_status: Option<TxStatus>,
 }
 
 #[derive(Copy, Clone, Debug, ValueEnum)]
@@ -71,14 +86,55 @@ impl FileFormat {
 
 /// Структура данных задачи для конвертации.
 pub struct ConvertTask {
-    /// Путь к исходному файлу.
-    pub input_file: PathBuf,
+    /// Исходные файлы и форматы их данных. Записи объединяются в указанном порядке.
+    pub inputs: Vec<(PathBuf, FileFormat)>,
     /// Путь к целевому файлу.
     pub output_file: PathBuf,
-    /// Формат данных в исходном файле (из предустановленных).
-    pub input_format: FileFormat,
     /// Формат данных в целевом файле (из предустановленных).
     pub output_format: FileFormat,
+    /// Правило замены в описаниях транзакций, если задано.
+    pub scrub: Option<DescriptionScrub>,
+    /// Записываются только транзакции с этим статусом, если задан.
+    pub filter_status: Option<TxStatus>,
+}
+
+/// Правило замены фрагментов описаний транзакций при конвертации.
+#[derive(Debug)]
+pub struct DescriptionScrub {
+    /// Регулярное выражение для поиска фрагментов.
+    pattern: Regex,
+    /// Строка замены (поддерживает ссылки на группы, например `$1`).
+    replacement: String,
+}
+
+impl DescriptionScrub {
+    /// Создаёт правило замены. Возвращает текст ошибки, если регулярное выражение некорректно.
+    pub fn new(pattern: &str, replacement: impl Into<String>) -> Result<Self, String> {
+        let pattern = Regex::new(pattern)
+            .map_err(|err| format!("Invalid `--scrub-description` pattern: {err}"))?;
+
+        Ok(Self {
+            pattern,
+            replacement: replacement.into(),
+        })
+    }
+
+    /// Применяет замену к описанию транзакции.
+    pub fn apply(&self, transaction: &mut YPBankTransaction) {
+        if let Some(description) = &transaction.description {
+            let scrubbed = self
+                .pattern
+                .replace_all(description, self.replacement.as_str());
+            transaction.description = Some(scrubbed.into_owned());
+        }
+    }
+}
+
+/// Разбор статуса транзакции для `--filter-status` (в любом регистре).
+fn parse_status(value: &str) -> Result<TxStatus, String> {
+    value
+        .parse()
+        .map_err(|_| format!("unknown status `{value}`, expected SUCCESS, FAILURE or PENDING"))
 }
 
 /// Получить от пользователя задание на конвертацию.
@@ -88,11 +144,23 @@ pub struct ConvertTask {
 pub fn cli_parse() -> ConvertTask {
     let args = Args::parse();
 
+    // Некорректное выражение должно остановить работу до начала обработки данных.
+    let scrub = match args.scrub_description.as_deref() {
+        Some([pattern, replacement]) => {
+            Some(DescriptionScrub::new(pattern, replacement).unwrap_or_else(|err| exit_err(&err)))
+        }
+        _ => None,
+    };
+
+    let inputs =
+        pair_inputs(args.input_file, args.input_format).unwrap_or_else(|err| exit_err(&err));
+
     let convert_task = ConvertTask {
-        input_file: args.input_file,
-        input_format: args.input_format,
+        inputs,
         output_file: args.output_file,
         output_format: args.output_format,
+        scrub,
+        filter_status: args.filter_status,
     };
 
     if let Err(err) = validate_paths(&convert_task, args.no_overwrite, args.strict_target_ext) {
@@ -102,6 +170,25 @@ pub fn cli_parse() -> ConvertTask {
     convert_task
 }
 
+/// Сопоставить исходные файлы с их форматами.
+///
+/// Форматов должно быть столько же, сколько файлов, либо один — тогда он применяется ко всем.
+fn pair_inputs(
+    files: Vec<PathBuf>,
+    formats: Vec<FileFormat>,
+) -> Result<Vec<(PathBuf, FileFormat)>, String> {
+    match formats.as_slice() {
+        [format] => Ok(files.into_iter().map(|file| (file, *format)).collect()),
+        _ if formats.len() == files.len() => Ok(files.into_iter().zip(formats).collect()),
+        _ => Err(format!(
+            "Got {} input files but {} input formats: pass `--input-format` once for all files \
+            or once per file.",
+            files.len(),
+            formats.len()
+        )),
+    }
+}
+
 /// Валидировать предоставленные пути к файлам, в том числе на соблюдение условий (например,
 /// запрет/разрешение) перезаписи.
 ///
@@ -117,12 +204,17 @@ fn validate_paths(
     no_overwrite: bool,
     strict_ext: bool,
 ) -> Result<(), String> {
-    if convert_task.input_file == convert_task.output_file {
-        return Err("The input file and the output file cannot be the same path.".to_string());
-    }
+    for (input_file, _) in &convert_task.inputs {
+        if *input_file == convert_task.output_file {
+            return Err("The input file and the output file cannot be the same path.".to_string());
+        }
 
-    if !convert_task.input_file.is_file() {
-        return Err("The input file was not found or is not a valid file.".to_string());
+        if !input_file.is_file() {
+            return Err(format!(
+                "The input file was not found or is not a valid file: {}",
+                input_file.display()
+            ));
+        }
     }
 
     if convert_task.output_file.is_dir() {
@@ -182,3 +274,71 @@ fn exit_err(message: &str) -> ! {
     eprintln!("Error: {}", message);
     exit(1);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parser::models::TxType;
+    use parser::testing::transaction;
+
+    #[test]
+    fn test_scrub_card_numbers() {
+        // Arrange
+        let scrub = DescriptionScrub::new(r"\d{16}", "****").unwrap();
+        let mut tx = YPBankTransaction {
+            description: Some("Paid by 4111111111111111, ref 42".to_string()),
+            ..transaction(1, TxType::Withdrawal, -500)
+        };
+        let mut empty = transaction(2, TxType::Withdrawal, -500);
+
+        // Act
+        scrub.apply(&mut tx);
+        scrub.apply(&mut empty);
+
+        // Assert
+        assert_eq!(tx.description.as_deref(), Some("Paid by ****, ref 42"));
+        assert_eq!(empty.description, None);
+    }
+
+    #[test]
+    fn test_pair_inputs_single_format_for_all() {
+        // Arrange
+        let files = vec![PathBuf::from("a.csv"), PathBuf::from("b.csv")];
+
+        // Act
+        let inputs = pair_inputs(files, vec![FileFormat::Csv]).unwrap();
+
+        // Assert
+        assert_eq!(inputs.len(), 2);
+        assert!(
+            inputs
+                .iter()
+                .all(|(_, format)| matches!(format, FileFormat::Csv))
+        );
+    }
+
+    #[test]
+    fn test_pair_inputs_count_mismatch() {
+        // Arrange
+        let files = vec![
+            PathBuf::from("a.csv"),
+            PathBuf::from("b.bin"),
+            PathBuf::from("c.txt"),
+        ];
+
+        // Act
+        let result = pair_inputs(files, vec![FileFormat::Csv, FileFormat::Bin]);
+
+        // Assert
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_scrub_invalid_pattern() {
+        // Act
+        let result = DescriptionScrub::new(r"(\d{16}", "****");
+
+        // Assert
+        assert!(result.is_err());
+    }
+}
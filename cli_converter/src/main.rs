@@ -3,7 +3,7 @@
 //! Консольное приложение для конвертации данных между форматами `CSV`, `BIN` и `TXT`,
 //! использующее возможности библиотеки [`parser`].
 //!
-//! Программа принимает входной файл, его формат, целевой формат и путь для сохранения.
+//! Программа принимает входные файлы (несколько файлов объединяются в один), их форматы, целевой формат и путь для сохранения.
 //! Поддерживаются параметры: перезапись выходного файла, проверка расширения и контроль
 //! соответствия форматов.
 //!
@@ -34,10 +34,12 @@
 //!    ```
 #![warn(missing_docs)]
 
-use cli::{ConvertTask, cli_parse};
+use cli::{ConvertTask, FileFormat, cli_parse};
+use parser::analytics::by_status;
 use parser::errors::ParseError;
 use parser::models::YPBankTransaction;
 use std::fs::File;
+use std::io::Write;
 use std::process::exit;
 
 mod cli;
@@ -57,36 +59,164 @@ fn main() {
 impl ConvertTask {
     /// Конвертировать данные из одного формата в другой.
     ///
+    /// Записи всех исходных файлов читаются потоком, фильтруются и очищаются по одной и сразу
+    /// пишутся в целевой файл. Исключение — бинарный формат: число записей объявляется
+    /// в заголовке файла, поэтому записи накапливаются перед записью.
+    ///
     /// Структура наполняется и проверяется при формировании.
     fn convert(&self) -> Result<(), ParseError> {
-        let read_data = self.read_with()?;
-        self.write_with(read_data)?;
-        Ok(())
-    }
-
-    /// Считать данные из исходного файла.
-    fn read_with(&self) -> Result<Vec<YPBankTransaction>, ParseError> {
-        let mut file = File::open(&self.input_file).map_err(|err| {
+        let inputs = self.open_inputs()?;
+        let mut file = File::create(&self.output_file).map_err(|err| {
             ParseError::io_error(
                 err,
-                format!("Failure to open file: {}", &self.input_file.display()),
+                format!("Failure to create file: {}", &self.output_file.display()),
             )
         })?;
 
-        self.input_format.to_parsers_fmt().to_transaction(&mut file)
+        let transactions = inputs
+            .into_iter()
+            .flat_map(|(file, format)| format.to_parsers_fmt().read_iter(file));
+        self.write_into(&mut file, transactions)
     }
 
-    /// Записать данные в целевой файл.
-    fn write_with(&self, data: Vec<YPBankTransaction>) -> Result<(), ParseError> {
-        let mut file = File::create(&self.output_file).map_err(|err| {
+    /// Открыть все исходные файлы до начала записи, чтобы ошибка открытия не оставила
+    /// неполный целевой файл.
+    fn open_inputs(&self) -> Result<Vec<(File, FileFormat)>, ParseError> {
+        self.inputs
+            .iter()
+            .map(|(input_file, input_format)| {
+                let file = File::open(input_file).map_err(|err| {
+                    ParseError::io_error(
+                        err,
+                        format!("Failure to open file: {}", input_file.display()),
+                    )
+                })?;
+                Ok((file, *input_format))
+            })
+            .collect()
+    }
+
+    /// Отфильтровать и очистить транзакции по одной, записать их в `writer` и явно сбросить
+    /// буферы.
+    ///
+    /// Ошибка сброса означает, что данные могли не попасть в файл, и возвращается вызывающему.
+    fn write_into<W: Write>(
+        &self,
+        writer: &mut W,
+        transactions: impl Iterator<Item = Result<YPBankTransaction, ParseError>>,
+    ) -> Result<(), ParseError> {
+        let keep = self.filter_status.clone().map(by_status);
+        let transactions = transactions
+            .filter(|tx| match (tx, &keep) {
+                (Ok(tx), Some(keep)) => keep(tx),
+                _ => true,
+            })
+            .map(|tx| {
+                let mut tx = tx?;
+                if let Some(scrub) = &self.scrub {
+                    scrub.apply(&mut tx);
+                }
+                Ok(tx)
+            });
+
+        self.output_format
+            .to_parsers_fmt()
+            .write_iter(writer, transactions)?;
+
+        writer.flush().map_err(|err| {
             ParseError::io_error(
                 err,
-                format!("Failure to create file: {}", &self.output_file.display()),
+                format!("Failure to flush file: {}", &self.output_file.display()),
             )
-        })?;
+        })
+    }
+}
 
-        self.output_format
-            .to_parsers_fmt()
-            .convert_transactions(&mut file, &data)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parser::models::{TxStatus, TxType};
+    use parser::testing::transaction;
+    use std::io;
+    use std::path::PathBuf;
+
+    /// Писатель, принимающий данные, но не способный их сбросить.
+    struct FailingFlush(Vec<u8>);
+
+    impl Write for FailingFlush {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Err(io::Error::other("disk full"))
+        }
+    }
+
+    #[test]
+    fn test_write_into_surfaces_flush_error() {
+        // Arrange
+        let task = ConvertTask {
+            inputs: vec![(PathBuf::from("input.csv"), FileFormat::Csv)],
+            output_file: PathBuf::from("output.txt"),
+            output_format: FileFormat::Txt,
+            scrub: None,
+            filter_status: None,
+        };
+        let data = vec![transaction(1, TxType::Deposit, 500)];
+        let mut writer = FailingFlush(Vec::new());
+
+        // Act
+        let result = task.write_into(&mut writer, data.into_iter().map(Ok));
+
+        // Assert
+        assert!(matches!(result, Err(ParseError::IOError { .. })));
+        assert_eq!(result.unwrap_err().io_kind(), Some(io::ErrorKind::Other));
+    }
+
+    #[test]
+    fn test_write_into_filters_and_scrubs_each_record() {
+        // Arrange
+        let task = ConvertTask {
+            inputs: vec![(PathBuf::from("input.csv"), FileFormat::Csv)],
+            output_file: PathBuf::from("output.csv"),
+            output_format: FileFormat::Csv,
+            scrub: Some(cli::DescriptionScrub::new(r"\d{16}", "****").unwrap()),
+            filter_status: Some(TxStatus::Success),
+        };
+        let description = Some("card 1234567812345678".to_string());
+        let data = [
+            YPBankTransaction {
+                description: description.clone(),
+                ..transaction(1, TxType::Deposit, 500)
+            },
+            YPBankTransaction {
+                status: TxStatus::Failure,
+                description: description.clone(),
+                ..transaction(2, TxType::Deposit, 500)
+            },
+            YPBankTransaction {
+                description,
+                ..transaction(3, TxType::Deposit, 500)
+            },
+        ];
+        let mut output = Vec::new();
+
+        // Act
+        task.write_into(&mut output, data.into_iter().map(Ok))
+            .unwrap();
+        let result = parser::YPFormatSupported::Csv
+            .to_transaction(&mut output.as_slice())
+            .unwrap();
+
+        // Assert
+        let ids: Vec<u64> = result.iter().map(|tx| tx.tx_id).collect();
+        assert_eq!(ids, [1, 3]);
+        assert!(
+            result
+                .iter()
+                .all(|tx| tx.description.as_deref() == Some("card ****"))
+        );
     }
 }
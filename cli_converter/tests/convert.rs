@@ -0,0 +1,155 @@
+//! Сквозная проверка конвертации: запуск собранного приложения на файлах во временном каталоге.
+
+use parser::YPFormatSupported;
+use std::fs;
+use std::io::Cursor;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+const CSV_DATA: &str = "TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION\n\
+    123456789,TRANSFER,1001,1002,50000,1633046400,SUCCESS,\"Test \"\"quoted\"\", transaction\"\n\
+    987654321,DEPOSIT,0,1003,100000,1633046401,PENDING,\"Salary\"\n";
+
+/// Отдельный временный каталог для теста `name`.
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("cli_converter_{name}_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Запускает конвертацию `input` (csv) в `output` (txt) с дополнительными аргументами.
+fn run_converter(input: &PathBuf, output: &PathBuf, extra: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_cli_converter"))
+        .arg("-i")
+        .arg(input)
+        .args(["--input-format", "csv", "--output-format", "txt"])
+        .arg("-o")
+        .arg(output)
+        .args(extra)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_convert_csv_to_txt() {
+    // Arrange
+    let dir = temp_dir("csv_to_txt");
+    let input = dir.join("records.csv");
+    let output = dir.join("records.txt");
+    fs::write(&input, CSV_DATA).unwrap();
+
+    // Act
+    let result = run_converter(&input, &output, &[]);
+
+    // Assert
+    assert!(result.status.success(), "{result:?}");
+    let expected = YPFormatSupported::Csv
+        .to_transaction(&mut Cursor::new(CSV_DATA))
+        .unwrap();
+    let converted = YPFormatSupported::Text
+        .to_transaction(&mut fs::File::open(&output).unwrap())
+        .unwrap();
+    assert_eq!(converted, expected);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_convert_refuses_overwrite() {
+    // Arrange
+    let dir = temp_dir("no_overwrite");
+    let input = dir.join("records.csv");
+    let output = dir.join("records.txt");
+    fs::write(&input, CSV_DATA).unwrap();
+    fs::write(&output, "keep").unwrap();
+
+    // Act
+    let result = run_converter(&input, &output, &["--not-overwrite"]);
+
+    // Assert
+    assert!(!result.status.success());
+    assert!(!result.stderr.is_empty());
+    assert_eq!(fs::read_to_string(&output).unwrap(), "keep");
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_convert_strict_extension_mismatch() {
+    // Arrange
+    let dir = temp_dir("strict_ext");
+    let input = dir.join("records.csv");
+    let output = dir.join("records.bin");
+    fs::write(&input, CSV_DATA).unwrap();
+
+    // Act
+    let result = run_converter(&input, &output, &["--strict-target-ext"]);
+
+    // Assert
+    assert!(!result.status.success());
+    assert!(!output.exists());
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_convert_filter_status() {
+    // Arrange
+    let dir = temp_dir("filter_status");
+    let input = dir.join("records.csv");
+    let output = dir.join("records.txt");
+    fs::write(&input, CSV_DATA).unwrap();
+
+    // Act
+    let result = run_converter(&input, &output, &["--filter-status", "success"]);
+
+    // Assert
+    assert!(result.status.success(), "{result:?}");
+    let converted = YPFormatSupported::Text
+        .to_transaction(&mut fs::File::open(&output).unwrap())
+        .unwrap();
+    assert_eq!(converted.len(), 1);
+    assert_eq!(converted[0].tx_id, 123456789);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_convert_merges_mixed_inputs() {
+    // Arrange
+    let dir = temp_dir("merge_inputs");
+    let csv_input = dir.join("records.csv");
+    let bin_input = dir.join("records.bin");
+    let output = dir.join("merged.txt");
+    fs::write(&csv_input, CSV_DATA).unwrap();
+    let csv_records = YPFormatSupported::Csv
+        .to_transaction(&mut Cursor::new(CSV_DATA))
+        .unwrap();
+    let bin_records = vec![csv_records[1].clone()];
+    YPFormatSupported::Binary
+        .write_transactions(&mut fs::File::create(&bin_input).unwrap(), &bin_records)
+        .unwrap();
+
+    // Act
+    let result = Command::new(env!("CARGO_BIN_EXE_cli_converter"))
+        .arg("-i")
+        .arg(&csv_input)
+        .args(["--input-format", "csv", "-i"])
+        .arg(&bin_input)
+        .args(["--input-format", "bin", "--output-format", "txt", "-o"])
+        .arg(&output)
+        .output()
+        .unwrap();
+
+    // Assert
+    assert!(result.status.success(), "{result:?}");
+    let converted = YPFormatSupported::Text
+        .to_transaction(&mut fs::File::open(&output).unwrap())
+        .unwrap();
+    assert_eq!(converted.len(), csv_records.len() + bin_records.len());
+    assert_eq!(converted[..csv_records.len()], csv_records[..]);
+    assert_eq!(converted[csv_records.len()..], bin_records[..]);
+
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -32,8 +32,24 @@ use syn::{Data, DataStruct, DeriveInput, Fields, parse_macro_input};
 /// println!("{}", n);
 /// ```
 ///
+/// - `pub const fn all() -> &'static [Self]`
+///
+/// Возвращает все варианты перечисления в порядке объявления (например, для справки CLI).
+///
+/// - `pub const fn as_pascal(&self) -> &'static str`
+///
+/// Возвращает имя варианта в исходном написании (`Deposit`), тогда как `Display` выводит его
+/// в верхнем регистре (`DEPOSIT`).
+///
 /// Два других метода: реализация `Display` и возможность получить экземпляр перечисления на основе
-/// его текстового представления (`FromStr`).
+/// его текстового представления (`FromStr`). `FromStr` не учитывает регистр, поэтому принимает
+/// оба написания.
+///
+/// Значения вариантов должны различаться: повтор (например, после копирования варианта)
+/// сообщается ошибкой компиляции с именами обоих вариантов.
+///
+/// Дополнительно реализуется трейт `parser::traits::TxKind`, поэтому перечисление можно
+/// использовать в обобщённых читателях библиотеки. Перечисление должно реализовывать `Clone`.
 #[proc_macro_derive(TxDisplay)]
 pub fn derive_tx_display(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
@@ -57,6 +73,24 @@ pub fn derive_tx_display(input: TokenStream) -> TokenStream {
         })
         .collect();
 
+    // Повтор значения сделал бы одну из ветвей from_u8 недостижимой.
+    for (i, (ident, value, _)) in variant_data.iter().enumerate() {
+        let value_key = discriminant_key(value);
+        if let Some((first, _, _)) = variant_data[..i]
+            .iter()
+            .find(|(_, other, _)| discriminant_key(other) == value_key)
+        {
+            return syn::Error::new_spanned(
+                ident,
+                format!(
+                    "Варианты {first} и {ident} перечисления {name} имеют одинаковое значение {value_key}"
+                ),
+            )
+            .to_compile_error()
+            .into();
+        }
+    }
+
     // from_u8
     let match_arms = variant_data.iter().map(|(ident, value, _)| {
         quote! { #value => Some(Self::#ident), }
@@ -67,6 +101,17 @@ pub fn derive_tx_display(input: TokenStream) -> TokenStream {
         quote! { Self::#ident => #uppercase, }
     });
 
+    // all
+    let all_variants = variant_data
+        .iter()
+        .map(|(ident, _, _)| quote! { Self::#ident });
+
+    // as_pascal
+    let pascal_arms = variant_data.iter().map(|(ident, _, _)| {
+        let pascal = ident.to_string();
+        quote! { Self::#ident => #pascal, }
+    });
+
     // FromStr
     let from_str_arms = variant_data.iter().map(|(ident, _, uppercase)| {
         quote! { #uppercase => Ok(Self::#ident), }
@@ -87,10 +132,30 @@ pub fn derive_tx_display(input: TokenStream) -> TokenStream {
                     _ => None,
                 }
             }
+
+            /// Все варианты перечисления в порядке объявления.
+            pub const fn all() -> &'static [Self] {
+                &[#(#all_variants),*]
+            }
+
+            /// Имя варианта в исходном написании (например, `Deposit`).
+            pub const fn as_pascal(&self) -> &'static str {
+                match self { #(#pascal_arms)* }
+            }
+        }
+
+        impl ::parser::traits::TxKind for #name {
+            fn as_u8(&self) -> u8 {
+                self.clone() as u8
+            }
+
+            fn from_u8(value: u8) -> Option<Self> {
+                #name::from_u8(value)
+            }
         }
 
         impl std::fmt::Display for #name {
-            fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), std::fmt::Error> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
                 let result = match self { #(#display_arms)* };
                 f.write_str(result)
             }
@@ -119,9 +184,16 @@ pub fn derive_tx_display(input: TokenStream) -> TokenStream {
 /// - `fn has_field_from_str(field: &str) -> bool` — проверяет наличие поля по строковому имени.
 /// - `fn fields() -> [&'static str; N]` — возвращает массив имён полей в верхнем регистре.
 ///
+/// ## Атрибуты полей
+///
+/// - `#[yp(skip)]` — служебное поле (например, длина описания в бинарном формате) не считается
+///   полем записи: оно не попадает в `fields()` и не распознаётся `has_field_from_str`.
+/// - `#[yp(rename = "TXID")]` — имя поля задаётся явно вместо имени идентификатора (также
+///   в верхнем регистре). Повтор имени в структуре — ошибка компиляции.
+///
 /// ## Ограничения:
 /// Работает только с именованными структурами (без tuple-structs и unit-structs).
-#[proc_macro_derive(YPBankFields)]
+#[proc_macro_derive(YPBankFields, attributes(yp))]
 pub fn derive_ypbank_fields(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let struct_name = input.ident;
@@ -141,24 +213,38 @@ pub fn derive_ypbank_fields(input: TokenStream) -> TokenStream {
         }
     };
 
-    // Собираем идентификаторы и строковые версии имён полей.
-    let field_pairs: Vec<_> = fields_named
-        .named
-        .iter()
-        .filter_map(|f| f.ident.as_ref())
-        .map(|ident| {
-            let field_str = ident.to_string();
-            let uppercase = field_str.to_uppercase();
-            (ident.clone(), field_str, uppercase)
-        })
-        .collect();
+    // Собираем имена полей: переименованные или идентификаторы в верхнем регистре.
+    let mut field_names: Vec<String> = Vec::new();
+    for field in &fields_named.named {
+        let attrs = match FieldAttrs::parse(field) {
+            Ok(attrs) => attrs,
+            Err(err) => return err.to_compile_error().into(),
+        };
+        let Some(ident) = field.ident.as_ref().filter(|_| !attrs.skip) else {
+            continue;
+        };
+
+        let name = attrs
+            .rename
+            .unwrap_or_else(|| ident.to_string())
+            .to_uppercase();
+        if field_names.contains(&name) {
+            return syn::Error::new_spanned(
+                ident,
+                format!("Имя поля {name} повторяется в {struct_name}: имена полей должны быть уникальными"),
+            )
+            .to_compile_error()
+            .into();
+        }
+        field_names.push(name);
+    }
 
     // Создаём выражения (`"FIELD_NAME"`) для массива `fields()`.
-    let field_names = field_pairs
+    let field_count = field_names.len();
+    let field_names = field_names
         .iter()
-        .map(|(_, _, uppercase)| syn::LitStr::new(uppercase, struct_name.span()));
+        .map(|name| syn::LitStr::new(name, struct_name.span()));
 
-    let field_count = field_pairs.len();
     // Генерируем реализацию
     let expanded = quote! {
         impl #struct_name {
@@ -190,3 +276,52 @@ pub fn derive_ypbank_fields(input: TokenStream) -> TokenStream {
 
     TokenStream::from(expanded)
 }
+
+/// Значение варианта перечисления для сравнения: число для целочисленного литерала, иначе
+/// текст выражения.
+fn discriminant_key(value: &syn::Expr) -> String {
+    match value {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(int),
+            ..
+        }) => int
+            .base10_parse::<u128>()
+            .map_or_else(|_| int.to_string(), |v| v.to_string()),
+        other => quote!(#other).to_string(),
+    }
+}
+
+/// Параметры поля из атрибута `#[yp(...)]`.
+#[derive(Default)]
+struct FieldAttrs {
+    /// `#[yp(skip)]`: поле исключается из списка полей.
+    skip: bool,
+    /// `#[yp(rename = "...")]`: имя поля вместо идентификатора.
+    rename: Option<String>,
+}
+
+impl FieldAttrs {
+    /// Разбирает атрибуты `yp` поля. Неизвестные параметры и пустое имя — ошибка.
+    fn parse(field: &syn::Field) -> syn::Result<Self> {
+        let mut attrs = Self::default();
+        for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("yp")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("skip") {
+                    attrs.skip = true;
+                    Ok(())
+                } else if meta.path.is_ident("rename") {
+                    let name: syn::LitStr = meta.value()?.parse()?;
+                    if name.value().is_empty() {
+                        return Err(meta.error("имя поля в yp(rename) не может быть пустым"));
+                    }
+                    attrs.rename = Some(name.value());
+                    Ok(())
+                } else {
+                    Err(meta.error("неизвестный параметр yp, ожидается skip или rename"))
+                }
+            })?;
+        }
+
+        Ok(attrs)
+    }
+}
@@ -0,0 +1,411 @@
+//! Сводные расчёты по набору транзакций.
+//!
+//! * [`balances`] — итоговое изменение баланса каждого пользователя для сверки.
+//! * [`filter_transactions`] — отбор записей по условию, например перед записью в другой формат.
+//!   Готовые условия: [`by_status`], [`by_timestamp_range`].
+//! * [`dedup_by_tx_id`] — удаление повторов `TX_ID` после объединения выгрузок.
+//! * [`sort_transactions`] — упорядочивание записей по полю [`SortKey`] перед сравнением или
+//!   выводом.
+
+use crate::models::{TxStatus, TxType, YPBankTransaction};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+
+/// Считает итоговое изменение баланса каждого пользователя.
+///
+/// Учитываются только успешные операции ([`TxStatus::Success`]). Пополнение зачисляет сумму
+/// получателю (`TO_USER_ID`), списание снимает её с отправителя (`FROM_USER_ID`), перевод
+/// переносит её от отправителя к получателю. Направление определяется типом операции, поэтому
+/// используется модуль знаковой суммы `amount`. При переполнении `i64` баланс насыщается.
+///
+/// ## Пример
+///
+/// ```
+/// use parser::analytics::balances;
+/// use parser::models::{TxStatus, TxType, YPBankTransaction};
+///
+/// let transfer = YPBankTransaction {
+///     tx_id: 1,
+///     tx_type: TxType::Transfer,
+///     from_user_id: 1001,
+///     to_user_id: 1002,
+///     amount: -300,
+///     timestamp: 1633046400,
+///     status: TxStatus::Success,
+///     description: None,
+/// };
+///
+/// let result = balances(&[transfer]);
+/// assert_eq!(result[&1001], -300);
+/// assert_eq!(result[&1002], 300);
+/// ```
+pub fn balances(txs: &[YPBankTransaction]) -> HashMap<u64, i64> {
+    let mut result: HashMap<u64, i64> = HashMap::new();
+    let mut apply = |user_id: u64, delta: i64| {
+        let balance = result.entry(user_id).or_default();
+        *balance = balance.saturating_add(delta);
+    };
+
+    for tx in txs.iter().filter(|tx| tx.status == TxStatus::Success) {
+        let amount = tx.amount.saturating_abs();
+        match tx.tx_type {
+            TxType::Deposit => apply(tx.to_user_id, amount),
+            TxType::Withdrawal => apply(tx.from_user_id, -amount),
+            TxType::Transfer => {
+                apply(tx.from_user_id, -amount);
+                apply(tx.to_user_id, amount);
+            }
+        }
+    }
+
+    result
+}
+
+/// Оставляет в наборе только транзакции, для которых `pred` возвращает `true`. Порядок
+/// записей сохраняется.
+///
+/// ## Пример
+///
+/// ```
+/// use parser::analytics::{by_status, filter_transactions};
+/// use parser::models::{TxStatus, TxType, YPBankTransaction};
+///
+/// let deposit = YPBankTransaction {
+///     tx_id: 1,
+///     tx_type: TxType::Deposit,
+///     from_user_id: 0,
+///     to_user_id: 1001,
+///     amount: 500,
+///     timestamp: 1633046400,
+///     status: TxStatus::Pending,
+///     description: None,
+/// };
+///
+/// let result = filter_transactions(vec![deposit], by_status(TxStatus::Success));
+/// assert!(result.is_empty());
+/// ```
+pub fn filter_transactions<F: Fn(&YPBankTransaction) -> bool>(
+    txs: Vec<YPBankTransaction>,
+    pred: F,
+) -> Vec<YPBankTransaction> {
+    txs.into_iter().filter(|tx| pred(tx)).collect()
+}
+
+/// Условие для [`filter_transactions`]: статус операции равен `status`.
+pub fn by_status(status: TxStatus) -> impl Fn(&YPBankTransaction) -> bool {
+    move |tx| tx.status == status
+}
+
+/// Условие для [`filter_transactions`]: метка времени в диапазоне `from..=to` (обе границы
+/// включены).
+pub fn by_timestamp_range(from: u64, to: u64) -> impl Fn(&YPBankTransaction) -> bool {
+    move |tx| (from..=to).contains(&tx.timestamp)
+}
+
+/// Результат [`dedup_by_tx_id`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Deduplicated {
+    /// Набор без повторов: первая запись каждого `TX_ID`, в исходном порядке.
+    pub records: Vec<YPBankTransaction>,
+    /// `TX_ID` отброшенных точных копий, по одному на каждую отброшенную запись.
+    pub duplicates: Vec<u64>,
+    /// `TX_ID` отброшенных записей, которые отличаются от первой записи с тем же `TX_ID`,
+    /// по одному на каждую отброшенную запись.
+    pub conflicts: Vec<u64>,
+}
+
+/// Удаляет повторы `TX_ID`, сохраняя первую запись каждого идентификатора.
+///
+/// Отброшенные записи делятся на точные копии первой записи и конфликты — записи с тем же
+/// `TX_ID`, но другими полями. Отсутствующее и пустое описание считаются одинаковыми.
+pub fn dedup_by_tx_id(txs: Vec<YPBankTransaction>) -> Deduplicated {
+    let mut result = Deduplicated {
+        records: Vec::with_capacity(txs.len()),
+        duplicates: Vec::new(),
+        conflicts: Vec::new(),
+    };
+    // Позиция первой записи идентификатора в `result.records`.
+    let mut first: HashMap<u64, usize> = HashMap::new();
+
+    for tx in txs {
+        match first.entry(tx.tx_id) {
+            Entry::Vacant(entry) => {
+                entry.insert(result.records.len());
+                result.records.push(tx);
+            }
+            Entry::Occupied(entry) => {
+                if result.records[*entry.get()].canonical_bytes() == tx.canonical_bytes() {
+                    result.duplicates.push(tx.tx_id);
+                } else {
+                    result.conflicts.push(tx.tx_id);
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// Поле, по которому упорядочиваются записи в [`sort_transactions`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SortKey {
+    /// Идентификатор транзакции `TX_ID`.
+    TxId,
+    /// Метка времени `TIMESTAMP`.
+    Timestamp,
+    /// Знаковая сумма `AMOUNT`.
+    Amount,
+}
+
+/// Направление сортировки в [`sort_transactions`].
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    /// По возрастанию.
+    #[default]
+    Ascending,
+    /// По убыванию.
+    Descending,
+}
+
+/// Упорядочивает записи по полю `key` в направлении `order`.
+///
+/// Сортировка устойчивая: записи с равными значениями поля сохраняют исходный порядок при любом
+/// направлении.
+pub fn sort_transactions(txs: &mut [YPBankTransaction], key: SortKey, order: SortOrder) {
+    let field = |tx: &YPBankTransaction| -> i128 {
+        match key {
+            SortKey::TxId => i128::from(tx.tx_id),
+            SortKey::Timestamp => i128::from(tx.timestamp),
+            SortKey::Amount => i128::from(tx.amount),
+        }
+    };
+
+    match order {
+        SortOrder::Ascending => txs.sort_by_key(field),
+        SortOrder::Descending => txs.sort_by_key(|tx| Reverse(field(tx))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::transaction;
+
+    #[test]
+    fn test_balances_mixed_set() {
+        // Arrange
+        let txs = [
+            YPBankTransaction {
+                to_user_id: 1001,
+                ..transaction(1, TxType::Deposit, 1000)
+            },
+            transaction(1, TxType::Deposit, 500),
+            transaction(1, TxType::Transfer, -300),
+            YPBankTransaction {
+                from_user_id: 1002,
+                ..transaction(1, TxType::Withdrawal, -200)
+            },
+            YPBankTransaction {
+                from_user_id: 1002,
+                to_user_id: 1003,
+                ..transaction(1, TxType::Transfer, -50)
+            },
+            YPBankTransaction {
+                to_user_id: 1003,
+                status: TxStatus::Pending,
+                ..transaction(1, TxType::Deposit, 700)
+            },
+            YPBankTransaction {
+                status: TxStatus::Failure,
+                ..transaction(1, TxType::Withdrawal, -400)
+            },
+        ];
+
+        // Act
+        let result = balances(&txs);
+
+        // Assert
+        assert_eq!(
+            result,
+            HashMap::from([(1001, 700), (1002, 550), (1003, 50)])
+        );
+    }
+
+    #[test]
+    fn test_balances_ignores_unsuccessful_and_saturates() {
+        // Arrange
+        let txs = [
+            transaction(1, TxType::Deposit, i64::MAX),
+            transaction(1, TxType::Deposit, i64::MAX),
+            YPBankTransaction {
+                status: TxStatus::Failure,
+                ..transaction(1, TxType::Transfer, -100)
+            },
+        ];
+
+        // Act
+        let result = balances(&txs);
+
+        // Assert
+        assert_eq!(result, HashMap::from([(1002, i64::MAX)]));
+    }
+
+    #[test]
+    fn test_filter_by_status() {
+        // Arrange
+        let txs = vec![
+            transaction(1, TxType::Deposit, 100),
+            YPBankTransaction {
+                status: TxStatus::Pending,
+                ..transaction(1, TxType::Deposit, 200)
+            },
+            transaction(1, TxType::Deposit, 300),
+        ];
+
+        // Act
+        let result = filter_transactions(txs, by_status(TxStatus::Success));
+
+        // Assert
+        let amounts: Vec<i64> = result.iter().map(|tx| tx.amount).collect();
+        assert_eq!(amounts, [100, 300]);
+    }
+
+    #[test]
+    fn test_filter_by_timestamp_range() {
+        // Arrange
+        let txs: Vec<YPBankTransaction> = (0..5)
+            .map(|i| YPBankTransaction {
+                to_user_id: 1000 + i,
+                timestamp: 1633046400 + i * 60,
+                ..transaction(1, TxType::Deposit, 100)
+            })
+            .collect();
+
+        // Act
+        let result = filter_transactions(txs, by_timestamp_range(1633046460, 1633046580));
+
+        // Assert
+        let users: Vec<u64> = result.iter().map(|tx| tx.to_user_id).collect();
+        assert_eq!(users, [1001, 1002, 1003]);
+    }
+
+    #[test]
+    fn test_dedup_exact_duplicates() {
+        // Arrange
+        let first = transaction(1, TxType::Deposit, 100);
+        let second = YPBankTransaction {
+            tx_id: 2,
+            description: Some(String::new()),
+            ..first.clone()
+        };
+        let txs = vec![
+            first.clone(),
+            second.clone(),
+            first.clone(),
+            YPBankTransaction {
+                description: None,
+                ..second.clone()
+            },
+            first.clone(),
+        ];
+
+        // Act
+        let result = dedup_by_tx_id(txs);
+
+        // Assert
+        assert_eq!(result.records, [first, second]);
+        assert_eq!(result.duplicates, [1, 2, 1]);
+        assert!(result.conflicts.is_empty());
+    }
+
+    #[test]
+    fn test_dedup_conflicting_records() {
+        // Arrange
+        let first = YPBankTransaction {
+            to_user_id: 1001,
+            ..transaction(1, TxType::Deposit, 100)
+        };
+        let txs = vec![
+            first.clone(),
+            YPBankTransaction {
+                amount: 200,
+                ..first.clone()
+            },
+            first.clone(),
+            YPBankTransaction {
+                status: TxStatus::Failure,
+                ..first.clone()
+            },
+        ];
+
+        // Act
+        let result = dedup_by_tx_id(txs);
+
+        // Assert
+        assert_eq!(result.records, [first]);
+        assert_eq!(result.duplicates, [1]);
+        assert_eq!(result.conflicts, [1, 1]);
+    }
+
+    /// Записи с совпадающими значениями полей для проверки устойчивости сортировки.
+    fn create_sortable() -> Vec<YPBankTransaction> {
+        [(3, 20, -50), (1, 10, 100), (2, 20, 100), (1, 30, -50)]
+            .into_iter()
+            .map(|(tx_id, timestamp, amount)| YPBankTransaction {
+                timestamp,
+                ..transaction(tx_id, TxType::Deposit, amount)
+            })
+            .collect()
+    }
+
+    /// Пары `(TX_ID, TIMESTAMP)` — однозначно определяют записи из [`create_sortable`].
+    fn ids(txs: &[YPBankTransaction]) -> Vec<(u64, u64)> {
+        txs.iter().map(|tx| (tx.tx_id, tx.timestamp)).collect()
+    }
+
+    #[test]
+    fn test_sort_by_tx_id() {
+        // Arrange
+        let mut ascending = create_sortable();
+        let mut descending = create_sortable();
+
+        // Act
+        sort_transactions(&mut ascending, SortKey::TxId, SortOrder::Ascending);
+        sort_transactions(&mut descending, SortKey::TxId, SortOrder::Descending);
+
+        // Assert
+        assert_eq!(ids(&ascending), [(1, 10), (1, 30), (2, 20), (3, 20)]);
+        assert_eq!(ids(&descending), [(3, 20), (2, 20), (1, 10), (1, 30)]);
+    }
+
+    #[test]
+    fn test_sort_by_timestamp() {
+        // Arrange
+        let mut ascending = create_sortable();
+        let mut descending = create_sortable();
+
+        // Act
+        sort_transactions(&mut ascending, SortKey::Timestamp, SortOrder::Ascending);
+        sort_transactions(&mut descending, SortKey::Timestamp, SortOrder::Descending);
+
+        // Assert
+        assert_eq!(ids(&ascending), [(1, 10), (3, 20), (2, 20), (1, 30)]);
+        assert_eq!(ids(&descending), [(1, 30), (3, 20), (2, 20), (1, 10)]);
+    }
+
+    #[test]
+    fn test_sort_by_amount() {
+        // Arrange
+        let mut ascending = create_sortable();
+        let mut descending = create_sortable();
+
+        // Act
+        sort_transactions(&mut ascending, SortKey::Amount, SortOrder::Ascending);
+        sort_transactions(&mut descending, SortKey::Amount, SortOrder::Descending);
+
+        // Assert
+        assert_eq!(ids(&ascending), [(3, 20), (1, 30), (1, 10), (2, 20)]);
+        assert_eq!(ids(&descending), [(1, 10), (2, 20), (3, 20), (1, 30)]);
+    }
+}
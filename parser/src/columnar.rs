@@ -0,0 +1,197 @@
+//! Преобразование транзакций в колоночный формат Apache Arrow и обратно.
+//!
+//! Доступно с функцией (feature) `arrow`. Полученный [`RecordBatch`] можно передавать
+//! в аналитические библиотеки, работающие с Arrow (DataFusion, Polars и т.п.).
+//!
+//! Каждому полю [`YPBankTransaction`] соответствует колонка с именем поля в верхнем регистре.
+//! Перечисления хранятся строковыми колонками (`DEPOSIT`, `SUCCESS`, ...), отсутствующее
+//! описание — значением `null`.
+
+use crate::errors::ParseError;
+use crate::models::{TxStatus, TxType, YPBankTransaction};
+use arrow_array::{Array, ArrayRef, Int64Array, RecordBatch, StringArray, UInt64Array};
+use arrow_schema::{DataType, Field, Schema};
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Схема колоночного представления транзакций.
+pub fn schema() -> Schema {
+    Schema::new(vec![
+        Field::new("TX_ID", DataType::UInt64, false),
+        Field::new("TX_TYPE", DataType::Utf8, false),
+        Field::new("FROM_USER_ID", DataType::UInt64, false),
+        Field::new("TO_USER_ID", DataType::UInt64, false),
+        Field::new("AMOUNT", DataType::Int64, false),
+        Field::new("TIMESTAMP", DataType::UInt64, false),
+        Field::new("STATUS", DataType::Utf8, false),
+        Field::new("DESCRIPTION", DataType::Utf8, true),
+    ])
+}
+
+/// Формирует колоночный [`RecordBatch`] из набора транзакций.
+///
+/// ## Пример
+///
+/// ```
+/// use parser::models::{TxStatus, TxType, YPBankTransaction};
+/// use parser::{from_arrow, to_arrow};
+///
+/// let records = vec![YPBankTransaction {
+///     tx_id: 1,
+///     tx_type: TxType::Deposit,
+///     from_user_id: 0,
+///     to_user_id: 1001,
+///     amount: 500,
+///     timestamp: 1633046400,
+///     status: TxStatus::Success,
+///     description: None,
+/// }];
+///
+/// let batch = to_arrow(&records);
+/// assert_eq!(batch.num_rows(), 1);
+/// assert_eq!(from_arrow(&batch).unwrap(), records);
+/// ```
+pub fn to_arrow(records: &[YPBankTransaction]) -> RecordBatch {
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(UInt64Array::from_iter_values(
+            records.iter().map(|tx| tx.tx_id),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            records.iter().map(|tx| tx.tx_type.to_string()),
+        )),
+        Arc::new(UInt64Array::from_iter_values(
+            records.iter().map(|tx| tx.from_user_id),
+        )),
+        Arc::new(UInt64Array::from_iter_values(
+            records.iter().map(|tx| tx.to_user_id),
+        )),
+        Arc::new(Int64Array::from_iter_values(
+            records.iter().map(|tx| tx.amount),
+        )),
+        Arc::new(UInt64Array::from_iter_values(
+            records.iter().map(|tx| tx.timestamp),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            records.iter().map(|tx| tx.status.to_string()),
+        )),
+        Arc::new(StringArray::from_iter(
+            records.iter().map(|tx| tx.description.as_deref()),
+        )),
+    ];
+
+    RecordBatch::try_new(Arc::new(schema()), columns)
+        .expect("Колонки не соответствуют схеме транзакций")
+}
+
+/// Восстанавливает транзакции из колоночного [`RecordBatch`].
+///
+/// Колонки ищутся по имени, их порядок не важен. Отсутствующая колонка, колонка другого типа
+/// или `null` в обязательном поле приводят к [`ParseError::IncorrectField`].
+pub fn from_arrow(batch: &RecordBatch) -> Result<Vec<YPBankTransaction>, ParseError> {
+    let tx_id = column::<UInt64Array>(batch, "TX_ID")?;
+    let tx_type = column::<StringArray>(batch, "TX_TYPE")?;
+    let from_user_id = column::<UInt64Array>(batch, "FROM_USER_ID")?;
+    let to_user_id = column::<UInt64Array>(batch, "TO_USER_ID")?;
+    let amount = column::<Int64Array>(batch, "AMOUNT")?;
+    let timestamp = column::<UInt64Array>(batch, "TIMESTAMP")?;
+    let status = column::<StringArray>(batch, "STATUS")?;
+    let description = column::<StringArray>(batch, "DESCRIPTION")?;
+
+    (0..batch.num_rows())
+        .map(|i| {
+            Ok(YPBankTransaction {
+                tx_id: required(tx_id, i, "TX_ID")?.value(i),
+                tx_type: TxType::from_str(required(tx_type, i, "TX_TYPE")?.value(i))
+                    .map_err(|_| incorrect("TX_TYPE"))?,
+                from_user_id: required(from_user_id, i, "FROM_USER_ID")?.value(i),
+                to_user_id: required(to_user_id, i, "TO_USER_ID")?.value(i),
+                amount: required(amount, i, "AMOUNT")?.value(i),
+                timestamp: required(timestamp, i, "TIMESTAMP")?.value(i),
+                status: TxStatus::from_str(required(status, i, "STATUS")?.value(i))
+                    .map_err(|_| incorrect("STATUS"))?,
+                description: description
+                    .is_valid(i)
+                    .then(|| description.value(i).to_string()),
+            })
+        })
+        .collect()
+}
+
+/// Находит колонку по имени и приводит её к ожидаемому типу массива.
+fn column<'a, T: Array + 'static>(batch: &'a RecordBatch, name: &str) -> Result<&'a T, ParseError> {
+    batch
+        .column_by_name(name)
+        .and_then(|column| column.as_any().downcast_ref::<T>())
+        .ok_or_else(|| incorrect(name))
+}
+
+/// Проверяет, что значение обязательного поля в строке `row` не `null`.
+fn required<'a, T: Array>(array: &'a T, row: usize, name: &str) -> Result<&'a T, ParseError> {
+    if array.is_null(row) {
+        return Err(incorrect(name));
+    }
+
+    Ok(array)
+}
+
+/// Ошибка некорректной или отсутствующей колонки `name`.
+fn incorrect(name: &str) -> ParseError {
+    ParseError::incorrect_field(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arrow_round_trip() {
+        // Arrange
+        let records = vec![
+            YPBankTransaction {
+                tx_id: 1,
+                tx_type: TxType::Deposit,
+                from_user_id: 0,
+                to_user_id: 1001,
+                amount: 500,
+                timestamp: 1633046400,
+                status: TxStatus::Success,
+                description: Some("Salary".to_string()),
+            },
+            YPBankTransaction {
+                tx_id: 2,
+                tx_type: TxType::Withdrawal,
+                from_user_id: 1001,
+                to_user_id: 0,
+                amount: -200,
+                timestamp: 1633046401,
+                status: TxStatus::Pending,
+                description: None,
+            },
+        ];
+
+        // Act
+        let batch = to_arrow(&records);
+        let restored = from_arrow(&batch).unwrap();
+
+        // Assert
+        assert_eq!(batch.num_columns(), 8);
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(restored, records);
+    }
+
+    #[test]
+    fn test_from_arrow_missing_column() {
+        // Arrange
+        let batch = to_arrow(&[]);
+        let batch = batch.project(&[0, 1, 2, 3, 4, 5, 6]).unwrap();
+
+        // Act
+        let result = from_arrow(&batch);
+
+        // Assert
+        assert!(matches!(
+            result,
+            Err(ParseError::IncorrectField { key, .. }) if key == "DESCRIPTION"
+        ));
+    }
+}
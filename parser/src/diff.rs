@@ -0,0 +1,369 @@
+//! Разница между наборами транзакций.
+//!
+//! Позволяет сохранить компактное описание отличий между двумя наборами (например, ежедневными
+//! выгрузками) в виде [`Patch`] и восстановить целевой набор из исходного при помощи
+//! [`apply_patch`]. Для проверок в тестах предназначен [`diff_records`], возвращающий
+//! структурированный список расхождений [`RecordDiff`], а для подробного отчёта —
+//! [`changed_fields`] с отличающимися полями пары записей.
+//!
+//! [`Patch`] сериализуется через `serde`, например в JSON:
+//! операции записываются объектами с полем `op` (`replace`, `append`, `truncate`).
+
+use crate::models::YPBankTransaction;
+use serde::{Deserialize, Serialize};
+
+/// Расхождение между ожидаемым и фактическим набором транзакций в одной позиции.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordDiff {
+    /// Запись в позиции `index` отличается от ожидаемой.
+    Changed {
+        /// Позиция записи (с нуля).
+        index: usize,
+        /// Ожидаемая запись.
+        expected: YPBankTransaction,
+        /// Фактическая запись.
+        actual: YPBankTransaction,
+    },
+
+    /// Ожидаемая запись отсутствует в фактическом наборе.
+    Missing {
+        /// Позиция записи (с нуля).
+        index: usize,
+        /// Ожидаемая запись.
+        expected: YPBankTransaction,
+    },
+
+    /// В фактическом наборе есть лишняя запись.
+    Unexpected {
+        /// Позиция записи (с нуля).
+        index: usize,
+        /// Фактическая запись.
+        actual: YPBankTransaction,
+    },
+}
+
+/// Сравнивает наборы транзакций позиционно и возвращает список расхождений.
+///
+/// Записи сравниваются в каноническом виде: отсутствующее и пустое описание считаются
+/// одинаковыми, так как форматы `csv` и `txt` их не различают.
+pub fn diff_records(
+    expected: &[YPBankTransaction],
+    actual: &[YPBankTransaction],
+) -> Vec<RecordDiff> {
+    let mut diffs: Vec<RecordDiff> = expected
+        .iter()
+        .zip(actual)
+        .enumerate()
+        .filter(|(_, (e, a))| e.canonical_bytes() != a.canonical_bytes())
+        .map(|(index, (e, a))| RecordDiff::Changed {
+            index,
+            expected: e.clone(),
+            actual: a.clone(),
+        })
+        .collect();
+
+    diffs.extend(
+        expected
+            .iter()
+            .enumerate()
+            .skip(actual.len())
+            .map(|(index, e)| RecordDiff::Missing {
+                index,
+                expected: e.clone(),
+            }),
+    );
+
+    diffs.extend(
+        actual
+            .iter()
+            .enumerate()
+            .skip(expected.len())
+            .map(|(index, a)| RecordDiff::Unexpected {
+                index,
+                actual: a.clone(),
+            }),
+    );
+
+    diffs
+}
+
+/// Отличающееся поле пары записей: имя поля, ожидаемое и фактическое значения.
+pub type FieldChange = (&'static str, String, String);
+
+/// Поля записи в порядке формата с текстовыми значениями.
+///
+/// Отсутствующее описание представлено пустой строкой.
+pub fn record_fields(record: &YPBankTransaction) -> [(&'static str, String); 8] {
+    [
+        ("TX_ID", record.tx_id.to_string()),
+        ("TX_TYPE", record.tx_type.to_string()),
+        ("FROM_USER_ID", record.from_user_id.to_string()),
+        ("TO_USER_ID", record.to_user_id.to_string()),
+        ("AMOUNT", record.amount.to_string()),
+        ("TIMESTAMP", record.timestamp.to_string()),
+        ("STATUS", record.status.to_string()),
+        (
+            "DESCRIPTION",
+            record.description.clone().unwrap_or_default(),
+        ),
+    ]
+}
+
+/// Возвращает поля, значения которых у записей различаются, в порядке формата.
+///
+/// Как и в [`diff_records`], отсутствующее и пустое описание считаются одинаковыми.
+pub fn changed_fields(
+    expected: &YPBankTransaction,
+    actual: &YPBankTransaction,
+) -> Vec<FieldChange> {
+    record_fields(expected)
+        .into_iter()
+        .zip(record_fields(actual))
+        .filter(|((_, e), (_, a))| e != a)
+        .map(|((field, e), (_, a))| (field, e, a))
+        .collect()
+}
+
+/// Одна операция изменения набора транзакций.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum PatchOp {
+    /// Заменить запись в позиции `index`.
+    Replace {
+        /// Позиция заменяемой записи (с нуля).
+        index: usize,
+        /// Новое содержимое записи.
+        record: YPBankTransaction,
+    },
+
+    /// Добавить запись в конец набора.
+    Append {
+        /// Добавляемая запись.
+        record: YPBankTransaction,
+    },
+
+    /// Сократить набор до `len` записей.
+    Truncate {
+        /// Итоговое количество записей.
+        len: usize,
+    },
+}
+
+/// Набор операций, превращающих один набор транзакций в другой.
+///
+/// Сравнение позиционное: записи сопоставляются по порядковому номеру, как и в `cli_comparer`.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Patch {
+    /// Операции в порядке применения.
+    pub ops: Vec<PatchOp>,
+}
+
+impl Patch {
+    /// Формирует патч, превращающий `left` в `right`.
+    ///
+    /// ## Пример
+    ///
+    /// ```
+    /// use parser::diff::{Patch, apply_patch};
+    /// use parser::models::{TxStatus, TxType, YPBankTransaction};
+    ///
+    /// let before = vec![YPBankTransaction {
+    ///     tx_id: 1,
+    ///     tx_type: TxType::Deposit,
+    ///     from_user_id: 0,
+    ///     to_user_id: 1001,
+    ///     amount: 500,
+    ///     timestamp: 1633046400,
+    ///     status: TxStatus::Pending,
+    ///     description: None,
+    /// }];
+    /// let mut after = before.clone();
+    /// after[0].status = TxStatus::Success;
+    ///
+    /// let patch = Patch::between(&before, &after);
+    /// assert_eq!(apply_patch(before, &patch), after);
+    /// ```
+    pub fn between(left: &[YPBankTransaction], right: &[YPBankTransaction]) -> Self {
+        let mut ops: Vec<PatchOp> = left
+            .iter()
+            .zip(right.iter())
+            .enumerate()
+            .filter(|(_, (l, r))| l != r)
+            .map(|(index, (_, r))| PatchOp::Replace {
+                index,
+                record: r.clone(),
+            })
+            .collect();
+
+        if left.len() > right.len() {
+            ops.push(PatchOp::Truncate { len: right.len() });
+        }
+
+        ops.extend(right.iter().skip(left.len()).map(|record| PatchOp::Append {
+            record: record.clone(),
+        }));
+
+        Self { ops }
+    }
+
+    /// Возвращает `true`, если патч не содержит изменений.
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+}
+
+/// Применяет патч к исходному набору транзакций и возвращает целевой набор.
+///
+/// Патч должен применяться к тому же набору, из которого он был получен через
+/// [`Patch::between`]. Операции замены с позицией за пределами набора игнорируются.
+pub fn apply_patch(mut base: Vec<YPBankTransaction>, patch: &Patch) -> Vec<YPBankTransaction> {
+    for op in &patch.ops {
+        match op {
+            PatchOp::Replace { index, record } => {
+                if let Some(target) = base.get_mut(*index) {
+                    *target = record.clone();
+                }
+            }
+            PatchOp::Append { record } => base.push(record.clone()),
+            PatchOp::Truncate { len } => base.truncate(*len),
+        }
+    }
+
+    base
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{TxStatus, TxType};
+    use crate::testing::transaction;
+
+    #[test]
+    fn test_changed_fields_lists_differences() {
+        // Arrange
+        let expected = YPBankTransaction {
+            description: Some("Record number 1".to_string()),
+            ..transaction(1, TxType::Transfer, -100)
+        };
+        let actual = YPBankTransaction {
+            status: TxStatus::Failure,
+            description: Some("Other".to_string()),
+            ..transaction(1, TxType::Transfer, -250)
+        };
+
+        // Act
+        let result = changed_fields(&expected, &actual);
+
+        // Assert
+        assert_eq!(
+            result,
+            vec![
+                ("AMOUNT", "-100".to_string(), "-250".to_string()),
+                ("STATUS", "SUCCESS".to_string(), "FAILURE".to_string()),
+                (
+                    "DESCRIPTION",
+                    "Record number 1".to_string(),
+                    "Other".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_changed_fields_empty_description_equals_missing() {
+        // Arrange
+        let expected = transaction(1, TxType::Transfer, -100);
+        let actual = YPBankTransaction {
+            description: Some(String::new()),
+            ..transaction(1, TxType::Transfer, -100)
+        };
+
+        // Act
+        let result = changed_fields(&expected, &actual);
+
+        // Assert
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_apply_patch_reconstructs_longer_target() {
+        // Arrange
+        let left = vec![
+            transaction(1, TxType::Transfer, -100),
+            transaction(2, TxType::Transfer, -200),
+        ];
+        let right = vec![
+            transaction(1, TxType::Transfer, -100),
+            transaction(2, TxType::Transfer, -250),
+            transaction(3, TxType::Transfer, -300),
+        ];
+
+        // Act
+        let patch = Patch::between(&left, &right);
+        let result = apply_patch(left, &patch);
+
+        // Assert
+        assert_eq!(patch.ops.len(), 2);
+        assert_eq!(result, right);
+    }
+
+    #[test]
+    fn test_apply_patch_reconstructs_shorter_target() {
+        // Arrange
+        let left = vec![
+            transaction(1, TxType::Transfer, -100),
+            transaction(2, TxType::Transfer, -200),
+            transaction(3, TxType::Transfer, -300),
+        ];
+        let right = vec![transaction(5, TxType::Transfer, -100)];
+
+        // Act
+        let patch = Patch::between(&left, &right);
+        let result = apply_patch(left, &patch);
+
+        // Assert
+        assert_eq!(result, right);
+    }
+
+    #[test]
+    fn test_patch_between_identical_is_empty() {
+        // Arrange
+        let left = vec![
+            transaction(1, TxType::Transfer, -100),
+            transaction(2, TxType::Transfer, -200),
+        ];
+
+        // Act
+        let patch = Patch::between(&left, &left.clone());
+
+        // Assert
+        assert!(patch.is_empty());
+    }
+
+    #[test]
+    fn test_patch_json_round_trip() {
+        // Arrange
+        let left = vec![
+            transaction(1, TxType::Transfer, -100),
+            transaction(2, TxType::Transfer, -200),
+            transaction(3, TxType::Transfer, -300),
+        ];
+        let mut right = vec![
+            transaction(1, TxType::Transfer, -150),
+            transaction(2, TxType::Transfer, -200),
+        ];
+        right[0].description = Some("Corrected".to_string());
+        let patch = Patch::between(&left, &right);
+
+        // Act
+        let json = serde_json::to_string(&patch).unwrap();
+        let decoded: Patch = serde_json::from_str(&json).unwrap();
+
+        // Assert
+        assert!(json.contains(r#""op":"replace""#));
+        assert!(json.contains(r#""op":"truncate""#));
+        assert!(json.contains(r#""tx_type":"TRANSFER""#));
+        assert_eq!(decoded, patch);
+        assert_eq!(apply_patch(left, &decoded), right);
+    }
+}
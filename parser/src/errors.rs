@@ -4,6 +4,9 @@ use std::error::Error;
 use std::fmt::{Display, Formatter};
 use std::io::Error as IOError;
 
+/// Количество примеров ошибок, включаемых в сводку [`ParseError::summarize`].
+pub const SUMMARY_EXAMPLES: usize = 3;
+
 /// Библиотека предоставляет набор собственных ошибок и методов для их обслуживания.
 #[derive(Debug)]
 pub enum ParseError {
@@ -29,6 +32,10 @@ pub enum ParseError {
     IncorrectField {
         /// Имя отсутствующего ключа (поля).
         key: String,
+        /// Номер строки с полем (начиная с 1), `0` — положение неизвестно.
+        line: usize,
+        /// Номер колонки поля (начиная с 1), `0` — неизвестен или неприменим к формату.
+        column: usize,
     },
 
     /// Ошибка парсинга файла (например, нарушена структура).
@@ -53,9 +60,29 @@ pub enum ParseError {
         message: String,
     },
 
-    /// Предоставленный комплект для парсинга пустой.
+    /// Источник не содержит ни одной записи (см. [`ParseError::empty_data`]).
     EmptyData,
 
+    /// Сводка по нескольким ошибкам (см. [`ParseError::summarize`]).
+    Multiple {
+        /// Общее количество ошибок.
+        count: usize,
+        /// Количество ошибок по категориям и первые примеры.
+        summary: String,
+    },
+
+    /// Превышено допустимое количество записей во входных данных.
+    RecordLimitExceeded {
+        /// Максимально допустимое количество записей.
+        limit: usize,
+    },
+
+    /// Чтение в режиме восстановления прервано: превышено допустимое количество ошибок.
+    ErrorLimitExceeded {
+        /// Максимально допустимое количество ошибок.
+        limit: usize,
+    },
+
     /// Ошибка, вызванная некорректным форматом файла. Ожидался, например,
     /// `txt`, получен `csv`.
     InvalidFormat {
@@ -118,14 +145,104 @@ impl Error for ParseError {
     }
 }
 
+/// Сравнение ошибок по описательным полям.
+///
+/// Исходные ошибки (`err_source`) вариантов [`ParseError::IOError`] и
+/// [`ParseError::InvalidFormat`] в сравнении не участвуют: `std::io::Error` и `dyn Error`
+/// не реализуют `PartialEq`. Для `IOError` сравнивается только описание, для
+/// `InvalidFormat` — ожидаемый и обнаруженный форматы.
+impl PartialEq for ParseError {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::IOError { description: a, .. }, Self::IOError { description: b, .. }) => a == b,
+            (
+                Self::SizeLimitExceeded { actual, limit },
+                Self::SizeLimitExceeded {
+                    actual: other_actual,
+                    limit: other_limit,
+                },
+            ) => actual == other_actual && limit == other_limit,
+            (
+                Self::IncorrectField { key, line, column },
+                Self::IncorrectField {
+                    key: other_key,
+                    line: other_line,
+                    column: other_column,
+                },
+            ) => key == other_key && line == other_line && column == other_column,
+            (
+                Self::ParseError {
+                    message,
+                    line,
+                    column,
+                },
+                Self::ParseError {
+                    message: other_message,
+                    line: other_line,
+                    column: other_column,
+                },
+            ) => message == other_message && line == other_line && column == other_column,
+            (Self::ParseBinaryError { message: a }, Self::ParseBinaryError { message: b }) => {
+                a == b
+            }
+            (Self::EmptyData, Self::EmptyData) => true,
+            (
+                Self::Multiple { count, summary },
+                Self::Multiple {
+                    count: other_count,
+                    summary: other_summary,
+                },
+            ) => count == other_count && summary == other_summary,
+            (Self::RecordLimitExceeded { limit: a }, Self::RecordLimitExceeded { limit: b }) => {
+                a == b
+            }
+            (Self::ErrorLimitExceeded { limit: a }, Self::ErrorLimitExceeded { limit: b }) => {
+                a == b
+            }
+            (
+                Self::InvalidFormat { expected, got, .. },
+                Self::InvalidFormat {
+                    expected: other_expected,
+                    got: other_got,
+                    ..
+                },
+            ) => expected == other_expected && got == other_got,
+            (
+                Self::OverflowSize {
+                    from,
+                    to,
+                    description,
+                },
+                Self::OverflowSize {
+                    from: other_from,
+                    to: other_to,
+                    description: other_description,
+                },
+            ) => from == other_from && to == other_to && description == other_description,
+            (
+                Self::UnsupportedFormat { invalid_format: a },
+                Self::UnsupportedFormat { invalid_format: b },
+            ) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for ParseError {}
+
 impl Display for ParseError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             ParseError::IOError { description, .. } => {
                 write!(f, "Ошибка чтения/записи: {}", description)
             }
-            ParseError::IncorrectField { key } => {
-                write!(f, "Некорректные данные для поля: {key}")
+            ParseError::IncorrectField { key, line, column } => {
+                write!(f, "Некорректные данные для поля: {key}")?;
+                match (line, column) {
+                    (0, _) => Ok(()),
+                    (line, 0) => write!(f, " (строка {line})"),
+                    (line, column) => write!(f, " (строка {line}, колонка {column})"),
+                }
             }
             ParseError::SizeLimitExceeded { actual, limit } => {
                 write!(
@@ -177,7 +294,19 @@ impl Display for ParseError {
                 )
             }
             ParseError::EmptyData => {
-                write!(f, "Отсутствуют данные для парсинга")
+                write!(f, "Источник не содержит ни одной записи")
+            }
+            ParseError::Multiple { count, summary } => {
+                write!(f, "Обнаружено ошибок: {count}. {summary}")
+            }
+            ParseError::RecordLimitExceeded { limit } => {
+                write!(f, "Количество записей превышает лимит {limit}")
+            }
+            ParseError::ErrorLimitExceeded { limit } => {
+                write!(
+                    f,
+                    "Чтение прервано: количество ошибок достигло лимита {limit}"
+                )
             }
         }
     }
@@ -213,7 +342,42 @@ impl ParseError {
         Self::SizeLimitExceeded { actual, limit }
     }
 
-    /// Конструктор ошибки `ParseError:ParseError`.
+    /// Конструктор для ошибки пустого источника: [`ParseError::EmptyData`].
+    pub fn empty_data() -> Self {
+        Self::EmptyData
+    }
+
+    /// Конструктор для ошибки превышения количества записей:
+    /// [`ParseError::RecordLimitExceeded`].
+    pub fn record_limit(limit: usize) -> Self {
+        Self::RecordLimitExceeded { limit }
+    }
+
+    /// Конструктор для ошибки превышения количества ошибок в режиме восстановления:
+    /// [`ParseError::ErrorLimitExceeded`].
+    pub fn error_limit(limit: usize) -> Self {
+        Self::ErrorLimitExceeded { limit }
+    }
+
+    /// Конструктор ошибки [`ParseError::IncorrectField`] без положения поля в данных.
+    pub fn incorrect_field(key: impl Into<String>) -> Self {
+        Self::IncorrectField {
+            key: key.into(),
+            line: 0,
+            column: 0,
+        }
+    }
+
+    /// Дополняет ошибку [`ParseError::IncorrectField`] положением поля: строкой `line`
+    /// и колонкой `column`. Остальные ошибки возвращаются без изменений.
+    pub fn at_field(self, line: usize, column: usize) -> Self {
+        match self {
+            Self::IncorrectField { key, .. } => Self::IncorrectField { key, line, column },
+            other => other,
+        }
+    }
+
+    /// Конструктор ошибки [`ParseError::ParseError`].
     pub fn parse_err(message: impl Into<String>, line: usize, column: usize) -> Self {
         Self::ParseError {
             message: message.into(),
@@ -222,7 +386,7 @@ impl ParseError {
         }
     }
 
-    /// Конструктор ошибки `ParseBinaryError`.
+    /// Конструктор ошибки [`ParseError::ParseBinaryError`].
     ///
     /// Аргумент `message` может быть пустым, в таком случае будет подменён фразой
     /// по-умолчанию.
@@ -251,6 +415,96 @@ impl ParseError {
         }
     }
 
+    /// Сводит несколько ошибок в одну [`ParseError::Multiple`].
+    ///
+    /// Ошибки группируются по категориям (с подсчётом), к сводке добавляются первые
+    /// [`SUMMARY_EXAMPLES`] сообщений в исходном порядке.
+    ///
+    /// ## Пример
+    ///
+    /// ```
+    /// use parser::errors::ParseError;
+    ///
+    /// let errors = vec![
+    ///     ParseError::parse_err("Некорректная строка", 2, 0),
+    ///     ParseError::EmptyData,
+    /// ];
+    ///
+    /// let summary = ParseError::summarize(&errors);
+    /// assert!(matches!(summary, ParseError::Multiple { count: 2, .. }));
+    /// ```
+    pub fn summarize(errors: &[ParseError]) -> Self {
+        let mut categories: Vec<(&'static str, usize)> = Vec::new();
+        for err in errors {
+            let category = err.category();
+            match categories.iter_mut().find(|(name, _)| *name == category) {
+                Some((_, count)) => *count += 1,
+                None => categories.push((category, 1)),
+            }
+        }
+
+        let counts = categories
+            .iter()
+            .map(|(name, count)| format!("{name}: {count}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let examples = errors
+            .iter()
+            .take(SUMMARY_EXAMPLES)
+            .map(|err| format!("- {err}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let summary = if examples.is_empty() {
+            counts
+        } else {
+            format!("{counts}\nПримеры:\n{examples}")
+        };
+
+        Self::Multiple {
+            count: errors.len(),
+            summary,
+        }
+    }
+
+    /// Категория ошибки для группировки в сводке.
+    fn category(&self) -> &'static str {
+        match self {
+            ParseError::IOError { .. } => "ввод-вывод",
+            ParseError::SizeLimitExceeded { .. } => "превышение объёма",
+            ParseError::IncorrectField { .. } => "некорректное поле",
+            ParseError::ParseError { .. } => "ошибка парсинга",
+            ParseError::ParseBinaryError { .. } => "бинарные данные",
+            ParseError::EmptyData => "нет данных",
+            ParseError::InvalidFormat { .. } => "формат",
+            ParseError::OverflowSize { .. } => "переполнение",
+            ParseError::UnsupportedFormat { .. } => "неподдерживаемый формат",
+            ParseError::Multiple { .. } => "сводка",
+            ParseError::RecordLimitExceeded { .. } => "лимит записей",
+            ParseError::ErrorLimitExceeded { .. } => "лимит ошибок",
+        }
+    }
+
+    /// Возвращает вид исходной ошибки ввода-вывода для варианта [`ParseError::IOError`] и `None`
+    /// для остальных.
+    ///
+    /// ## Пример
+    ///
+    /// ```
+    /// use std::io::{Error, ErrorKind};
+    /// use parser::errors::ParseError;
+    ///
+    /// let err = ParseError::io_error(Error::from(ErrorKind::NotFound), "Файл не найден");
+    /// assert_eq!(err.io_kind(), Some(ErrorKind::NotFound));
+    /// ```
+    pub fn io_kind(&self) -> Option<std::io::ErrorKind> {
+        match self {
+            ParseError::IOError { err_source, .. } => Some(err_source.kind()),
+            _ => None,
+        }
+    }
+
     /// Конструктор ошибки `ParseError:InvalidFormat`.
     pub fn invalid_format(
         expected: impl Into<String>,
@@ -264,3 +518,96 @@ impl ParseError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::ErrorKind;
+
+    #[test]
+    fn test_io_kind() {
+        // Arrange
+        let io_err = ParseError::io_error(IOError::from(ErrorKind::NotFound), "Файл не найден");
+        let parse_err = ParseError::parse_err("Некорректная строка", 1, 0);
+
+        // Act & Assert
+        assert_eq!(io_err.io_kind(), Some(ErrorKind::NotFound));
+        assert_eq!(parse_err.io_kind(), None);
+    }
+
+    #[test]
+    fn test_summarize() {
+        // Arrange
+        let mut errors: Vec<ParseError> = (1..=9)
+            .map(|line| ParseError::parse_err("Ошибка чтения строки csv", line, 0))
+            .collect();
+        errors.push(ParseError::incorrect_field("AMOUNT"));
+
+        // Act
+        let summary = ParseError::summarize(&errors);
+        let message = summary.to_string();
+
+        // Assert
+        assert!(matches!(summary, ParseError::Multiple { count: 10, .. }));
+        assert!(message.contains("Обнаружено ошибок: 10"));
+        assert!(message.contains("ошибка парсинга: 9, некорректное поле: 1"));
+        assert_eq!(message.matches("\n- ").count(), SUMMARY_EXAMPLES);
+    }
+
+    #[test]
+    fn test_parse_err_constructor() {
+        // Act
+        let err = ParseError::parse_err("Некорректная строка", 3, 7);
+
+        // Assert
+        assert_eq!(
+            err,
+            ParseError::ParseError {
+                message: "Некорректная строка".to_string(),
+                line: 3,
+                column: 7,
+            }
+        );
+        assert!(err.source().is_none());
+        assert_eq!(
+            err.to_string(),
+            "Ошибка парсинга файла (строка 3, символ 7): Некорректная строка"
+        );
+    }
+
+    #[test]
+    fn test_parse_bin_error_constructor() {
+        // Act
+        let err = ParseError::parse_bin_error("Неизвестный TX_TYPE");
+        let empty = ParseError::parse_bin_error("");
+
+        // Assert
+        assert!(matches!(err, ParseError::ParseBinaryError { .. }));
+        assert!(err.source().is_none());
+        assert_eq!(
+            err.to_string(),
+            "Ошибка парсинга бинарного файла: Неизвестный TX_TYPE"
+        );
+        assert_eq!(empty.to_string(), "Ошибка парсинга бинарного файла");
+    }
+
+    #[test]
+    fn test_partial_eq_ignores_source() {
+        // Arrange
+        let not_found = ParseError::io_error(IOError::from(ErrorKind::NotFound), "Нет файла");
+        let denied = ParseError::io_error(IOError::from(ErrorKind::PermissionDenied), "Нет файла");
+        let csv =
+            ParseError::invalid_format("csv", "txt", Some(Box::new(IOError::other("первая"))));
+        let csv_other_source = ParseError::invalid_format("csv", "txt", None);
+
+        // Act & Assert
+        assert_eq!(not_found, denied);
+        assert_eq!(csv, csv_other_source);
+        assert_ne!(csv, ParseError::invalid_format("csv", "bin", None));
+        assert_ne!(
+            ParseError::incorrect_field("AMOUNT"),
+            ParseError::incorrect_field("AMOUNT").at_field(2, 5)
+        );
+        assert_eq!(ParseError::EmptyData, ParseError::empty_data());
+    }
+}
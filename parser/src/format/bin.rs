@@ -5,8 +5,62 @@
 //!
 //! * [`YPBankBinFormat::read_from`] — чтение (парсинг) данных в бинарном формате и распаковка в
 //!   отдельные экземпляры [`YPBankBinFormat`] каждой записи
+//! * [`YPBankBinFormat::read_iter`] — потоковое чтение: записи читаются по одной, без
+//!   загрузки всего файла в память.
+//! * [`YPBankBinFormat::read_from_lossy`] — чтение с восстановлением: повреждённые участки
+//!   пропускаются до следующего `MAGIC`, а сами повреждения возвращаются списком ошибок.
 //! * [`YPBankBinFormat::write_to`] — запись предоставленных элементов [`YPBankBinFormat`].
 //!
+//! ## Заголовок файла
+//!
+//! [`YPBankBinFormat::write_to`] начинает файл заголовком из [`HEADER_SIZE`] байт: [`MAGIC`],
+//! версия формата `u16` (1 или [`FORMAT_VERSION`]) и число записей `u64` (big-endian). При чтении
+//! заголовок проверяется: если прочитано иное число записей, чем объявлено (например, файл
+//! оборван на границе записи), возвращается [`ParseError::ParseBinaryError`].
+//!
+//! Заголовок необязателен: файлы прежних версий начинаются сразу с записей и читаются как
+//! прежде. Заголовок отличается от записи со старым [`MAGIC`] тем, что за идентификатором
+//! следует допустимая версия, а после заголовка — конец данных или идентификатор записи.
+//!
+//! ## Конкатенация
+//!
+//! Каждая запись самостоятельно обрамлена `MAGIC` и `RECORD_SIZE`, а заголовок допускается
+//! на любой границе записей и задаёт число записей до следующего заголовка. Поэтому результат
+//! простой склейки нескольких бинарных файлов (например, `cat a.bin b.bin`) читается как
+//! единый поток записей.
+//!
+//! ## Контрольная сумма
+//!
+//! Записи с идентификатором [`MAGIC_CRC`] (`YPBC`) после тела содержат CRC32 (IEEE, big-endian)
+//! байтов тела, и `RECORD_SIZE` учитывает эти 4 байта. [`YPBankBinFormat::write_to`] пишет
+//! записи с контрольной суммой. При чтении с идентификатором по умолчанию принимаются оба вида
+//! записей, поэтому файлы со старым [`MAGIC`] (без контрольной суммы) читаются как прежде.
+//! Несовпадение суммы — ошибка [`ParseError::ParseBinaryError`] со смещением записи.
+//!
+//! Записи с собственным идентификатором ([`YPBankBinFormat::write_to_with_magic`]) пишутся
+//! после заголовка файла и всегда содержат CRC32: признаком суммы служит сам заголовок.
+//!
+//! ## Пустое описание
+//!
+//! Отсутствующее описание и пустая строка на диске неразличимы: в обоих случаях `DESC_LEN`
+//! равен `0`, отдельного флага формат не предусматривает. Поэтому `Some("")` при записи
+//! сохраняется как отсутствующее описание и читается обратно как `None`. Преобразование
+//! [`YPBankTransaction`](crate::models::YPBankTransaction) в [`YPBankBinFormat`] выполняет
+//! ту же нормализацию сразу, чтобы модель в памяти совпадала с прочитанной с диска.
+//!
+//! ## Описание в base64
+//!
+//! Описание из произвольных байтов (поле `raw_description`, не обязательно UTF-8) пишется
+//! текстом base64, а в `DESC_LEN` выставляется старший бит [`DESC_BASE64_FLAG`]; остальные биты
+//! задают длину текста base64. При чтении такое описание декодируется обратно в
+//! `raw_description`.
+//!
+//! Флаг пишется только после заголовка версии [`FORMAT_VERSION`] (2): писатель выставляет её,
+//! лишь когда среди записей есть описание в base64, иначе пишет версию 1. Прежние читатели не
+//! распознают заголовок версии 2 и отклоняют такой файл, а не принимают флаг за длину
+//! описания. Поэтому записи с описанием в base64 нельзя писать без заголовка файла (с [`MAGIC`]
+//! или [`MAGIC_CRC`] в [`YPBankBinFormat::write_to_with_magic`]).
+//!
 //! # Примеры
 //!
 //! ```no_run
@@ -25,20 +79,450 @@ use crate::errors::ParseError;
 use crate::format::tools::validate_exceed_max_bytes;
 use crate::models::YPBankBinFormat;
 use crate::models::{TxStatus, TxType};
-use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use std::io::{self, BufReader, BufWriter, ErrorKind, Read, Seek, SeekFrom, Write};
 
 const MAGIC_SIZE: usize = 4;
-const MAGIC: [u8; 4] = [0x59, 0x50, 0x42, 0x4E];
+/// Идентификатор записи по умолчанию (`YPBN`).
+///
+/// Собственный идентификатор можно задать через [`YPBankBinFormat::read_from_with_magic`] и
+/// [`YPBankBinFormat::write_to_with_magic`].
+pub const MAGIC: [u8; 4] = [0x59, 0x50, 0x42, 0x4E];
+
+/// Идентификатор записи с контрольной суммой CRC32 после тела (`YPBC`).
+pub const MAGIC_CRC: [u8; 4] = [0x59, 0x50, 0x42, 0x43];
+
+/// Размер контрольной суммы CRC32 в байтах.
+pub const CRC_SIZE: usize = 4;
+
+/// Старшая поддерживаемая версия формата в заголовке файла. Версия 2 допускает описания
+/// в base64, см. [`DESC_BASE64_FLAG`].
+pub const FORMAT_VERSION: u16 = 2;
+
+/// Версия формата файлов, все описания которых записаны в UTF-8.
+const PLAIN_FORMAT_VERSION: u16 = 1;
+
+/// Старший бит `DESC_LEN`: описание записано в base64, а остальные биты задают длину текста
+/// base64 в байтах.
+pub const DESC_BASE64_FLAG: u32 = 0x8000_0000;
+
+/// Размер заголовка файла (`MAGIC`, версия `u16` и число записей `u64`) в байтах.
+pub const HEADER_SIZE: usize = MAGIC_SIZE + 2 + 8;
+
+/// Размер фиксированной части тела записи (от `TX_ID` до `DESC_LEN` включительно) в байтах.
+pub const FIXED_BODY_SIZE: usize = 46;
+
+/// Порядок байтов в многобайтовом поле.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endianness {
+    /// Старший байт первым (Big Endian).
+    Big,
+}
+
+/// Знаковость числового поля.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Signedness {
+    /// Знаковое целое (дополнительный код).
+    Signed,
+    /// Беззнаковое целое.
+    Unsigned,
+}
+
+/// Описание расположения одного поля в теле бинарной записи.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldLayout {
+    /// Имя поля в верхнем регистре.
+    pub name: &'static str,
+    /// Смещение от начала тела записи (после `MAGIC` и `RECORD_SIZE`) в байтах.
+    pub offset: usize,
+    /// Размер поля в байтах. `None` для поля переменной длины (`DESCRIPTION`, длина задаётся
+    /// полем `DESC_LEN`).
+    pub size: Option<usize>,
+    /// Порядок байтов. `None` для однобайтовых полей и строки описания.
+    pub endianness: Option<Endianness>,
+    /// Знаковость. `None` для строки описания (UTF-8).
+    pub signedness: Option<Signedness>,
+}
 
 impl YPBankBinFormat {
+    /// Описание побайтовой структуры тела записи в порядке следования полей.
+    ///
+    /// Предназначено для генерации парсеров на других языках: описание формируется из того же
+    /// кода, что и чтение/запись, и не расходится с ним.
+    ///
+    /// ## Пример
+    ///
+    /// ```
+    /// use parser::models::YPBankBinFormat;
+    ///
+    /// for field in YPBankBinFormat::layout_spec() {
+    ///     println!("{} @ {}: {:?}", field.name, field.offset, field.size);
+    /// }
+    /// ```
+    pub fn layout_spec() -> Vec<FieldLayout> {
+        use Signedness::{Signed, Unsigned};
+
+        let fields: [(&'static str, Option<usize>, Option<Signedness>); 9] = [
+            ("TX_ID", Some(8), Some(Unsigned)),
+            ("TX_TYPE", Some(1), Some(Unsigned)),
+            ("FROM_USER_ID", Some(8), Some(Unsigned)),
+            ("TO_USER_ID", Some(8), Some(Unsigned)),
+            ("AMOUNT", Some(8), Some(Signed)),
+            ("TIMESTAMP", Some(8), Some(Unsigned)),
+            ("STATUS", Some(1), Some(Unsigned)),
+            ("DESC_LEN", Some(4), Some(Unsigned)),
+            ("DESCRIPTION", None, None),
+        ];
+
+        let mut offset = 0;
+        fields
+            .into_iter()
+            .map(|(name, size, signedness)| {
+                let layout = FieldLayout {
+                    name,
+                    offset,
+                    size,
+                    endianness: size.filter(|&s| s > 1).map(|_| Endianness::Big),
+                    signedness,
+                };
+                offset += size.unwrap_or(0);
+                layout
+            })
+            .collect()
+    }
+
     /// Чтение данных в бинарном формате.
     pub fn read_from<R: Read>(reader: &mut R) -> Result<Vec<Self>, ParseError> {
-        let mut records: Vec<Self> = Vec::new();
+        Self::read_from_with_magic(reader, MAGIC)
+    }
+
+    /// Чтение данных в бинарном формате, но не более `max_records` записей.
+    ///
+    /// Чтение прерывается с ошибкой [`ParseError::RecordLimitExceeded`], как только в потоке
+    /// обнаруживается запись сверх лимита. `None` снимает ограничение.
+    pub fn read_from_limited<R: Read>(
+        reader: &mut R,
+        max_records: Option<usize>,
+    ) -> Result<Vec<Self>, ParseError> {
+        Self::read_records(reader, MAGIC, max_records)
+    }
+
+    /// Чтение данных в бинарном формате с собственным идентификатором записи `magic`.
+    ///
+    /// Записи с любым другим идентификатором (в том числе [`MAGIC`] по умолчанию) отклоняются с
+    /// ошибкой [`ParseError::ParseError`]. Записи после заголовка файла (см.
+    /// [`YPBankBinFormat::write_to_with_magic`]) содержат контрольную сумму, она проверяется;
+    /// файлы без заголовка, записанные прежними версиями, читаются без неё.
+    pub fn read_from_with_magic<R: Read>(
+        reader: &mut R,
+        magic: [u8; 4],
+    ) -> Result<Vec<Self>, ParseError> {
+        Self::read_records(reader, magic, None)
+    }
+
+    /// Потоковое чтение данных в бинарном формате: каждый вызов `next()` читает одну запись
+    /// (`MAGIC`, `RECORD_SIZE` и тело).
+    ///
+    /// Конец данных на месте `MAGIC` завершает итерацию без ошибки, как и в
+    /// [`YPBankBinFormat::read_from`]. Любая ошибка (в том числе некорректный `MAGIC`)
+    /// возвращается элементом `Err`, после чего итератор больше ничего не выдаёт. Ограничение
+    /// [`MAX_SIZE_BIN_BYTES`] применяется к каждой записи отдельно, а не к потоку целиком.
+    ///
+    /// ## Пример
+    ///
+    /// ```no_run
+    /// use std::fs::File;
+    /// use parser::models::YPBankBinFormat;
+    ///
+    /// let file = File::open("data.bin").unwrap();
+    /// for record in YPBankBinFormat::read_iter(file) {
+    ///     println!("{}", record.unwrap().tx_id);
+    /// }
+    /// ```
+    pub fn read_iter<R: Read>(reader: R) -> impl Iterator<Item = Result<Self, ParseError>> {
+        BinRecordIter {
+            reader: PeekReader::new(BufReader::new(reader)),
+            offset: 0,
+            segment: None,
+            finished: false,
+        }
+    }
+
+    /// Чтение данных в бинарном формате с восстановлением после повреждений.
+    ///
+    /// При некорректном `MAGIC` или теле записи (в том числе при несовпадении контрольной суммы)
+    /// поток просматривается побайтово до следующего [`MAGIC`] или [`MAGIC_CRC`], и разбор
+    /// продолжается с него. Каждый пропущенный участок возвращается ошибкой
+    /// [`ParseError::ParseBinaryError`] со смещением и длиной. Заголовки файла пропускаются,
+    /// объявленное в них число записей не проверяется.
+    ///
+    /// Поток читается в память целиком; ошибка чтения возвращается единственной ошибкой без
+    /// записей.
+    ///
+    /// ## Пример
+    ///
+    /// ```no_run
+    /// use std::fs::File;
+    /// use parser::models::YPBankBinFormat;
+    ///
+    /// let mut file = File::open("data.bin").unwrap();
+    /// let (records, errors) = YPBankBinFormat::read_from_lossy(&mut file);
+    /// for error in errors {
+    ///     eprintln!("{error}");
+    /// }
+    /// ```
+    pub fn read_from_lossy<R: Read>(reader: &mut R) -> (Vec<Self>, Vec<ParseError>) {
+        let mut data = Vec::new();
+        if let Err(e) = reader.read_to_end(&mut data) {
+            return (
+                Vec::new(),
+                vec![ParseError::io_error(e, "Ошибка чтения бинарного файла")],
+            );
+        }
+
+        let mut records = Vec::new();
+        let mut errors = Vec::new();
+        let mut offset = 0;
+        while offset < data.len() {
+            let rest = &data[offset..];
+            if parse_file_header(&rest[..rest.len().min(HEADER_SIZE + MAGIC_SIZE)], MAGIC).is_some()
+            {
+                offset += HEADER_SIZE;
+                continue;
+            }
+
+            match Self::decode_at(rest, offset) {
+                Ok((record, size)) => {
+                    records.push(record);
+                    offset += size;
+                }
+                Err(err) => {
+                    let skipped = (1..rest.len())
+                        .find(|&i| {
+                            rest[i..].starts_with(&MAGIC) || rest[i..].starts_with(&MAGIC_CRC)
+                        })
+                        .unwrap_or(rest.len());
+                    errors.push(ParseError::parse_bin_error(format!(
+                        "Пропущено {skipped} байт со смещением {offset}: {err}"
+                    )));
+                    offset += skipped;
+                }
+            }
+        }
+
+        (records, errors)
+    }
+
+    /// Разбирает запись в начале `data`. Возвращает запись и её размер вместе с `MAGIC` и
+    /// `RECORD_SIZE`. `offset` — смещение начала записи в потоке для сообщения об ошибке.
+    fn decode_at(data: &[u8], offset: usize) -> Result<(Self, usize), ParseError> {
+        let truncated = || ParseError::parse_bin_error("Запись оборвана");
+
+        let magic = data.get(..MAGIC_SIZE).ok_or_else(truncated)?;
+        let mut magic_buf = [0u8; MAGIC_SIZE];
+        magic_buf.copy_from_slice(magic);
+        let has_crc = check_magic(magic_buf, MAGIC)?;
+
+        let mut size_buf = [0u8; 4];
+        size_buf.copy_from_slice(data.get(MAGIC_SIZE..MAGIC_SIZE + 4).ok_or_else(truncated)?);
+        let record_size = u32::from_be_bytes(size_buf) as usize;
+        validate_exceed_max_bytes(record_size, MAX_SIZE_BIN_BYTES)?;
+
+        let end = MAGIC_SIZE + 4 + record_size;
+        let payload = data.get(MAGIC_SIZE + 4..end).ok_or_else(truncated)?;
+        let record = Self::decode_payload(payload, has_crc, offset)?;
+
+        Ok((record, end))
+    }
+
+    /// Чтение записей с `TIMESTAMP` в полуинтервале `[start, end)`.
+    ///
+    /// Предполагается, что записи упорядочены по `TIMESTAMP`. Сначала строится индекс границ
+    /// записей: из каждой записи считывается только заголовок и метка времени, остальное тело
+    /// пропускается. Затем границы диапазона находятся двоичным поиском, и полностью
+    /// декодируются только попавшие в него записи.
+    ///
+    /// ## Пример
+    ///
+    /// ```no_run
+    /// use std::fs::File;
+    /// use parser::models::YPBankBinFormat;
+    ///
+    /// let mut file = File::open("data.bin").unwrap();
+    /// let day = YPBankBinFormat::read_time_range(&mut file, 1633046400, 1633132800).unwrap();
+    /// ```
+    pub fn read_time_range<R: Read + Seek>(
+        reader: &mut R,
+        start: u64,
+        end: u64,
+    ) -> Result<Vec<Self>, ParseError> {
+        let index = Self::timestamp_index(reader)?;
+
+        let first = index.partition_point(|&(_, timestamp)| timestamp < start);
+        let last = index.partition_point(|&(_, timestamp)| timestamp < end);
+        if first >= last {
+            return Ok(Vec::new());
+        }
+
+        let mut position = index[first].0;
+        reader.seek(SeekFrom::Start(position))?;
+        let mut buf_reader = BufReader::new(reader);
+        index[first..last]
+            .iter()
+            .map(|&(offset, _)| {
+                // Между записями склеенных файлов находится заголовок: переходим к началу
+                // записи по индексу.
+                buf_reader.seek_relative((offset - position) as i64)?;
+                let mut magic_buf = [0u8; MAGIC_SIZE];
+                buf_reader.read_exact(&mut magic_buf)?;
+                let has_crc = check_magic(magic_buf, MAGIC)?;
+                let (record, read) =
+                    Self::read_executor(&mut buf_reader, 0, has_crc, offset as usize)?;
+                position = offset + (MAGIC_SIZE + read) as u64;
+                Ok(record)
+            })
+            .collect()
+    }
+
+    /// Строит индекс записей: смещение начала записи в потоке и её `TIMESTAMP`.
+    fn timestamp_index<R: Read + Seek>(reader: &mut R) -> Result<Vec<(u64, u64)>, ParseError> {
+        /// Смещение `TIMESTAMP` в теле записи (см. [`YPBankBinFormat::layout_spec`]).
+        const TIMESTAMP_OFFSET: usize = 33;
+
+        let mut index = Vec::new();
+        let mut offset = reader.stream_position()?;
         let mut buf_reader = BufReader::new(reader);
+        let mut magic_buf = [0u8; MAGIC_SIZE];
+        loop {
+            match buf_reader.read_exact(&mut magic_buf) {
+                Ok(_) => {}
+                Err(ref e) if e.kind() == ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(ParseError::io_error(e, "Ошибка чтения бинарного файла")),
+            }
+
+            if magic_buf == MAGIC {
+                let mut prefix = [0u8; HEADER_SIZE + MAGIC_SIZE];
+                prefix[..MAGIC_SIZE].copy_from_slice(&magic_buf);
+                let read = Self::read_up_to(&mut buf_reader, &mut prefix[MAGIC_SIZE..])?;
+                let is_header = parse_file_header(&prefix[..MAGIC_SIZE + read], MAGIC).is_some();
+                // Возвращаемся к концу заголовка файла либо к RECORD_SIZE записи.
+                let consumed = if is_header {
+                    HEADER_SIZE - MAGIC_SIZE
+                } else {
+                    0
+                };
+                buf_reader.seek_relative(consumed as i64 - read as i64)?;
+                if is_header {
+                    offset += HEADER_SIZE as u64;
+                    continue;
+                }
+            }
+            check_magic(magic_buf, MAGIC)?;
+
+            let record_size = Self::read_u32be(&mut buf_reader)? as usize;
+            if record_size < FIXED_BODY_SIZE {
+                return Err(ParseError::parse_bin_error(
+                    "Размер записи меньше минимального",
+                ));
+            }
+
+            buf_reader.seek_relative(TIMESTAMP_OFFSET as i64)?;
+            let timestamp = Self::read_u64_be(&mut buf_reader)?;
+            buf_reader.seek_relative((record_size - TIMESTAMP_OFFSET - 8) as i64)?;
+
+            index.push((offset, timestamp));
+            offset += (MAGIC_SIZE + 4 + record_size) as u64;
+        }
+
+        Ok(index)
+    }
+
+    /// Проверяет, что поток завершается на границе записи (запись не оборвана).
+    ///
+    /// Тела записей не разбираются: после заголовка (`MAGIC` и `RECORD_SIZE`) тело пропускается
+    /// целиком. Некорректный `MAGIC` — это повреждение, а не обрыв, и приводит к ошибке.
+    /// Если записей меньше, чем объявлено в заголовке файла, файл считается оборванным.
+    pub(crate) fn is_complete<R: Read>(reader: &mut R) -> Result<bool, ParseError> {
+        let mut buf_reader = PeekReader::new(BufReader::new(reader));
+        let mut header = [0u8; MAGIC_SIZE + 4];
+        // Объявленное в последнем заголовке файла число записей и число записей после него.
+        let mut segment: Option<(u64, usize)> = None;
+        loop {
+            while let Some((_, declared)) = read_file_header(&mut buf_reader, MAGIC)? {
+                if let Some((previous, read)) = segment {
+                    check_record_count(previous, read)?;
+                }
+                segment = Some((declared, 0));
+            }
+
+            let header_bytes = Self::read_up_to(&mut buf_reader, &mut header)?;
+            if header_bytes == 0 {
+                return match segment {
+                    Some((declared, read)) if (read as u64) < declared => Ok(false),
+                    Some((declared, read)) => check_record_count(declared, read).map(|_| true),
+                    None => Ok(true),
+                };
+            }
+            if header_bytes < header.len() {
+                return Ok(false);
+            }
+
+            let mut magic_buf = [0u8; MAGIC_SIZE];
+            magic_buf.copy_from_slice(&header[..MAGIC_SIZE]);
+            check_magic(magic_buf, MAGIC)?;
+
+            let mut size_buf = [0u8; 4];
+            size_buf.copy_from_slice(&header[MAGIC_SIZE..]);
+            let record_size = u64::from(u32::from_be_bytes(size_buf));
+            let skipped = io::copy(&mut (&mut buf_reader).take(record_size), &mut io::sink())?;
+            if skipped < record_size {
+                return Ok(false);
+            }
+            if let Some((_, read)) = &mut segment {
+                *read += 1;
+            }
+        }
+    }
+
+    /// Читает в `buf` до его заполнения или до конца потока. Возвращает число прочитанных байт.
+    fn read_up_to<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<usize, ParseError> {
+        let mut filled = 0;
+        while filled < buf.len() {
+            match reader.read(&mut buf[filled..]) {
+                Ok(0) => break,
+                Ok(n) => filled += n,
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(ParseError::io_error(e, "Ошибка чтения бинарного файла")),
+            }
+        }
+
+        Ok(filled)
+    }
+
+    /// Общий цикл чтения записей с проверкой идентификатора и лимита количества записей.
+    fn read_records<R: Read>(
+        reader: &mut R,
+        magic: [u8; 4],
+        max_records: Option<usize>,
+    ) -> Result<Vec<Self>, ParseError> {
+        let mut records: Vec<Self> = Vec::new();
+        let mut buf_reader = PeekReader::new(BufReader::new(reader));
         let mut total_read_bytes: usize = 0;
+        let mut header_bytes: usize = 0;
+        // Объявленное в последнем заголовке число записей и число записей до него.
+        let mut segment: Option<(u64, usize)> = None;
 
         let mut magic_buf = [0u8; MAGIC_SIZE];
         loop {
+            while let Some((_, declared)) = read_file_header(&mut buf_reader, magic)? {
+                if let Some((previous, start)) = segment {
+                    check_record_count(previous, records.len() - start)?;
+                }
+                segment = Some((declared, records.len()));
+                header_bytes += HEADER_SIZE;
+            }
+
             match buf_reader.read_exact(&mut magic_buf) {
                 Ok(_) => {}
                 Err(ref e) if e.kind() == ErrorKind::UnexpectedEof => {
@@ -47,32 +531,38 @@ impl YPBankBinFormat {
                 Err(e) => return Err(ParseError::io_error(e, "Ошибка чтения бинарного файла")),
             }
 
-            if magic_buf != MAGIC {
-                return Err(ParseError::parse_err(
-                    format!(
-                        "Некорректный идентификатор Magic: {:?} (ожидается: {:?})",
-                        magic_buf, MAGIC
-                    ),
-                    0,
-                    0,
-                ));
+            if let Some(limit) = max_records.filter(|&limit| records.len() >= limit) {
+                return Err(ParseError::record_limit(limit));
             }
 
-            let record = Self::read_executor(&mut buf_reader, total_read_bytes)?;
+            // Записи с собственным идентификатором после заголовка файла содержат CRC32.
+            let has_crc = check_magic(magic_buf, magic)? || (magic != MAGIC && segment.is_some());
+            // total_read_bytes учитывает RECORD_SIZE и тело, но не MAGIC и заголовки файла.
+            let offset = total_read_bytes + MAGIC_SIZE * records.len() + header_bytes;
+            let record = Self::read_executor(&mut buf_reader, total_read_bytes, has_crc, offset)?;
             records.push(record.0);
-            total_read_bytes += record.1;
+            total_read_bytes = record.1;
+        }
+
+        if let Some((declared, start)) = segment {
+            check_record_count(declared, records.len() - start)?;
         }
 
         Ok(records)
     }
 
-    /// Читает одну запись из потока.
+    /// Читает одну запись из потока (после `MAGIC`).
+    ///
+    /// Если `has_crc`, последние [`CRC_SIZE`] байт записи — контрольная сумма тела, она
+    /// проверяется. `offset` — смещение начала записи в потоке для сообщения об ошибке.
     ///
     /// Возвращает экземпляр записи в структуре [`YPBankBinFormat`] и число
     /// считанных байт из входного потока.
     fn read_executor<R: Read>(
         reader: &mut R,
         total_read_bytes: usize,
+        has_crc: bool,
+        offset: usize,
     ) -> Result<(Self, usize), ParseError> {
         let record_size = Self::read_u32be(reader)?;
         let record_size = record_size as usize;
@@ -83,74 +573,192 @@ impl YPBankBinFormat {
 
         validate_exceed_max_bytes(current_bytes, MAX_SIZE_BIN_BYTES)?;
 
-        let mut body = vec![0u8; record_size];
-        reader.read_exact(&mut body)?;
-        let mut cursor = &body[..];
-        let record = Self::new_from_cursor(&mut cursor)?;
+        let mut payload = vec![0u8; record_size];
+        reader.read_exact(&mut payload)?;
+        let record = Self::decode_payload(&payload, has_crc, offset)?;
 
         Ok((record, current_bytes))
     }
 
-    /// Запись данных в бинарном формате.
-    pub fn write_to<W: Write>(mut writer: W, records: &[Self]) -> Result<(), ParseError> {
+    /// Разбирает тело записи, при `has_crc` предварительно сверяя контрольную сумму.
+    fn decode_payload(payload: &[u8], has_crc: bool, offset: usize) -> Result<Self, ParseError> {
+        let body = if has_crc {
+            let body_len = payload.len().checked_sub(CRC_SIZE).ok_or_else(|| {
+                ParseError::parse_bin_error("Размер записи меньше контрольной суммы")
+            })?;
+            let (body, footer) = payload.split_at(body_len);
+            let mut crc_buf = [0u8; CRC_SIZE];
+            crc_buf.copy_from_slice(footer);
+            let expected = u32::from_be_bytes(crc_buf);
+            let actual = crc32(body);
+            if expected != actual {
+                return Err(ParseError::parse_bin_error(format!(
+                    "Несовпадение CRC32 записи со смещением {offset}: \
+                     ожидается {expected:#010x}, вычислено {actual:#010x}"
+                )));
+            }
+            body
+        } else {
+            payload
+        };
+
+        Self::new_from_cursor(&mut &body[..])
+    }
+
+    /// Запись данных в бинарном формате: заголовок файла и записи с контрольной суммой каждой
+    /// записи ([`MAGIC_CRC`]).
+    pub fn write_to<W: Write>(writer: W, records: &[Self]) -> Result<(), ParseError> {
+        let mut buf_writer = BufWriter::new(writer);
+        let version = Self::header_version(records);
+        buf_writer.write_all(&encode_file_header(version, records.len() as u64))?;
+        Self::encode_all(buf_writer, records, MAGIC_CRC)
+    }
+
+    /// Запись данных в бинарном формате с собственным идентификатором записи `magic`.
+    ///
+    /// С собственным идентификатором файл, как и в [`YPBankBinFormat::write_to`], начинается
+    /// заголовком, а каждая запись содержит CRC32: заголовок служит признаком контрольной суммы
+    /// для [`YPBankBinFormat::read_from_with_magic`]. С [`MAGIC`] и [`MAGIC_CRC`] записи
+    /// пишутся без заголовка файла, как в прежних версиях, и контрольная сумма добавляется
+    /// только для [`MAGIC_CRC`]. Без заголовка файла описания в base64 не допускаются.
+    pub fn write_to_with_magic<W: Write>(
+        writer: W,
+        records: &[Self],
+        magic: [u8; 4],
+    ) -> Result<(), ParseError> {
+        let mut buf_writer = BufWriter::new(writer);
+        let version = Self::header_version(records);
+        if magic != MAGIC && magic != MAGIC_CRC {
+            buf_writer.write_all(&encode_file_header(version, records.len() as u64))?;
+        } else if version != PLAIN_FORMAT_VERSION {
+            return Err(ParseError::parse_bin_error(
+                "Описание в base64 требует заголовка файла версии 2",
+            ));
+        }
+
+        Self::encode_all(buf_writer, records, magic)
+    }
+
+    /// Версия заголовка файла для `records`: [`FORMAT_VERSION`], если хотя бы одно описание
+    /// пишется в base64, иначе версия 1, читаемая и прежними версиями.
+    fn header_version(records: &[Self]) -> u16 {
+        if records.iter().any(|r| r.raw_description.is_some()) {
+            FORMAT_VERSION
+        } else {
+            PLAIN_FORMAT_VERSION
+        }
+    }
+
+    /// Кодирует и пишет записи с идентификатором `magic` без заголовка файла.
+    fn encode_all<W: Write>(
+        mut buf_writer: W,
+        records: &[Self],
+        magic: [u8; 4],
+    ) -> Result<(), ParseError> {
         for record in records {
-            // TX_ID
-            let mut body = Vec::new();
-            body.extend(record.tx_id.to_be_bytes());
-
-            // TX_TYPE
-            let tx_type_byte = record.tx_type.clone().as_u8();
-            body.push(tx_type_byte);
-
-            // FROM_USER
-            let from_user = match record.tx_type {
-                TxType::Deposit => 0,
-                _ => record.from_user_id,
-            };
-            body.extend(from_user.to_be_bytes());
-
-            // TO_USER
-            let to_user = match record.tx_type {
-                TxType::Withdrawal => 0,
-                _ => record.to_user_id,
-            };
-            body.extend(to_user.to_be_bytes());
-
-            // AMOUNT
-            body.extend(record.amount.to_be_bytes());
-
-            // TIMESTAMP
-            body.extend(record.timestamp.to_be_bytes());
-
-            // STATUS
-            let status = record.status.clone().as_u8();
-            body.push(status);
-
-            // DESC_LEN + DESCRIPTION
-            let desc_bytes = match &record.description {
-                Some(desc) => desc.as_bytes(),
-                None => &[],
-            };
-
-            let desc_len = u32::try_from(desc_bytes.len())
-                .map_err(|_| ParseError::over_flow_size("usize", "u32", desc_bytes.len()))?;
-
-            body.extend(desc_len.to_be_bytes());
-            body.extend(desc_bytes);
-
-            let mut buf_writer = BufWriter::new(&mut writer);
-
-            // MAGIC & RECORD_SIZE
-            buf_writer.write_all(&MAGIC)?;
-            buf_writer.write_all(&(body.len() as u32).to_be_bytes())?;
-
-            // Записать всё накопленное.
-            buf_writer.write_all(&body)?;
+            buf_writer.write_all(&Self::encode_record(record, magic)?)?;
         }
 
+        buf_writer
+            .flush()
+            .map_err(|e| ParseError::io_error(e, "Ошибка записи данных"))?;
+
         Ok(())
     }
 
+    /// Кодирует одну запись в байты вместе с заголовком (`MAGIC` и `RECORD_SIZE`) и, для любого
+    /// идентификатора, кроме [`MAGIC`], контрольной суммой.
+    ///
+    /// Поле `desc_len` должно совпадать с длиной описания в байтах, а описание задано не более
+    /// чем одним из полей `description` и `raw_description`, иначе возвращается
+    /// [`ParseError::ParseBinaryError`].
+    fn encode_record(record: &Self, magic: [u8; 4]) -> Result<Vec<u8>, ParseError> {
+        // TX_ID
+        let mut body = Vec::new();
+        body.extend(record.tx_id.to_be_bytes());
+
+        // TX_TYPE
+        let tx_type_byte = record.tx_type.clone().as_u8();
+        body.push(tx_type_byte);
+
+        // FROM_USER
+        let from_user = match record.tx_type {
+            TxType::Deposit => 0,
+            _ => record.from_user_id,
+        };
+        body.extend(from_user.to_be_bytes());
+
+        // TO_USER
+        let to_user = match record.tx_type {
+            TxType::Withdrawal => 0,
+            _ => record.to_user_id,
+        };
+        body.extend(to_user.to_be_bytes());
+
+        // AMOUNT
+        body.extend(record.amount.to_be_bytes());
+
+        // TIMESTAMP
+        body.extend(record.timestamp.to_be_bytes());
+
+        // STATUS
+        let status = record.status.clone().as_u8();
+        body.push(status);
+
+        // DESC_LEN + DESCRIPTION
+        let desc_bytes = match (&record.description, &record.raw_description) {
+            (Some(_), Some(_)) => {
+                return Err(ParseError::parse_bin_error(format!(
+                    "У записи TX_ID={} заданы оба описания: description и raw_description",
+                    record.tx_id
+                )));
+            }
+            (Some(desc), None) => desc.as_bytes(),
+            (None, Some(raw)) => raw,
+            (None, None) => &[],
+        };
+
+        let desc_len = u32::try_from(desc_bytes.len())
+            .map_err(|_| ParseError::over_flow_size("usize", "u32", desc_bytes.len()))?;
+        // Расхождение означает ошибку при сборке записи: исправлять его молча нельзя.
+        if desc_len != record.desc_len {
+            return Err(ParseError::parse_bin_error(format!(
+                "DESC_LEN записи TX_ID={} равно {}, а длина описания — {} байт",
+                record.tx_id, record.desc_len, desc_len
+            )));
+        }
+
+        match &record.raw_description {
+            Some(raw) => {
+                let encoded = BASE64.encode(raw);
+                let encoded_len = u32::try_from(encoded.len())
+                    .ok()
+                    .filter(|len| len & DESC_BASE64_FLAG == 0)
+                    .ok_or_else(|| ParseError::over_flow_size("usize", "u31", encoded.len()))?;
+                body.extend((encoded_len | DESC_BASE64_FLAG).to_be_bytes());
+                body.extend(encoded.as_bytes());
+            }
+            None => {
+                body.extend(desc_len.to_be_bytes());
+                body.extend(desc_bytes);
+            }
+        }
+
+        // CRC32
+        if magic != MAGIC {
+            let crc = crc32(&body);
+            body.extend(crc.to_be_bytes());
+        }
+
+        // MAGIC & RECORD_SIZE
+        let mut encoded = Vec::with_capacity(MAGIC_SIZE + 4 + body.len());
+        encoded.extend(magic);
+        encoded.extend((body.len() as u32).to_be_bytes());
+        encoded.extend(body);
+
+        Ok(encoded)
+    }
+
     fn read_u8<R: Read>(reader: &mut R) -> Result<u8, ParseError> {
         let mut buf = [0u8; 1];
         reader
@@ -197,15 +805,19 @@ impl YPBankBinFormat {
         let status = TxStatus::from_u8(status_byte)
             .ok_or_else(|| ParseError::parse_bin_error("Некорректный TX_STATUS"))?;
         let desc_len = Self::read_u32be(cursor)?;
-        let description = if desc_len > 0 {
-            let mut desc_buf = vec![0u8; desc_len as usize];
-            cursor.read_exact(&mut desc_buf)?;
-            Some(
-                String::from_utf8(desc_buf)
-                    .map_err(|_| ParseError::parse_bin_error("Описание невалидная строка UTF-8"))?,
-            )
+        let mut desc_buf = vec![0u8; (desc_len & !DESC_BASE64_FLAG) as usize];
+        cursor.read_exact(&mut desc_buf)?;
+        let (desc_len, description, raw_description) = if desc_len & DESC_BASE64_FLAG != 0 {
+            let raw = BASE64
+                .decode(&desc_buf)
+                .map_err(|e| ParseError::parse_bin_error(format!("Описание в base64: {e}")))?;
+            (raw.len() as u32, None, Some(raw))
+        } else if desc_len > 0 {
+            let description = String::from_utf8(desc_buf)
+                .map_err(|_| ParseError::parse_bin_error("Описание невалидная строка UTF-8"))?;
+            (desc_len, Some(description), None)
         } else {
-            None
+            (0, None, None)
         };
 
         Ok(Self {
@@ -218,89 +830,472 @@ impl YPBankBinFormat {
             status,
             desc_len,
             description,
+            raw_description,
         })
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::models::{TxStatus, TxType};
-    use std::io::Cursor;
-    use std::slice::from_ref;
+/// Итератор потокового чтения, см. [`YPBankBinFormat::read_iter`].
+struct BinRecordIter<R> {
+    reader: PeekReader<BufReader<R>>,
+    /// Смещение начала следующей записи в потоке.
+    offset: usize,
+    /// Объявленное в последнем заголовке файла число записей и число прочитанных после него.
+    segment: Option<(u64, usize)>,
+    /// Итерация завершена: данные закончились или возникла ошибка.
+    finished: bool,
+}
 
-    fn create_test_record(description: Option<&str>) -> YPBankBinFormat {
-        YPBankBinFormat {
-            tx_id: 123456789,
-            tx_type: TxType::Transfer,
-            from_user_id: 1001,
-            to_user_id: 1002,
-            amount: 50000,
-            timestamp: 1633046400, // 1 Oct 2021
-            status: TxStatus::Success,
-            desc_len: description.map(|s| s.len() as u32).unwrap_or(0),
-            description: description.map(|s| s.to_string()),
+impl<R: Read> BinRecordIter<R> {
+    fn next_record(&mut self) -> Option<Result<YPBankBinFormat, ParseError>> {
+        loop {
+            match read_file_header(&mut self.reader, MAGIC) {
+                Ok(Some((_, declared))) => {
+                    if let Err(err) = self.close_segment() {
+                        return Some(Err(err));
+                    }
+                    self.segment = Some((declared, 0));
+                    self.offset += HEADER_SIZE;
+                }
+                Ok(None) => break,
+                Err(err) => return Some(Err(err)),
+            }
         }
-    }
 
-    fn create_test_large_record(size_description: usize) -> YPBankBinFormat {
-        let large_description = "A".repeat(size_description);
-        create_test_record(Some(&large_description))
+        let mut magic_buf = [0u8; MAGIC_SIZE];
+        match self.reader.read_exact(&mut magic_buf) {
+            Ok(_) => {}
+            Err(ref e) if e.kind() == ErrorKind::UnexpectedEof => {
+                return self.close_segment().err().map(Err);
+            }
+            Err(e) => {
+                return Some(Err(ParseError::io_error(
+                    e,
+                    "Ошибка чтения бинарного файла",
+                )));
+            }
+        }
+
+        let has_crc = match check_magic(magic_buf, MAGIC) {
+            Ok(has_crc) => has_crc,
+            Err(err) => return Some(Err(err)),
+        };
+
+        let offset = self.offset;
+        Some(
+            YPBankBinFormat::read_executor(&mut self.reader, 0, has_crc, offset).map(
+                |(record, read_bytes)| {
+                    self.offset += MAGIC_SIZE + read_bytes;
+                    if let Some((_, read)) = &mut self.segment {
+                        *read += 1;
+                    }
+                    record
+                },
+            ),
+        )
     }
 
-    fn create_deposit_record() -> YPBankBinFormat {
-        YPBankBinFormat {
-            tx_id: 987654321,
-            tx_type: TxType::Deposit,
-            from_user_id: 0, // will be ignored in write
-            to_user_id: 1003,
-            amount: 100000,
-            timestamp: 1633046401,
-            status: TxStatus::Pending,
-            desc_len: 0,
-            description: None,
+    /// Сверяет число записей, прочитанных после последнего заголовка файла, с объявленным.
+    fn close_segment(&mut self) -> Result<(), ParseError> {
+        match self.segment.take() {
+            Some((declared, read)) => check_record_count(declared, read),
+            None => Ok(()),
         }
     }
+}
 
-    fn create_withdrawal_record() -> YPBankBinFormat {
-        YPBankBinFormat {
-            tx_id: 555555555,
-            tx_type: TxType::Withdrawal,
-            from_user_id: 1004,
-            to_user_id: 0, // will be ignored in write
-            amount: -25000,
-            timestamp: 1633046402,
-            status: TxStatus::Failure,
-            desc_len: 10,
-            description: Some("Withdrawal".to_string()),
+impl<R: Read> Iterator for BinRecordIter<R> {
+    type Item = Result<YPBankBinFormat, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
         }
+
+        let item = self.next_record();
+        self.finished = !matches!(item, Some(Ok(_)));
+        item
     }
+}
 
-    #[test]
-    fn test_write_read_single_record() {
-        // Arrange
-        let record = create_test_record(Some("Test transaction"));
+/// Перезапись бинарного потока с точечными изменениями.
+///
+/// Каждая запись из `reader` передаётся в замыкание `f`, которое может вернуть изменённую запись,
+/// исходную без изменений, либо `None` — тогда запись будет исключена. Записи, оставшиеся без
+/// изменений, переносятся в `writer` байт-в-байт из исходного потока, без повторного
+/// кодирования. Изменённые записи кодируются заново. Заголовки файла проверяются и переносятся
+/// в `writer`, поэтому без изменений результат совпадает с исходным потоком байт-в-байт.
+///
+/// Записи не накапливаются в памяти: если записей после заголовка стало меньше, чем в нём
+/// объявлено, число записей исправляется на месте, когда известен конец части. Так же
+/// повышается до [`FORMAT_VERSION`] версия заголовка, после которого изменённая запись получила
+/// описание в base64. Поэтому `writer` должен поддерживать [`Seek`].
+///
+/// ## Пример
+///
+/// ```no_run
+/// use std::fs::File;
+/// use parser::format::bin::rewrite;
+/// use parser::models::TxStatus;
+///
+/// let mut source = File::open("data.bin").unwrap();
+/// let target = File::create("data_fixed.bin").unwrap();
+///
+/// rewrite(&mut source, target, |mut record| {
+///     if record.tx_id == 1000000000000863 {
+///         record.status = TxStatus::Failure;
+///     }
+///     Some(record)
+/// })
+/// .unwrap();
+/// ```
+pub fn rewrite<R, W, F>(reader: &mut R, writer: W, mut f: F) -> Result<(), ParseError>
+where
+    R: Read,
+    W: Write + Seek,
+    F: FnMut(YPBankBinFormat) -> Option<YPBankBinFormat>,
+{
+    let mut buf_reader = PeekReader::new(BufReader::new(reader));
+    let mut buf_writer = BufWriter::new(writer);
+    let mut offset = 0;
+    // Позиция в `writer`, с которой пишутся следующие данные.
+    let mut position = buf_writer.stream_position()?;
+    // Объявленное в последнем заголовке файла число записей и число записей после него.
+    let mut segment: Option<(u64, usize)> = None;
+    // Позиция перенесённого заголовка в `writer`, его исходные версия и число записей и
+    // фактически записанные после него.
+    let mut written: Option<(u64, FileHeader, FileHeader)> = None;
+
+    let mut magic_buf = [0u8; MAGIC_SIZE];
+    loop {
+        while let Some((version, declared)) = read_file_header(&mut buf_reader, MAGIC)? {
+            if let Some((previous, read)) = segment {
+                check_record_count(previous, read)?;
+            }
+            if let Some((header, original, actual)) = written {
+                patch_file_header(&mut buf_writer, header, original, actual, position)?;
+            }
+            buf_writer.write_all(&encode_file_header(version, declared))?;
+            written = Some((position, (version, declared), (version, 0)));
+            segment = Some((declared, 0));
+            position += HEADER_SIZE as u64;
+            offset += HEADER_SIZE;
+        }
 
-        // Act
-        let mut buffer = Vec::new();
-        YPBankBinFormat::write_to(&mut buffer, from_ref(&record)).unwrap();
-        let mut cursor = Cursor::new(buffer);
-        let result = YPBankBinFormat::read_from(&mut cursor).unwrap();
+        match buf_reader.read_exact(&mut magic_buf) {
+            Ok(_) => {}
+            Err(ref e) if e.kind() == ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(ParseError::io_error(e, "Ошибка чтения бинарного файла")),
+        }
 
-        // Assert
-        assert_eq!(result.len(), 1);
-        let read_record = &result[0];
-        assert_eq!(read_record.tx_id, record.tx_id);
-        assert_eq!(read_record.tx_type, record.tx_type);
-        assert_eq!(read_record.from_user_id, record.from_user_id);
-        assert_eq!(read_record.to_user_id, record.to_user_id);
-        assert_eq!(read_record.amount, record.amount);
-        assert_eq!(read_record.timestamp, record.timestamp);
-        assert_eq!(read_record.status, record.status);
-        assert_eq!(read_record.description, record.description);
-    }
+        let has_crc = check_magic(magic_buf, MAGIC)?;
+
+        let record_size = YPBankBinFormat::read_u32be(&mut buf_reader)?;
+        validate_exceed_max_bytes(record_size as usize, MAX_SIZE_BIN_BYTES)?;
+
+        let mut raw = Vec::with_capacity(MAGIC_SIZE + 4 + record_size as usize);
+        raw.extend(magic_buf);
+        raw.extend(record_size.to_be_bytes());
+        raw.resize(raw.len() + record_size as usize, 0);
+        buf_reader.read_exact(&mut raw[MAGIC_SIZE + 4..])?;
+
+        let record = YPBankBinFormat::decode_payload(&raw[MAGIC_SIZE + 4..], has_crc, offset)?;
+        offset += raw.len();
+        if let Some((_, read)) = &mut segment {
+            *read += 1;
+        }
+        let original = record.clone();
+
+        let output = match f(record) {
+            Some(changed) if changed == original => raw,
+            Some(changed) => {
+                // Описание в base64 допускается только под заголовком версии 2.
+                if changed.raw_description.is_some() {
+                    match &mut written {
+                        Some((_, _, (version, _))) => *version = FORMAT_VERSION,
+                        None => {
+                            return Err(ParseError::parse_bin_error(
+                                "Описание в base64 требует заголовка файла версии 2",
+                            ));
+                        }
+                    }
+                }
+                YPBankBinFormat::encode_record(&changed, magic_buf)?
+            }
+            None => continue,
+        };
+        buf_writer.write_all(&output)?;
+        position += output.len() as u64;
+        if let Some((_, _, (_, count))) = &mut written {
+            *count += 1;
+        }
+    }
+
+    if let Some((declared, read)) = segment {
+        check_record_count(declared, read)?;
+    }
+    if let Some((header, original, actual)) = written {
+        patch_file_header(&mut buf_writer, header, original, actual, position)?;
+    }
+
+    buf_writer
+        .flush()
+        .map_err(|e| ParseError::io_error(e, "Ошибка записи данных"))?;
+
+    Ok(())
+}
+
+/// Исправляет заголовок файла по позиции `header`, если фактические версия и число записей
+/// `actual` отличаются от перенесённых `original`, и возвращает позицию записи в `end`.
+fn patch_file_header<W: Write + Seek>(
+    writer: &mut W,
+    header: u64,
+    original: FileHeader,
+    actual: FileHeader,
+    end: u64,
+) -> Result<(), ParseError> {
+    if actual == original {
+        return Ok(());
+    }
+
+    writer.seek(SeekFrom::Start(header))?;
+    writer.write_all(&encode_file_header(actual.0, actual.1))?;
+    writer.seek(SeekFrom::Start(end))?;
+    Ok(())
+}
+
+/// Сверяет идентификатор записи `found` с ожидаемым `expected`.
+///
+/// Вместо [`MAGIC`] принимается и [`MAGIC_CRC`]. Возвращает `true`, если запись содержит
+/// контрольную сумму.
+fn check_magic(found: [u8; MAGIC_SIZE], expected: [u8; MAGIC_SIZE]) -> Result<bool, ParseError> {
+    if found == expected || (expected == MAGIC && found == MAGIC_CRC) {
+        return Ok(found == MAGIC_CRC);
+    }
+
+    Err(ParseError::parse_err(
+        format!(
+            "Некорректный идентификатор Magic: {:?} (ожидается: {:?})",
+            found, expected
+        ),
+        0,
+        0,
+    ))
+}
+
+/// Версия формата и число записей из заголовка файла.
+type FileHeader = (u16, u64);
+
+/// Поток с возможностью заглянуть вперёд: просмотренные байты не теряются и возвращаются
+/// следующими чтениями.
+struct PeekReader<R> {
+    inner: R,
+    peeked: Vec<u8>,
+}
+
+impl<R: Read> PeekReader<R> {
+    fn new(inner: R) -> Self {
+        Self {
+            inner,
+            peeked: Vec::new(),
+        }
+    }
+
+    /// Возвращает до `len` следующих байт потока, не извлекая их. Меньше `len` байт
+    /// возвращается только в конце данных.
+    fn peek(&mut self, len: usize) -> Result<&[u8], ParseError> {
+        if self.peeked.len() < len {
+            let filled = self.peeked.len();
+            self.peeked.resize(len, 0);
+            let read = YPBankBinFormat::read_up_to(&mut self.inner, &mut self.peeked[filled..])?;
+            self.peeked.truncate(filled + read);
+        }
+
+        Ok(&self.peeked[..len.min(self.peeked.len())])
+    }
+}
+
+impl<R: Read> Read for PeekReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.peeked.is_empty() {
+            return self.inner.read(buf);
+        }
+
+        let n = buf.len().min(self.peeked.len());
+        buf[..n].copy_from_slice(&self.peeked[..n]);
+        self.peeked.drain(..n);
+        Ok(n)
+    }
+}
+
+/// Читает заголовок файла, если поток продолжается им, и возвращает версию формата и объявленное
+/// число записей.
+///
+/// Заголовок распознаётся по [`MAGIC`], за которым следует версия из диапазона
+/// `1..=FORMAT_VERSION`, а после заголовка — конец данных или идентификатор записи
+/// ([`MAGIC`], [`MAGIC_CRC`] или собственный `record_magic`). Иначе поток не изменяется: это
+/// запись со старым [`MAGIC`] без заголовка файла.
+fn read_file_header<R: Read>(
+    reader: &mut PeekReader<R>,
+    record_magic: [u8; MAGIC_SIZE],
+) -> Result<Option<FileHeader>, ParseError> {
+    let prefix = reader.peek(HEADER_SIZE + MAGIC_SIZE)?;
+    let Some(header) = parse_file_header(prefix, record_magic) else {
+        return Ok(None);
+    };
+
+    reader.read_exact(&mut [0u8; HEADER_SIZE])?;
+    Ok(Some(header))
+}
+
+/// Разбирает заголовок файла в начале `prefix`, см. [`read_file_header`].
+fn parse_file_header(prefix: &[u8], record_magic: [u8; MAGIC_SIZE]) -> Option<FileHeader> {
+    if prefix.len() < HEADER_SIZE || prefix[..MAGIC_SIZE] != MAGIC {
+        return None;
+    }
+
+    let version = u16::from_be_bytes([prefix[MAGIC_SIZE], prefix[MAGIC_SIZE + 1]]);
+    if !(1..=FORMAT_VERSION).contains(&version) {
+        return None;
+    }
+
+    let next = &prefix[HEADER_SIZE..];
+    if !next.is_empty() && next != MAGIC && next != MAGIC_CRC && next != record_magic {
+        return None;
+    }
+
+    let mut count_buf = [0u8; 8];
+    count_buf.copy_from_slice(&prefix[MAGIC_SIZE + 2..HEADER_SIZE]);
+    Some((version, u64::from_be_bytes(count_buf)))
+}
+
+/// Кодирует заголовок файла версии `version` с числом записей `count`.
+fn encode_file_header(version: u16, count: u64) -> [u8; HEADER_SIZE] {
+    let mut header = [0u8; HEADER_SIZE];
+    header[..MAGIC_SIZE].copy_from_slice(&MAGIC);
+    header[MAGIC_SIZE..MAGIC_SIZE + 2].copy_from_slice(&version.to_be_bytes());
+    header[MAGIC_SIZE + 2..].copy_from_slice(&count.to_be_bytes());
+    header
+}
+
+/// Сверяет объявленное в заголовке файла число записей с фактически прочитанным.
+fn check_record_count(declared: u64, actual: usize) -> Result<(), ParseError> {
+    if declared == actual as u64 {
+        return Ok(());
+    }
+
+    Err(ParseError::parse_bin_error(format!(
+        "Заголовок файла объявляет записей: {declared}, прочитано: {actual}"
+    )))
+}
+
+/// Контрольная сумма CRC32 (IEEE 802.3, отражённый полином `0xEDB88320`).
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(u32::MAX, |crc, &byte| {
+        (0..8).fold(crc ^ u32::from(byte), |crc, _| {
+            (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg())
+        })
+    })
+}
+
+/// Сравнивает два бинарных потока по содержимому записей, а не по байтам.
+///
+/// Оба потока читаются [`YPBankBinFormat::read_from`], после чего записи сравниваются попарно
+/// в порядке следования: порядок и содержимое записей должны совпадать.
+///
+/// Обрамление в сравнении не участвует. Заголовки файла ([`HEADER_SIZE`] байт, в том числе
+/// склеенных частей) только проверяются при чтении, а записи с [`MAGIC`] и [`MAGIC_CRC`]
+/// равны, если совпадают их поля: контрольная сумма проверяется при чтении и не сравнивается.
+/// Поэтому файл с заголовком и без него, с CRC и без него логически равны при одинаковых записях.
+pub fn logically_equal<R: Read, S: Read>(a: &mut R, b: &mut S) -> Result<bool, ParseError> {
+    let left = YPBankBinFormat::read_from(a)?;
+    let right = YPBankBinFormat::read_from(b)?;
+
+    Ok(left == right)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{TxStatus, TxType, YPBankTransaction};
+    use std::io::Cursor;
+    use std::slice::from_ref;
+
+    fn create_test_record(description: Option<&str>) -> YPBankBinFormat {
+        YPBankBinFormat {
+            tx_id: 123456789,
+            tx_type: TxType::Transfer,
+            from_user_id: 1001,
+            to_user_id: 1002,
+            amount: 50000,
+            timestamp: 1633046400, // 1 Oct 2021
+            status: TxStatus::Success,
+            desc_len: description.map(|s| s.len() as u32).unwrap_or(0),
+            description: description.map(|s| s.to_string()),
+            raw_description: None,
+        }
+    }
+
+    fn create_test_large_record(size_description: usize) -> YPBankBinFormat {
+        let large_description = "A".repeat(size_description);
+        create_test_record(Some(&large_description))
+    }
+
+    fn create_deposit_record() -> YPBankBinFormat {
+        YPBankBinFormat {
+            tx_id: 987654321,
+            tx_type: TxType::Deposit,
+            from_user_id: 0, // will be ignored in write
+            to_user_id: 1003,
+            amount: 100000,
+            timestamp: 1633046401,
+            status: TxStatus::Pending,
+            desc_len: 0,
+            description: None,
+            raw_description: None,
+        }
+    }
+
+    fn create_withdrawal_record() -> YPBankBinFormat {
+        YPBankBinFormat {
+            tx_id: 555555555,
+            tx_type: TxType::Withdrawal,
+            from_user_id: 1004,
+            to_user_id: 0, // will be ignored in write
+            amount: -25000,
+            timestamp: 1633046402,
+            status: TxStatus::Failure,
+            desc_len: 10,
+            description: Some("Withdrawal".to_string()),
+            raw_description: None,
+        }
+    }
 
-    /// Проверка ошибки при превышении лимита входных данных.
+    #[test]
+    fn test_write_read_single_record() {
+        // Arrange
+        let record = create_test_record(Some("Test transaction"));
+
+        // Act
+        let mut buffer = Vec::new();
+        YPBankBinFormat::write_to(&mut buffer, from_ref(&record)).unwrap();
+        let mut cursor = Cursor::new(buffer);
+        let result = YPBankBinFormat::read_from(&mut cursor).unwrap();
+
+        // Assert
+        assert_eq!(result.len(), 1);
+        let read_record = &result[0];
+        assert_eq!(read_record.tx_id, record.tx_id);
+        assert_eq!(read_record.tx_type, record.tx_type);
+        assert_eq!(read_record.from_user_id, record.from_user_id);
+        assert_eq!(read_record.to_user_id, record.to_user_id);
+        assert_eq!(read_record.amount, record.amount);
+        assert_eq!(read_record.timestamp, record.timestamp);
+        assert_eq!(read_record.status, record.status);
+        assert_eq!(read_record.description, record.description);
+    }
+
+    /// Проверка ошибки при превышении лимита входных данных.
     #[test]
     fn test_read_large_record() {
         let large_record = create_test_large_record(MAX_SIZE_BIN_BYTES);
@@ -360,6 +1355,28 @@ mod tests {
         assert!(result[0].description.is_none());
     }
 
+    #[test]
+    fn test_round_trip_empty_string_description_becomes_none() {
+        // Arrange
+        let transaction = YPBankTransaction {
+            description: Some(String::new()),
+            ..YPBankTransaction::try_from(create_test_record(None)).unwrap()
+        };
+
+        // Act
+        let record = YPBankBinFormat::try_from(transaction.clone()).unwrap();
+        let mut buffer = Vec::new();
+        YPBankBinFormat::write_to(&mut buffer, from_ref(&record)).unwrap();
+        let result = YPBankBinFormat::read_from(&mut Cursor::new(buffer)).unwrap();
+        let restored = YPBankTransaction::try_from(result[0].clone()).unwrap();
+
+        // Assert
+        assert_eq!(record.description, None);
+        assert_eq!(result[0], record);
+        assert_eq!(restored.description, None);
+        assert_eq!(restored.canonical_bytes(), transaction.canonical_bytes());
+    }
+
     #[test]
     fn test_write_read_long_description() {
         // Arrange
@@ -367,6 +1384,7 @@ mod tests {
         let record = YPBankBinFormat {
             desc_len: 500,
             description: Some(long_desc.clone()),
+            raw_description: None,
             ..create_test_record(None)
         };
 
@@ -538,6 +1556,22 @@ mod tests {
         assert_eq!(result[0].to_user_id, 0); // Для withdrawal to_user должно быть 0
     }
 
+    #[test]
+    fn test_write_rejects_mismatched_desc_len() {
+        // Arrange
+        let record = YPBankBinFormat {
+            desc_len: 3,
+            ..create_test_record(Some("Test transaction"))
+        };
+        let mut buffer = Vec::new();
+
+        // Act
+        let result = YPBankBinFormat::write_to(&mut buffer, from_ref(&record));
+
+        // Assert
+        assert!(matches!(result, Err(ParseError::ParseBinaryError { .. })));
+    }
+
     #[test]
     fn test_negative_amount() {
         // Arrange
@@ -675,9 +1709,10 @@ mod tests {
         YPBankBinFormat::write_to(&mut buffer, from_ref(&deposit)).unwrap();
 
         // Проверяем, что в записанных данных from_user = 0
-        // Пропускаем magic (4) и record_size (4) = 8 байт
-        // tx_id (8) + tx_type (1) = 9 байт, from_user начинается с 17-го байта
-        let from_user_bytes = &buffer[17..25];
+        // Пропускаем заголовок файла (HEADER_SIZE), magic (4) и record_size (4) = 8 байт
+        // tx_id (8) + tx_type (1) = 9 байт, from_user начинается с 17-го байта записи
+        let record = &buffer[HEADER_SIZE..];
+        let from_user_bytes = &record[17..25];
         let from_user = u64::from_be_bytes(from_user_bytes.try_into().unwrap());
 
         // Assert
@@ -700,8 +1735,9 @@ mod tests {
 
         // Проверяем, что в записанных данных to_user = 0
         // Пропускаем: magic(4) + record_size(4) + tx_id(8) + tx_type(1) + from_user(8) = 25 байт
-        // to_user начинается с 25-го байта
-        let to_user_bytes = &buffer[25..33];
+        // to_user начинается с 25-го байта записи (после заголовка файла)
+        let record = &buffer[HEADER_SIZE..];
+        let to_user_bytes = &record[25..33];
         let to_user = u64::from_be_bytes(to_user_bytes.try_into().unwrap());
 
         // Assert
@@ -723,15 +1759,702 @@ mod tests {
         YPBankBinFormat::write_to(&mut buffer, from_ref(&transfer)).unwrap();
 
         // Проверяем from_user
-        let from_user_bytes = &buffer[17..25];
+        let record = &buffer[HEADER_SIZE..];
+        let from_user_bytes = &record[17..25];
         let from_user = u64::from_be_bytes(from_user_bytes.try_into().unwrap());
 
         // Проверяем to_user
-        let to_user_bytes = &buffer[25..33];
+        let to_user_bytes = &record[25..33];
         let to_user = u64::from_be_bytes(to_user_bytes.try_into().unwrap());
 
         // Assert
         assert_eq!(from_user, 1001);
         assert_eq!(to_user, 1002);
     }
+
+    #[test]
+    fn test_rewrite_identity_is_byte_identical() {
+        // Arrange
+        let records = vec![
+            create_test_record(Some("First")),
+            create_deposit_record(),
+            create_withdrawal_record(),
+        ];
+        let mut source = Vec::new();
+        YPBankBinFormat::write_to(&mut source, &records).unwrap();
+
+        // Act
+        let mut target = Cursor::new(Vec::new());
+        rewrite(&mut Cursor::new(source.clone()), &mut target, Some).unwrap();
+
+        // Assert
+        assert_eq!(source, target.into_inner());
+    }
+
+    #[test]
+    fn test_rewrite_changes_and_drops_records() {
+        // Arrange
+        let records = vec![
+            create_test_record(Some("First")),
+            create_deposit_record(),
+            create_withdrawal_record(),
+        ];
+        let mut source = Vec::new();
+        YPBankBinFormat::write_to(&mut source, &records).unwrap();
+
+        // Act: удаляем депозит и меняем статус у списания
+        let mut target = Cursor::new(Vec::new());
+        rewrite(
+            &mut Cursor::new(source),
+            &mut target,
+            |mut record| match record.tx_type {
+                TxType::Deposit => None,
+                TxType::Withdrawal => {
+                    record.status = TxStatus::Success;
+                    Some(record)
+                }
+                TxType::Transfer => Some(record),
+            },
+        )
+        .unwrap();
+        let target = target.into_inner();
+        let declared = parse_file_header(&target[..HEADER_SIZE + MAGIC_SIZE], MAGIC);
+        let result = YPBankBinFormat::read_from(&mut Cursor::new(target)).unwrap();
+
+        // Assert
+        assert_eq!(declared, Some((PLAIN_FORMAT_VERSION, 2)));
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0], records[0]);
+        assert_eq!(result[1].tx_type, TxType::Withdrawal);
+        assert_eq!(result[1].status, TxStatus::Success);
+    }
+
+    #[test]
+    fn test_rewrite_patches_counts_of_concatenated_parts() {
+        // Arrange
+        let mut source = Vec::new();
+        YPBankBinFormat::write_to(
+            &mut source,
+            &[create_deposit_record(), create_test_record(Some("First"))],
+        )
+        .unwrap();
+        let second_part = source.len();
+        YPBankBinFormat::write_to(
+            &mut source,
+            &[create_withdrawal_record(), create_deposit_record()],
+        )
+        .unwrap();
+
+        // Act
+        let mut target = Cursor::new(Vec::new());
+        rewrite(&mut Cursor::new(source), &mut target, |record| {
+            (record.tx_type != TxType::Deposit).then_some(record)
+        })
+        .unwrap();
+        let target = target.into_inner();
+        let first = parse_file_header(&target[..HEADER_SIZE + MAGIC_SIZE], MAGIC);
+        let dropped = YPBankBinFormat::encode_record(&create_deposit_record(), MAGIC_CRC)
+            .unwrap()
+            .len();
+        let header = second_part - dropped;
+        let second = parse_file_header(&target[header..header + HEADER_SIZE + MAGIC_SIZE], MAGIC);
+        let result = YPBankBinFormat::read_from(&mut Cursor::new(&target)).unwrap();
+
+        // Assert
+        assert_eq!(first, Some((PLAIN_FORMAT_VERSION, 1)));
+        assert_eq!(second, Some((PLAIN_FORMAT_VERSION, 1)));
+        assert_eq!(result.len(), 2);
+    }
+
+    #[test]
+    fn test_read_concatenated_streams() {
+        // Arrange
+        let mut first = Vec::new();
+        YPBankBinFormat::write_to(&mut first, &[create_test_record(Some("First"))]).unwrap();
+        let mut second = Vec::new();
+        YPBankBinFormat::write_to(
+            &mut second,
+            &[create_deposit_record(), create_withdrawal_record()],
+        )
+        .unwrap();
+
+        // Act
+        let concatenated = [first, second].concat();
+        let result = YPBankBinFormat::read_from(&mut Cursor::new(concatenated)).unwrap();
+
+        // Assert
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[0].description, Some("First".to_string()));
+        assert_eq!(result[1].tx_type, TxType::Deposit);
+        assert_eq!(result[2].tx_type, TxType::Withdrawal);
+    }
+
+    #[test]
+    fn test_custom_magic_round_trip() {
+        // Arrange
+        let custom_magic = *b"ACME";
+        let records = vec![create_test_record(Some("Branded")), create_deposit_record()];
+        let mut buffer = Vec::new();
+
+        // Act
+        YPBankBinFormat::write_to_with_magic(&mut buffer, &records, custom_magic).unwrap();
+        let result =
+            YPBankBinFormat::read_from_with_magic(&mut Cursor::new(&buffer), custom_magic).unwrap();
+
+        // Assert
+        assert_eq!(
+            parse_file_header(&buffer[..HEADER_SIZE], custom_magic),
+            Some((PLAIN_FORMAT_VERSION, 2))
+        );
+        assert_eq!(&buffer[HEADER_SIZE..HEADER_SIZE + MAGIC_SIZE], b"ACME");
+        assert_eq!(result, records);
+    }
+
+    #[test]
+    fn test_custom_magic_detects_corruption() {
+        // Arrange
+        let custom_magic = *b"ACME";
+        let records = vec![create_test_record(Some("Branded")), create_deposit_record()];
+        let mut buffer = Vec::new();
+        YPBankBinFormat::write_to_with_magic(&mut buffer, &records, custom_magic).unwrap();
+        let mut corrupted = buffer.clone();
+        // Байт TX_ID первой записи: за заголовком файла, MAGIC и RECORD_SIZE.
+        corrupted[HEADER_SIZE + MAGIC_SIZE + 4] ^= 0xFF;
+        let truncated = &buffer[..buffer.len() - 1];
+
+        // Act
+        let corrupted_result =
+            YPBankBinFormat::read_from_with_magic(&mut Cursor::new(&corrupted), custom_magic);
+        let truncated_result =
+            YPBankBinFormat::read_from_with_magic(&mut Cursor::new(truncated), custom_magic);
+
+        // Assert
+        assert!(matches!(
+            corrupted_result,
+            Err(ParseError::ParseBinaryError { .. })
+        ));
+        assert!(truncated_result.is_err());
+    }
+
+    #[test]
+    fn test_custom_magic_reads_legacy_without_header() {
+        // Arrange
+        let custom_magic = *b"ACME";
+        let records = vec![create_test_record(Some("Legacy"))];
+        let encoded = YPBankBinFormat::encode_record(&records[0], MAGIC).unwrap();
+        let mut legacy = custom_magic.to_vec();
+        legacy.extend(&encoded[MAGIC_SIZE..]);
+
+        // Act
+        let result = YPBankBinFormat::read_from_with_magic(&mut Cursor::new(legacy), custom_magic);
+
+        // Assert
+        assert_eq!(result.unwrap(), records);
+    }
+
+    #[test]
+    fn test_custom_magic_rejects_default() {
+        // Arrange
+        let mut buffer = Vec::new();
+        YPBankBinFormat::write_to(&mut buffer, &[create_test_record(None)]).unwrap();
+
+        // Act
+        let result = YPBankBinFormat::read_from_with_magic(&mut Cursor::new(&buffer), *b"ACME");
+
+        // Assert
+        assert!(matches!(result, Err(ParseError::ParseError { .. })));
+    }
+
+    #[test]
+    fn test_logically_equal_ignores_framing() {
+        // Arrange
+        let first = create_test_record(Some("First"));
+        let second = create_deposit_record();
+        let mut whole = Vec::new();
+        YPBankBinFormat::write_to(&mut whole, &[first.clone(), second.clone()]).unwrap();
+        let mut pieces = Vec::new();
+        YPBankBinFormat::write_to(&mut pieces, from_ref(&first)).unwrap();
+        YPBankBinFormat::write_to(&mut pieces, from_ref(&second)).unwrap();
+        let mut plain = Vec::new();
+        YPBankBinFormat::write_to_with_magic(&mut plain, &[first.clone(), second.clone()], MAGIC)
+            .unwrap();
+        let mut reordered = Vec::new();
+        YPBankBinFormat::write_to(&mut reordered, &[second, first]).unwrap();
+
+        // Act
+        let equal = logically_equal(&mut Cursor::new(&whole), &mut Cursor::new(&pieces)).unwrap();
+        let equal_plain =
+            logically_equal(&mut Cursor::new(&whole), &mut Cursor::new(&plain)).unwrap();
+        let not_equal =
+            logically_equal(&mut Cursor::new(&whole), &mut Cursor::new(&reordered)).unwrap();
+
+        // Assert
+        assert!(equal);
+        assert!(equal_plain);
+        assert!(!not_equal);
+    }
+
+    #[test]
+    fn test_read_from_limited() {
+        // Arrange
+        let records = vec![
+            create_test_record(None),
+            create_deposit_record(),
+            create_withdrawal_record(),
+        ];
+        let mut buffer = Vec::new();
+        YPBankBinFormat::write_to(&mut buffer, &records).unwrap();
+
+        // Act
+        let at_limit = YPBankBinFormat::read_from_limited(&mut Cursor::new(&buffer), Some(3));
+        let over_limit = YPBankBinFormat::read_from_limited(&mut Cursor::new(&buffer), Some(2));
+
+        // Assert
+        assert_eq!(at_limit.unwrap(), records);
+        assert!(matches!(
+            over_limit,
+            Err(ParseError::RecordLimitExceeded { limit: 2 })
+        ));
+    }
+
+    #[test]
+    fn test_layout_spec() {
+        // Act
+        let layout = YPBankBinFormat::layout_spec();
+
+        // Assert
+        let fixed_size: usize = layout.iter().filter_map(|f| f.size).sum();
+        assert_eq!(fixed_size, FIXED_BODY_SIZE);
+
+        let amount = layout.iter().find(|f| f.name == "AMOUNT").unwrap();
+        assert_eq!(amount.size, Some(8));
+        assert_eq!(amount.endianness, Some(Endianness::Big));
+        assert_eq!(amount.signedness, Some(Signedness::Signed));
+
+        let description = layout.last().unwrap();
+        assert_eq!(description.offset, FIXED_BODY_SIZE);
+        assert_eq!(description.size, None);
+
+        // Сверка с фактическим кодированием записи без описания.
+        let encoded = YPBankBinFormat::encode_record(&create_test_record(None), MAGIC).unwrap();
+        assert_eq!(encoded.len(), MAGIC_SIZE + 4 + FIXED_BODY_SIZE);
+    }
+
+    #[test]
+    fn test_read_time_range() {
+        // Arrange
+        let records: Vec<YPBankBinFormat> = (0..10)
+            .map(|i| {
+                let mut record = create_test_record(Some(&"x".repeat(i as usize)));
+                record.tx_id = i;
+                record.timestamp = 1000 + i * 10;
+                record
+            })
+            .collect();
+        let mut buffer = Vec::new();
+        YPBankBinFormat::write_to(&mut buffer, &records).unwrap();
+
+        // Act
+        let in_range =
+            YPBankBinFormat::read_time_range(&mut Cursor::new(&buffer), 1025, 1060).unwrap();
+        let empty =
+            YPBankBinFormat::read_time_range(&mut Cursor::new(&buffer), 2000, 3000).unwrap();
+
+        // Assert
+        assert_eq!(in_range, records[3..6]);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_read_time_range_across_concatenated_files() {
+        // Arrange
+        let records: Vec<YPBankBinFormat> = (0..6)
+            .map(|i| {
+                let mut record = create_test_record(Some("Range"));
+                record.tx_id = i;
+                record.timestamp = 1000 + i * 10;
+                record
+            })
+            .collect();
+        let mut first = Vec::new();
+        YPBankBinFormat::write_to(&mut first, &records[..3]).unwrap();
+        let mut second = Vec::new();
+        YPBankBinFormat::write_to(&mut second, &records[3..]).unwrap();
+        let concatenated = [first, second].concat();
+
+        // Act
+        let in_range =
+            YPBankBinFormat::read_time_range(&mut Cursor::new(&concatenated), 1015, 1045).unwrap();
+
+        // Assert
+        assert_eq!(in_range, records[2..5]);
+    }
+
+    /// Считает обращения к исходному потоку.
+    struct CountingReader<R> {
+        inner: R,
+        reads: usize,
+    }
+
+    impl<R: Read> Read for CountingReader<R> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.reads += 1;
+            self.inner.read(buf)
+        }
+    }
+
+    #[test]
+    fn test_read_from_batches_underlying_reads() {
+        // Arrange
+        let records: Vec<YPBankBinFormat> = (0..100).map(|_| create_test_record(None)).collect();
+        let mut buffer = Vec::new();
+        YPBankBinFormat::write_to(&mut buffer, &records).unwrap();
+        let mut reader = CountingReader {
+            inner: Cursor::new(&buffer),
+            reads: 0,
+        };
+
+        // Act
+        let result = YPBankBinFormat::read_from(&mut reader).unwrap();
+
+        // Assert
+        // Без буферизации потребовалось бы не менее трёх чтений на запись.
+        assert_eq!(result, records);
+        assert!(reader.reads < records.len(), "reads: {}", reader.reads);
+    }
+
+    #[test]
+    fn test_read_iter_yields_records_until_eof() {
+        // Arrange
+        let records = vec![create_test_record(Some("First")), create_test_record(None)];
+        let mut buffer = Vec::new();
+        YPBankBinFormat::write_to(&mut buffer, &records).unwrap();
+
+        // Act
+        let mut iter = YPBankBinFormat::read_iter(Cursor::new(buffer));
+
+        // Assert
+        assert_eq!(iter.next().unwrap().unwrap(), records[0]);
+        assert_eq!(iter.next().unwrap().unwrap(), records[1]);
+        assert!(iter.next().is_none());
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_read_iter_invalid_magic_mid_stream_fuses() {
+        // Arrange
+        let records = vec![create_test_record(Some("First"))];
+        let mut buffer = Vec::new();
+        YPBankBinFormat::write_to(&mut buffer, &records).unwrap();
+        let valid_len = buffer.len();
+        buffer.extend_from_within(..valid_len);
+        buffer[valid_len] = 0x00;
+        buffer.extend_from_within(..valid_len);
+
+        // Act
+        let result: Vec<_> = YPBankBinFormat::read_iter(Cursor::new(buffer)).collect();
+
+        // Assert
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].as_ref().unwrap(), &records[0]);
+        assert!(matches!(
+            &result[1],
+            Err(ParseError::ParseError { message, .. }) if message.contains("Magic")
+        ));
+    }
+
+    #[test]
+    fn test_crc32_known_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn test_crc_detects_flipped_body_byte() {
+        // Arrange
+        let records = vec![create_test_record(Some("First")), create_test_record(None)];
+        let mut buffer = Vec::new();
+        YPBankBinFormat::write_to(&mut buffer, &records).unwrap();
+        assert_eq!(buffer[HEADER_SIZE..HEADER_SIZE + MAGIC_SIZE], MAGIC_CRC);
+        // Портим второй байт TX_ID во второй записи.
+        let first_len = HEADER_SIZE + MAGIC_SIZE + 4 + FIXED_BODY_SIZE + "First".len() + CRC_SIZE;
+        buffer[first_len + MAGIC_SIZE + 4 + 1] ^= 0xFF;
+
+        // Act
+        let result = YPBankBinFormat::read_from(&mut Cursor::new(&buffer));
+        let streamed: Vec<_> = YPBankBinFormat::read_iter(Cursor::new(&buffer)).collect();
+
+        // Assert
+        assert!(matches!(
+            &result,
+            Err(ParseError::ParseBinaryError { message })
+                if message.contains("CRC32") && message.contains(&format!("смещением {first_len}"))
+        ));
+        assert!(streamed[0].is_ok());
+        assert!(matches!(
+            &streamed[1],
+            Err(ParseError::ParseBinaryError { message }) if message.contains("CRC32")
+        ));
+    }
+
+    #[test]
+    fn test_legacy_magic_without_crc_still_parses() {
+        // Arrange
+        let records = vec![create_test_record(Some("Legacy")), create_test_record(None)];
+        let mut legacy = Vec::new();
+        YPBankBinFormat::write_to_with_magic(&mut legacy, &records, MAGIC).unwrap();
+        let mut current = Vec::new();
+        YPBankBinFormat::write_to(&mut current, &records).unwrap();
+
+        // Act
+        let mut mixed = legacy.clone();
+        mixed.extend_from_slice(&current);
+        let result = YPBankBinFormat::read_from(&mut Cursor::new(&mixed)).unwrap();
+
+        // Assert
+        assert_eq!(
+            current.len(),
+            HEADER_SIZE + legacy.len() + records.len() * CRC_SIZE
+        );
+        assert_eq!(result.len(), 4);
+        assert_eq!(result[..2], records[..]);
+        assert_eq!(result[2..], records[..]);
+    }
+
+    #[test]
+    fn test_write_to_emits_file_header() {
+        // Arrange
+        let records = vec![create_test_record(None), create_deposit_record()];
+
+        // Act
+        let mut buffer = Vec::new();
+        YPBankBinFormat::write_to(&mut buffer, &records).unwrap();
+
+        // Assert
+        assert_eq!(buffer[..MAGIC_SIZE], MAGIC);
+        assert_eq!(
+            buffer[MAGIC_SIZE..MAGIC_SIZE + 2],
+            PLAIN_FORMAT_VERSION.to_be_bytes()
+        );
+        assert_eq!(buffer[MAGIC_SIZE + 2..HEADER_SIZE], 2u64.to_be_bytes());
+        assert_eq!(
+            YPBankBinFormat::read_from(&mut Cursor::new(&buffer)).unwrap(),
+            records
+        );
+    }
+
+    #[test]
+    fn test_read_truncated_file_fewer_records_than_declared() {
+        // Arrange
+        let records = vec![
+            create_test_record(Some("First")),
+            create_deposit_record(),
+            create_withdrawal_record(),
+        ];
+        let mut buffer = Vec::new();
+        YPBankBinFormat::write_to(&mut buffer, &records).unwrap();
+        // Обрыв ровно на границе записей: последняя запись отсутствует целиком.
+        let last_len = MAGIC_SIZE + 4 + FIXED_BODY_SIZE + "Withdrawal".len() + CRC_SIZE;
+        let truncated = &buffer[..buffer.len() - last_len];
+
+        // Act
+        let result = YPBankBinFormat::read_from(&mut Cursor::new(truncated));
+        let streamed: Vec<_> = YPBankBinFormat::read_iter(Cursor::new(truncated)).collect();
+        let complete = YPBankBinFormat::is_complete(&mut Cursor::new(truncated)).unwrap();
+
+        // Assert
+        assert!(matches!(
+            &result,
+            Err(ParseError::ParseBinaryError { message })
+                if message.contains("объявляет записей: 3, прочитано: 2")
+        ));
+        assert_eq!(streamed.len(), 3);
+        assert!(streamed[..2].iter().all(Result::is_ok));
+        assert!(matches!(
+            streamed[2],
+            Err(ParseError::ParseBinaryError { .. })
+        ));
+        assert!(!complete);
+    }
+
+    #[test]
+    fn test_read_header_only_file() {
+        // Arrange
+        let mut buffer = Vec::new();
+        YPBankBinFormat::write_to(&mut buffer, &[]).unwrap();
+
+        // Act
+        let result = YPBankBinFormat::read_from(&mut Cursor::new(&buffer)).unwrap();
+
+        // Assert
+        assert_eq!(buffer.len(), HEADER_SIZE);
+        assert!(result.is_empty());
+    }
+
+    #[test]
+    fn test_raw_description_base64_round_trip() {
+        // Arrange
+        let raw = vec![0xff, 0xfe, b'a'];
+        let records = vec![
+            YPBankBinFormat {
+                desc_len: raw.len() as u32,
+                raw_description: Some(raw.clone()),
+                ..create_test_record(None)
+            },
+            create_deposit_record(),
+        ];
+
+        // Act
+        let mut buffer = Vec::new();
+        YPBankBinFormat::write_to(&mut buffer, &records).unwrap();
+        let result = YPBankBinFormat::read_from(&mut Cursor::new(&buffer)).unwrap();
+        let streamed: Vec<_> = YPBankBinFormat::read_iter(Cursor::new(&buffer))
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let converted = YPBankTransaction::try_from(result[0].clone());
+
+        // Assert
+        let desc_len_at = HEADER_SIZE + MAGIC_SIZE + 4 + FIXED_BODY_SIZE - 4;
+        let mut desc_len = [0u8; 4];
+        desc_len.copy_from_slice(&buffer[desc_len_at..desc_len_at + 4]);
+        assert_eq!(
+            buffer[MAGIC_SIZE..MAGIC_SIZE + 2],
+            FORMAT_VERSION.to_be_bytes()
+        );
+        assert_eq!(u32::from_be_bytes(desc_len), DESC_BASE64_FLAG | 4);
+        assert_eq!(&buffer[desc_len_at + 4..desc_len_at + 8], b"//5h");
+        assert_eq!(result, records);
+        assert_eq!(streamed, records);
+        assert!(matches!(
+            converted,
+            Err(ParseError::ParseBinaryError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_raw_description_rejected_without_header_or_with_text() {
+        // Arrange
+        let raw = YPBankBinFormat {
+            desc_len: 2,
+            raw_description: Some(vec![0xc3, 0x28]),
+            ..create_test_record(None)
+        };
+        let both = YPBankBinFormat {
+            description: Some("ab".to_string()),
+            ..raw.clone()
+        };
+
+        // Act
+        let legacy = YPBankBinFormat::write_to_with_magic(Vec::new(), from_ref(&raw), MAGIC);
+        let conflicting = YPBankBinFormat::write_to(Vec::new(), from_ref(&both));
+
+        // Assert
+        assert!(matches!(legacy, Err(ParseError::ParseBinaryError { .. })));
+        assert!(matches!(
+            conflicting,
+            Err(ParseError::ParseBinaryError { .. })
+        ));
+    }
+
+    #[test]
+    fn test_rewrite_raises_header_version_for_raw_description() {
+        // Arrange
+        let records = vec![create_test_record(Some("Test")), create_deposit_record()];
+        let mut source = Vec::new();
+        YPBankBinFormat::write_to(&mut source, &records).unwrap();
+
+        // Act
+        let mut target = Cursor::new(Vec::new());
+        rewrite(&mut Cursor::new(&source), &mut target, |record| {
+            Some(match record.tx_type {
+                TxType::Deposit => YPBankBinFormat {
+                    desc_len: 1,
+                    raw_description: Some(vec![0x80]),
+                    ..record
+                },
+                _ => record,
+            })
+        })
+        .unwrap();
+        let target = target.into_inner();
+        let header = parse_file_header(&target[..HEADER_SIZE + MAGIC_SIZE], MAGIC);
+        let result = YPBankBinFormat::read_from(&mut Cursor::new(&target)).unwrap();
+
+        // Assert
+        assert_eq!(
+            parse_file_header(&source[..HEADER_SIZE + MAGIC_SIZE], MAGIC),
+            Some((PLAIN_FORMAT_VERSION, 2))
+        );
+        assert_eq!(header, Some((FORMAT_VERSION, 2)));
+        assert_eq!(result[0], records[0]);
+        assert_eq!(result[1].raw_description, Some(vec![0x80]));
+    }
+
+    #[test]
+    fn test_legacy_large_record_not_mistaken_for_header() {
+        // Arrange: RECORD_SIZE > 65535, поэтому его старшие два байта совпадают с версией 1.
+        let records = vec![create_test_large_record(70_000), create_deposit_record()];
+        let mut legacy = Vec::new();
+        YPBankBinFormat::write_to_with_magic(&mut legacy, &records, MAGIC).unwrap();
+        assert_eq!(
+            legacy[MAGIC_SIZE..MAGIC_SIZE + 2],
+            PLAIN_FORMAT_VERSION.to_be_bytes()
+        );
+
+        // Act
+        let result = YPBankBinFormat::read_from(&mut Cursor::new(&legacy)).unwrap();
+
+        // Assert
+        assert_eq!(result, records);
+    }
+
+    #[test]
+    fn test_read_from_lossy_resyncs_after_garbage() {
+        // Arrange
+        let first = create_test_record(Some("First"));
+        let second = create_withdrawal_record();
+        let mut buffer = Vec::new();
+        YPBankBinFormat::write_to_with_magic(&mut buffer, from_ref(&first), MAGIC_CRC).unwrap();
+        let garbage_offset = buffer.len();
+        buffer.extend_from_slice(b"\x00garbage\xFF\x01");
+        YPBankBinFormat::write_to_with_magic(&mut buffer, from_ref(&second), MAGIC_CRC).unwrap();
+
+        // Act
+        let (records, errors) = YPBankBinFormat::read_from_lossy(&mut Cursor::new(&buffer));
+
+        // Assert
+        assert_eq!(records, vec![first, second]);
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            &errors[0],
+            ParseError::ParseBinaryError { message }
+                if message.contains(&format!("Пропущено 10 байт со смещением {garbage_offset}"))
+        ));
+        assert!(YPBankBinFormat::read_from(&mut Cursor::new(&buffer)).is_err());
+    }
+
+    #[test]
+    fn test_read_from_lossy_skips_corrupted_body() {
+        // Arrange
+        let records = vec![
+            create_test_record(Some("First")),
+            create_deposit_record(),
+            create_withdrawal_record(),
+        ];
+        let mut buffer = Vec::new();
+        YPBankBinFormat::write_to(&mut buffer, &records).unwrap();
+        // Портим TX_ID во второй записи: не сойдётся контрольная сумма.
+        let first_len = MAGIC_SIZE + 4 + FIXED_BODY_SIZE + "First".len() + CRC_SIZE;
+        buffer[HEADER_SIZE + first_len + MAGIC_SIZE + 4] ^= 0xFF;
+
+        // Act
+        let (result, errors) = YPBankBinFormat::read_from_lossy(&mut Cursor::new(&buffer));
+
+        // Assert
+        assert_eq!(result, vec![records[0].clone(), records[2].clone()]);
+        assert_eq!(errors.len(), 1);
+        assert!(
+            matches!(&errors[0], ParseError::ParseBinaryError { message } if message.contains("CRC32"))
+        );
+    }
 }
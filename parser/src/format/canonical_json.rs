@@ -0,0 +1,167 @@
+//! Каноническое JSON-представление транзакций для внешнего API.
+//!
+//! Представление предназначено только для записи и следует опубликованной схеме, а не
+//! внутренним соглашениям библиотеки:
+//!
+//! * `amount` — целое число со знаком (как в [`YPBankTransaction`]);
+//! * `tx_type`, `status` — строки в нижнем регистре (`deposit`, `success`, ...);
+//! * `timestamp` — строка ISO-8601 в UTC (см. [`format_iso8601`]);
+//! * `description` — `null`, если описание отсутствует.
+//!
+//! Ключи объекта следуют в фиксированном порядке, пробелы между элементами не добавляются.
+//!
+//! ## Пример записи
+//!
+//! ```plain
+//! {"tx_id":1,"tx_type":"deposit","from_user_id":0,"to_user_id":1001,"amount":500,"timestamp":"2021-10-01T00:00:00Z","status":"success","description":null}
+//! ```
+
+use crate::errors::ParseError;
+use crate::models::YPBankTransaction;
+use crate::utils::format_iso8601;
+use std::fmt::Write as _;
+use std::io::{BufWriter, Write};
+
+/// Формирует каноническое JSON-представление одной транзакции.
+///
+/// ## Пример
+///
+/// ```
+/// use parser::format::canonical_json::to_canonical_json;
+/// use parser::models::{TxStatus, TxType, YPBankTransaction};
+///
+/// let tx = YPBankTransaction {
+///     tx_id: 1,
+///     tx_type: TxType::Withdrawal,
+///     from_user_id: 1001,
+///     to_user_id: 0,
+///     amount: -500,
+///     timestamp: 1633046400,
+///     status: TxStatus::Success,
+///     description: Some("ATM".to_string()),
+/// };
+///
+/// assert!(to_canonical_json(&tx).contains(r#""amount":-500"#));
+/// ```
+pub fn to_canonical_json(record: &YPBankTransaction) -> String {
+    let description = match &record.description {
+        Some(description) => json_string(description),
+        None => "null".to_string(),
+    };
+
+    format!(
+        "{{\"tx_id\":{},\"tx_type\":{},\"from_user_id\":{},\"to_user_id\":{},\"amount\":{},\
+         \"timestamp\":{},\"status\":{},\"description\":{}}}",
+        record.tx_id,
+        json_string(&record.tx_type.to_string().to_lowercase()),
+        record.from_user_id,
+        record.to_user_id,
+        record.amount,
+        json_string(&format_iso8601(record.timestamp)),
+        json_string(&record.status.to_string().to_lowercase()),
+        description,
+    )
+}
+
+/// Записывает транзакции JSON-массивом канонических объектов, по одному объекту в строке.
+pub fn write_canonical_json<W: Write>(
+    writer: W,
+    records: &[YPBankTransaction],
+) -> Result<(), ParseError> {
+    let mut buf_writer = BufWriter::new(writer);
+    write!(buf_writer, "[")?;
+    for (i, record) in records.iter().enumerate() {
+        let separator = if i == 0 { "" } else { "," };
+        write!(buf_writer, "{separator}\n{}", to_canonical_json(record))?;
+    }
+    writeln!(buf_writer, "\n]")?;
+    buf_writer
+        .flush()
+        .map_err(|e| ParseError::io_error(e, "Ошибка записи данных"))?;
+
+    Ok(())
+}
+
+/// Строка в кавычках с JSON-экранированием.
+fn json_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TxType;
+    use crate::testing::transaction;
+
+    #[test]
+    fn test_canonical_json_null_description() {
+        // Arrange
+        let record = transaction(1001, TxType::Transfer, -500);
+
+        // Act
+        let json = to_canonical_json(&record);
+
+        // Assert
+        assert_eq!(
+            json,
+            r#"{"tx_id":1001,"tx_type":"transfer","from_user_id":1001,"to_user_id":1002,"amount":-500,"timestamp":"2021-10-01T00:00:00Z","status":"success","description":null}"#
+        );
+    }
+
+    #[test]
+    fn test_canonical_json_escapes_description() {
+        // Arrange
+        let record = YPBankTransaction {
+            description: Some("Rent \"May\"\n\\ok\u{1}".to_string()),
+            ..transaction(1001, TxType::Transfer, -500)
+        };
+
+        // Act
+        let json = to_canonical_json(&record);
+
+        // Assert
+        assert!(json.ends_with(r#""description":"Rent \"May\"\n\\ok\u0001"}"#));
+    }
+
+    #[test]
+    fn test_write_canonical_json_array() {
+        // Arrange
+        let records = vec![
+            transaction(1001, TxType::Transfer, -500),
+            YPBankTransaction {
+                description: Some("Rent".to_string()),
+                ..transaction(1002, TxType::Deposit, 500)
+            },
+        ];
+        let mut output = Vec::new();
+
+        // Act
+        write_canonical_json(&mut output, &records).unwrap();
+
+        // Assert
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], "[");
+        assert_eq!(lines[1], format!("{},", to_canonical_json(&records[0])));
+        assert_eq!(lines[2], to_canonical_json(&records[1]));
+        assert_eq!(lines[3], "]");
+    }
+}
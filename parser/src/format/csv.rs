@@ -6,7 +6,25 @@
 //!
 //! * [`YPBankCsvFormat::read_from`] — чтение (парсинг) данных в формате CSV и распаковка в
 //!   отдельные экземпляры [`YPBankCsvFormat`] каждой записи
+//! * [`YPBankCsvFormat::read_iter`] — потоковое чтение: записи разбираются по одной, без
+//!   загрузки всего файла в память.
 //! * [`YPBankCsvFormat::write_to`] — запись предоставленных элементов [`YPBankCsvFormat`].
+//! * [`YPBankCsvFormat::read_lenient`] — чтение с пропуском строк с нарушенной структурой
+//!   ([`YPBankCsvFormat::read_lenient_with`] — с настройками, в том числе лимитом ошибок).
+//! * [`YPBankCsvFormat::read_executor_with_delimiter`],
+//!   [`YPBankCsvFormat::write_to_with_delimiter`] — чтение и запись с другим разделителем
+//!   колонок (`;`, табуляция); разделитель можно определить через [`detect_delimiter`].
+//! * [`detect_delimiter`] — определение разделителя по строке заголовка.
+//!
+//! Поле в кавычках может занимать несколько строк (RFC 4180): переводы строк в описании
+//! записываются как есть, а при чтении строки объединяются, пока кавычка не закрыта. Номер
+//! строки в ошибках — номер первой строки записи; кавычка, не закрытая до конца данных, даёт
+//! ошибку с номером строки и позицией этой кавычки.
+//!
+//! Кавычки при чтении нужны, только если значение содержит разделитель, кавычку или перевод
+//! строки: `...,SUCCESS,plain text` и `...,SUCCESS,"plain text"` равнозначны. Значение без
+//! кавычек обрезается по краям, значение в кавычках — нет. При записи описание всегда
+//! заключается в кавычки.
 //!
 //! # Примеры
 //!
@@ -22,54 +40,353 @@
 //! YPBankCsvFormat::write_to(&mut file_target, &data);
 //! ```
 
+use crate::MAX_SIZE_CSV_TXT_BYTES;
 use crate::errors::ParseError;
-use crate::format::tools::LineUtils;
+use crate::format::options::{CsvHeaderMode, ReadOptions, WriteOptions};
+use crate::format::tools::{LineUtils, collect_records, validate_exceed_max_bytes};
 use crate::models::YPBankCsvFormat;
 use crate::traits::YPBankIO;
+use std::borrow::Borrow;
 use std::collections::HashMap;
-use std::io::{BufWriter, Write};
+use std::io::{self, BufRead, BufReader, BufWriter, Lines, Read, Write};
 
 impl YPBankIO for YPBankCsvFormat {
     type DataFormat = YPBankCsvFormat;
 
-    fn read_executor(buffer: String) -> Result<Vec<Self::DataFormat>, ParseError> {
-        // Проверим заголовок.
-        let mut lines = buffer.lines();
-        let title_line = lines
-            .next()
-            .ok_or_else(|| ParseError::parse_err("Ошибка парсинга заголовка csv", 0, 0))?;
-
-        if !title_line.is_eq(Self::make_title().as_str()) {
-            return Err(ParseError::parse_err(
-                format!("Некорректный заголовок csv: {}", title_line),
-                0,
-                0,
+    /// Собирает записи потокового чтения [`YPBankCsvFormat::read_iter`].
+    ///
+    /// Ограничение на объём данных ([`MAX_SIZE_CSV_TXT_BYTES`]) сохраняется: чтение
+    /// прекращается, как только оно превышено. Так же чтение прекращается на первой записи
+    /// сверх [`ReadOptions::max_records`].
+    fn read_from_with<R: Read>(
+        reader: &mut R,
+        options: &ReadOptions,
+    ) -> Result<Vec<Self::DataFormat>, ParseError> {
+        let mut limited = reader.take(MAX_SIZE_CSV_TXT_BYTES as u64 + 1);
+        let records = collect_records(
+            Self::read_iter_with(&mut limited, options.clone()),
+            options.max_records,
+        );
+        if limited.limit() == 0 {
+            return Err(ParseError::lim_exceed(
+                MAX_SIZE_CSV_TXT_BYTES + 1,
+                MAX_SIZE_CSV_TXT_BYTES,
             ));
         }
+        let records = records?;
 
-        let title_data = title_line
-            .split_csv_line()
-            .ok_or_else(|| ParseError::parse_err("Ошибка разбора csv-заголовка", 0, 0))?;
+        if records.is_empty() {
+            return Err(ParseError::empty_data());
+        }
 
-        lines
-            .enumerate()
-            .map(|(i, line)| Self::parse_data_line(&title_data, line, i + 1))
+        Ok(records)
+    }
+
+    fn read_executor_with(
+        buffer: String,
+        options: &ReadOptions,
+    ) -> Result<Vec<Self::DataFormat>, ParseError> {
+        let mut records = record_lines(&buffer);
+        let title_data = records.parse_title(options)?;
+
+        records
+            .map(|record| {
+                let (count_line, record) = record?;
+                Self::parse_data_line(&title_data, &record, count_line, options)
+            })
             .collect()
     }
 
     /// Добавить запись на основе предоставленного экземпляра `YPBankCsvFormat`.
-    fn write_to<W: Write>(writer: W, records: &[Self::DataFormat]) -> Result<(), ParseError> {
-        let mut buf_writer = BufWriter::new(writer);
-        writeln!(buf_writer, "{}", Self::make_title())?;
-        for record in records {
-            writeln!(buf_writer, "{}", Self::makeup_records(record))?;
-        }
+    fn write_to_with<W: Write>(
+        writer: W,
+        records: &[Self::DataFormat],
+        options: &WriteOptions,
+    ) -> Result<(), ParseError> {
+        Self::write_iter_with(writer, records.iter().map(Ok), options)?;
 
         Ok(())
     }
 }
 
 impl YPBankCsvFormat {
+    /// Потоковая запись: заголовок пишется сразу, затем каждая запись из `records` — по мере
+    /// получения, поэтому данные не накапливаются в памяти. Первая ошибка источника прерывает
+    /// запись.
+    ///
+    /// Возвращает число записанных записей.
+    pub fn write_iter<W: Write>(
+        writer: W,
+        records: impl IntoIterator<Item = Result<Self, ParseError>>,
+    ) -> Result<usize, ParseError> {
+        Self::write_iter_with(writer, records, &WriteOptions::default())
+    }
+
+    /// Потоковая запись с настройками `options`, см. [`YPBankCsvFormat::write_iter`].
+    pub(crate) fn write_iter_with<W: Write, B: Borrow<Self>>(
+        writer: W,
+        records: impl IntoIterator<Item = Result<B, ParseError>>,
+        options: &WriteOptions,
+    ) -> Result<usize, ParseError> {
+        let columns = Self::column_positions(options)?;
+        let fields = Self::fields();
+        let title: Vec<&str> = columns.iter().map(|&i| fields[i]).collect();
+
+        let delimiter = options.csv_delimiter().to_string();
+
+        let mut buf_writer = BufWriter::new(writer);
+        writeln!(buf_writer, "{}", title.join(&delimiter))?;
+        let mut count = 0;
+        for record in records {
+            writeln!(
+                buf_writer,
+                "{}",
+                Self::makeup_records(record?.borrow(), options, &columns)
+            )?;
+            count += 1;
+        }
+
+        buf_writer
+            .flush()
+            .map_err(|e| ParseError::io_error(e, "Ошибка записи данных"))?;
+
+        Ok(count)
+    }
+
+    /// Парсит строку с данными `csv`, колонки которых разделены `delimiter` (например, `;` или
+    /// табуляцией). Правила для полей в кавычках те же, что и для запятой.
+    ///
+    /// ## Пример
+    ///
+    /// ```
+    /// use parser::models::YPBankCsvFormat;
+    ///
+    /// let data = "TX_ID;TX_TYPE;FROM_USER_ID;TO_USER_ID;AMOUNT;TIMESTAMP;STATUS;DESCRIPTION\n\
+    ///             1;DEPOSIT;0;1001;500;1633046400;SUCCESS;\"Salary; May\"";
+    ///
+    /// let records = YPBankCsvFormat::read_executor_with_delimiter(data.to_string(), ';').unwrap();
+    /// assert_eq!(records[0].description, "Salary; May");
+    /// ```
+    pub fn read_executor_with_delimiter(
+        buffer: String,
+        delimiter: char,
+    ) -> Result<Vec<Self>, ParseError> {
+        let options = ReadOptions {
+            delimiter: Some(delimiter),
+            ..ReadOptions::default()
+        };
+        Self::read_executor_with(buffer, &options)
+    }
+
+    /// Записывает данные `csv`, разделяя колонки символом `delimiter`.
+    pub fn write_to_with_delimiter<W: Write>(
+        writer: W,
+        records: &[Self],
+        delimiter: char,
+    ) -> Result<(), ParseError> {
+        let options = WriteOptions {
+            delimiter: Some(delimiter),
+            ..WriteOptions::default()
+        };
+        Self::write_to_with(writer, records, &options)
+    }
+
+    /// Потоковое чтение данных: заголовок разбирается один раз, затем каждый вызов `next()`
+    /// читает и разбирает одну строку.
+    ///
+    /// В отличие от [`YPBankCsvFormat::read_from`], данные не загружаются в память целиком
+    /// и ограничение на их объём не действует. Ошибка заголовка возвращается первым элементом,
+    /// после любой ошибки итерация завершается. Номера строк в [`ParseError::ParseError`]
+    /// совпадают с [`YPBankCsvFormat::read_from`].
+    ///
+    /// ## Пример
+    ///
+    /// ```no_run
+    /// use std::fs::File;
+    /// use parser::models::YPBankCsvFormat;
+    ///
+    /// let file = File::open("data.csv").unwrap();
+    /// for record in YPBankCsvFormat::read_iter(file) {
+    ///     println!("{}", record.unwrap().tx_id);
+    /// }
+    /// ```
+    pub fn read_iter<R: Read>(reader: R) -> impl Iterator<Item = Result<Self, ParseError>> {
+        Self::read_iter_with(reader, ReadOptions::default())
+    }
+
+    /// Потоковое чтение с настройками `options`. Ограничение [`ReadOptions::max_records`]
+    /// не проверяется.
+    fn read_iter_with<R: Read>(
+        reader: R,
+        options: ReadOptions,
+    ) -> impl Iterator<Item = Result<Self, ParseError>> {
+        Self::read_iter_positioned(reader, options).map(|item| item.map(|(_, record)| record))
+    }
+
+    /// Потоковое чтение с настройками `options`: вместе с записью возвращается номер её первой
+    /// строки (начиная с 1, заголовок — строка 1).
+    pub(crate) fn read_iter_positioned<R: Read>(
+        reader: R,
+        options: ReadOptions,
+    ) -> impl Iterator<Item = Result<(usize, Self), ParseError>> {
+        CsvRecordIter {
+            records: CsvRecordLines {
+                lines: BufReader::new(reader).lines(),
+                next_line: 0,
+            },
+            title_data: None,
+            options,
+            finished: false,
+        }
+    }
+
+    /// Чтение данных в режиме восстановления.
+    ///
+    /// Строки с нарушенной структурой (неверное число колонок, незакрытые кавычки) не прерывают
+    /// чтение: для каждой из них формируется ошибка с номером строки, а сама строка пропускается.
+    /// Ошибки заголовка и некорректные значения полей по-прежнему прерывают разбор.
+    ///
+    /// ## Пример
+    ///
+    /// ```no_run
+    /// use std::fs::File;
+    /// use parser::models::YPBankCsvFormat;
+    ///
+    /// let mut file = File::open("data.csv").unwrap();
+    /// let (records, errors) = YPBankCsvFormat::read_lenient(&mut file).unwrap();
+    ///
+    /// for err in errors {
+    ///     eprintln!("Пропущено: {err}");
+    /// }
+    /// ```
+    ///
+    /// ## Returns
+    ///
+    /// Кортеж из корректных записей и ошибок пропущенных строк.
+    pub fn read_lenient<R: Read>(
+        reader: &mut R,
+    ) -> Result<(Vec<Self>, Vec<ParseError>), ParseError> {
+        Self::read_lenient_with(reader, &ReadOptions::default())
+    }
+
+    /// Чтение данных в режиме восстановления с настройками `options`.
+    ///
+    /// Если задано [`ReadOptions::max_errors`], то после накопления указанного числа ошибок
+    /// чтение прекращается: возвращаются уже прочитанные записи, а список ошибок завершается
+    /// маркером [`ParseError::ErrorLimitExceeded`].
+    pub fn read_lenient_with<R: Read>(
+        reader: &mut R,
+        options: &ReadOptions,
+    ) -> Result<(Vec<Self>, Vec<ParseError>), ParseError> {
+        let mut buffer = String::new();
+        BufReader::new(reader)
+            .read_to_string(&mut buffer)
+            .map_err(|e| ParseError::io_error(e, "Ошибка парсинга данных"))?;
+        validate_exceed_max_bytes(buffer.len(), MAX_SIZE_CSV_TXT_BYTES)?;
+
+        let mut lines = record_lines(&buffer);
+        let title_data = lines.parse_title(options)?;
+
+        let mut records = Vec::new();
+        let mut errors = Vec::new();
+        for record in lines {
+            let (count_line, line) = record?;
+            match Self::split_data_line(&title_data, &line, count_line, options.csv_delimiter()) {
+                Ok(data) => records.push(Self::record_from_fields(
+                    &title_data,
+                    data,
+                    count_line,
+                    options,
+                )?),
+                Err(err) => errors.push(err),
+            }
+
+            if let Some(limit) = options.max_errors.filter(|&limit| errors.len() >= limit) {
+                errors.push(ParseError::error_limit(limit));
+                break;
+            }
+        }
+
+        Ok((records, errors))
+    }
+
+    /// Проверяет, что данные завершаются целой записью.
+    ///
+    /// Разбирается только заголовок и последняя непустая запись: оборванная запись даёт неверное
+    /// число колонок, незакрытую кавычку или некорректное значение поля.
+    pub(crate) fn is_complete<R: Read>(reader: &mut R) -> Result<bool, ParseError> {
+        let mut buffer = String::new();
+        BufReader::new(reader)
+            .read_to_string(&mut buffer)
+            .map_err(|e| ParseError::io_error(e, "Ошибка парсинга данных"))?;
+        validate_exceed_max_bytes(buffer.len(), MAX_SIZE_CSV_TXT_BYTES)?;
+
+        let mut lines = record_lines(&buffer);
+        let options = ReadOptions::default();
+        let title_data = lines.parse_title(&options)?;
+
+        let last_line = lines
+            .filter_map(Result::ok)
+            .filter(|(_, line)| !line.trim().is_empty())
+            .last();
+        Ok(match last_line {
+            Some((count_line, line)) => {
+                Self::parse_data_line(&title_data, &line, count_line, &options).is_ok()
+            }
+            None => true,
+        })
+    }
+
+    /// Проверка и разбор строки заголовка.
+    ///
+    /// В режиме [`CsvHeaderMode::AnyOrder`] допускается любой порядок колонок, но каждое поле
+    /// должно встречаться ровно один раз. Если в настройках задано соответствие полей ([`ReadOptions::field_map`]), внешние имена
+    /// колонок заменяются на канонические до проверки.
+    fn parse_title_line(
+        title_line: Option<&str>,
+        options: &ReadOptions,
+    ) -> Result<Vec<String>, ParseError> {
+        let title_line = title_line
+            .ok_or_else(|| ParseError::parse_err("Ошибка парсинга заголовка csv", 0, 0))?;
+
+        let delimiter = options.csv_delimiter();
+        let title_line = match &options.field_map {
+            Some(field_map) => title_line
+                .split(delimiter)
+                .map(|title| field_map.canonical(title.trim()))
+                .collect::<Vec<_>>()
+                .join(&delimiter.to_string()),
+            None => title_line.to_string(),
+        };
+
+        let title_valid = match options.header_mode {
+            CsvHeaderMode::Strict => title_line.is_eq(&Self::make_title(delimiter)),
+            CsvHeaderMode::AnyOrder => {
+                let mut titles: Vec<&str> = title_line.split(delimiter).map(str::trim).collect();
+                let mut expected = Self::fields();
+                titles.sort_unstable();
+                expected.sort_unstable();
+                titles == expected
+            }
+        };
+
+        if !title_valid {
+            return Err(ParseError::parse_err(
+                format!("Некорректный заголовок csv: {}", title_line),
+                0,
+                0,
+            ));
+        }
+
+        title_line.split_csv_line(delimiter).map_err(|err| {
+            let column = match err {
+                ParseError::ParseError { column, .. } => column,
+                _ => 0,
+            };
+            ParseError::parse_err("Ошибка разбора csv-заголовка", 0, column)
+        })
+    }
+
     /// Формирует строку заголовка. Может быть использована при формировании файла, либо при
     /// парсинге, для сопоставления корректности заголовка.
     ///
@@ -78,34 +395,71 @@ impl YPBankCsvFormat {
     /// ```plain
     /// TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION
     /// ```
-    fn make_title() -> String {
-        Self::fields().join(",")
+    fn make_title(delimiter: char) -> String {
+        Self::fields().join(&delimiter.to_string())
     }
 
-    /// Формирует строку записи.
+    /// Позиции канонических полей в порядке колонок записи.
+    ///
+    /// Без [`WriteOptions::column_order`] порядок канонический. Заданный порядок должен содержать
+    /// каждое поле ровно один раз, иначе возвращается [`ParseError::IncorrectField`].
+    fn column_positions(options: &WriteOptions) -> Result<Vec<usize>, ParseError> {
+        let fields = Self::fields();
+        let Some(order) = &options.column_order else {
+            return Ok((0..fields.len()).collect());
+        };
+
+        let mut positions = Vec::with_capacity(fields.len());
+        for name in order {
+            let position = fields
+                .iter()
+                .position(|field| field.eq_ignore_ascii_case(name.trim()))
+                .filter(|position| !positions.contains(position))
+                .ok_or_else(|| ParseError::incorrect_field(name.as_str()))?;
+            positions.push(position);
+        }
+
+        if let Some(missing) = (0..fields.len()).find(|i| !positions.contains(i)) {
+            return Err(ParseError::incorrect_field(fields[missing]));
+        }
+
+        Ok(positions)
+    }
+
+    /// Формирует строку записи. `columns` — позиции канонических полей в порядке колонок
+    /// (см. [`YPBankCsvFormat::column_positions`]).
     ///
     /// ## Пример записи
     ///
     /// ```plain
     /// 1000000000000009,DEPOSIT,0,9223372036854775807,1000,1633037400000,FAILURE,"Record number 10"
     /// ```
-    fn makeup_records(records: &YPBankCsvFormat) -> String {
+    fn makeup_records(
+        records: &YPBankCsvFormat,
+        options: &WriteOptions,
+        columns: &[usize],
+    ) -> String {
         let description = format!(
             "\"{}\"",
             records.description.replace('"', "\"\"") // CSV-экранирование
         );
 
-        [
+        let values = [
             records.tx_id.to_string(),
             records.tx_type.to_string(),
             records.from_user_id.to_string(),
             records.to_user_id.to_string(),
-            records.amount.to_string(),
+            options.format_amount(records.amount),
             records.timestamp.to_string(),
             records.status.to_string(),
             description,
-        ]
-        .join(",")
+        ];
+
+        columns
+            .iter()
+            .map(|&i| values[i].as_str())
+            .collect::<Vec<_>>()
+            .join(&options.csv_delimiter().to_string())
     }
 
     /// Разбор отдельной строки в CSV.
@@ -113,34 +467,227 @@ impl YPBankCsvFormat {
         title_data: &[String],
         line: &str,
         count_line: usize,
+        options: &ReadOptions,
     ) -> Result<YPBankCsvFormat, ParseError> {
-        let data = match line.split_csv_line() {
-            Some(data) => {
-                if data.len() != title_data.len() {
-                    return Err(ParseError::parse_err(
-                        format!("Заголовок не совпадает со строкой: {}", line),
-                        count_line,
-                        0,
-                    ));
-                }
-                data
-            }
-            None => {
-                return Err(ParseError::parse_err(
-                    "Ошибка чтения строки csv",
-                    count_line,
-                    0,
-                ));
-            }
+        let data = Self::split_data_line(title_data, line, count_line, options.csv_delimiter())?;
+        Self::record_from_fields(title_data, data, count_line, options)
+    }
+
+    /// Разбивает запись на поля и сверяет их количество с заголовком. `count_line` — номер первой
+    /// строки записи: запись с переводами строк в кавычках занимает несколько строк.
+    ///
+    /// Нарушение структуры, неверное число полей и запись из одних пустых полей сообщаются
+    /// разными ошибками.
+    fn split_data_line(
+        title_data: &[String],
+        line: &str,
+        count_line: usize,
+        delimiter: char,
+    ) -> Result<Vec<String>, ParseError> {
+        let data = line.split_csv_line(delimiter).map_err(|err| match err {
+            ParseError::ParseError {
+                message,
+                line: line_offset,
+                column,
+            } => ParseError::parse_err(message, count_line + line_offset, column),
+            other => other,
+        })?;
+
+        if data.len() != title_data.len() {
+            return Err(ParseError::parse_err(
+                format!(
+                    "Число полей ({}) не совпадает с заголовком ({}): {}",
+                    data.len(),
+                    title_data.len(),
+                    line
+                ),
+                count_line,
+                0,
+            ));
+        }
+
+        if data.iter().all(String::is_empty) {
+            return Err(ParseError::parse_err(
+                "Все поля записи csv пустые",
+                count_line,
+                0,
+            ));
+        }
+
+        Ok(data)
+    }
+
+    /// Собирает запись из полей строки, сопоставленных с заголовком.
+    ///
+    /// Ошибка некорректного поля дополняется номером строки `count_line` и колонкой поля —
+    /// его позицией в заголовке (начиная с 1).
+    fn record_from_fields(
+        title_data: &[String],
+        data: Vec<String>,
+        count_line: usize,
+        options: &ReadOptions,
+    ) -> Result<YPBankCsvFormat, ParseError> {
+        let locate = |err: ParseError| {
+            let column = match &err {
+                ParseError::IncorrectField { key, .. } => title_data
+                    .iter()
+                    .position(|title| title == key)
+                    .map_or(0, |i| i + 1),
+                _ => 0,
+            };
+            err.at_field(count_line, column)
         };
 
-        let csv_parse: HashMap<_, _> = title_data
+        let mut csv_parse: HashMap<_, _> = title_data
             .iter()
             .zip(data)
-            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .map(|(key, value)| (key.to_string(), value))
             .collect();
+        options.prepare_fields(&mut csv_parse).map_err(locate)?;
+
+        YPBankCsvFormat::new_from_map(&csv_parse).map_err(locate)
+    }
+}
+
+/// Нечётное количество кавычек: поле в кавычках продолжается на следующей строке.
+fn has_odd_quotes(line: &str) -> bool {
+    line.bytes().filter(|&b| b == b'"').count() % 2 == 1
+}
 
-        YPBankCsvFormat::new_from_map(&csv_parse)
+/// Записи данных `csv` из физических строк (RFC 4180): строки объединяются через `\n`, пока
+/// поле в кавычках не закрыто. Элемент — номер первой строки записи (заголовок — строка 0)
+/// и текст записи.
+///
+/// Если кавычка не закрыта до конца данных, последней записью возвращается весь остаток.
+pub(crate) struct CsvRecordLines<I> {
+    lines: I,
+    /// Номер следующей строки.
+    next_line: usize,
+}
+
+/// Записи `csv` из текста, уже загруженного в память, см. [`CsvRecordLines`].
+pub(crate) fn record_lines(
+    buffer: &str,
+) -> CsvRecordLines<impl Iterator<Item = io::Result<String>> + '_> {
+    CsvRecordLines {
+        lines: buffer.lines().map(|line| Ok(line.to_string())),
+        next_line: 0,
+    }
+}
+
+impl<I: Iterator<Item = io::Result<String>>> CsvRecordLines<I> {
+    /// Читает и разбирает заголовок, см. [`YPBankCsvFormat::parse_title_line`].
+    fn parse_title(&mut self, options: &ReadOptions) -> Result<Vec<String>, ParseError> {
+        let title_line = self.next().transpose()?;
+        YPBankCsvFormat::parse_title_line(title_line.as_ref().map(|(_, l)| l.strip_bom()), options)
+    }
+
+    /// Следующая физическая строка.
+    fn next_line(&mut self) -> Option<Result<String, ParseError>> {
+        let line = self.lines.next()?;
+        self.next_line += 1;
+        Some(line.map_err(|e| ParseError::io_error(e, "Ошибка парсинга данных")))
+    }
+}
+
+impl<I: Iterator<Item = io::Result<String>>> Iterator for CsvRecordLines<I> {
+    type Item = Result<(usize, String), ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let count_line = self.next_line;
+        let mut record = match self.next_line()? {
+            Ok(line) => line,
+            Err(err) => return Some(Err(err)),
+        };
+
+        let mut open = has_odd_quotes(&record);
+        while open {
+            let line = match self.next_line() {
+                Some(Ok(line)) => line,
+                Some(Err(err)) => return Some(Err(err)),
+                None => break,
+            };
+            open ^= has_odd_quotes(&line);
+            record.push('\n');
+            record.push_str(&line);
+        }
+
+        Some(Ok((count_line, record)))
+    }
+}
+
+/// Итератор потокового чтения, см. [`YPBankCsvFormat::read_iter`].
+struct CsvRecordIter<R> {
+    records: CsvRecordLines<Lines<BufReader<R>>>,
+    /// Разобранный заголовок; `None` до чтения первой строки.
+    title_data: Option<Vec<String>>,
+    options: ReadOptions,
+    /// Итерация завершена: данные закончились или возникла ошибка.
+    finished: bool,
+}
+
+impl<R: Read> CsvRecordIter<R> {
+    fn next_record(&mut self) -> Option<Result<(usize, YPBankCsvFormat), ParseError>> {
+        if self.title_data.is_none() {
+            match self.records.parse_title(&self.options) {
+                Ok(title_data) => self.title_data = Some(title_data),
+                Err(err) => return Some(Err(err)),
+            }
+        }
+
+        let (count_line, record) = match self.records.next()? {
+            Ok(record) => record,
+            Err(err) => return Some(Err(err)),
+        };
+        let title_data = self.title_data.as_deref().unwrap_or_default();
+
+        Some(
+            YPBankCsvFormat::parse_data_line(title_data, &record, count_line, &self.options)
+                .map(|record| (count_line + 1, record)),
+        )
+    }
+}
+
+impl<R: Read> Iterator for CsvRecordIter<R> {
+    type Item = Result<(usize, YPBankCsvFormat), ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let item = self.next_record();
+        self.finished = !matches!(item, Some(Ok(_)));
+        item
+    }
+}
+
+/// Разделители, среди которых выбирает [`detect_delimiter`], в порядке приоритета.
+const DELIMITER_CANDIDATES: [char; 3] = [',', ';', '\t'];
+
+/// Определяет разделитель колонок по строке заголовка.
+///
+/// Выбирается тот из разделителей `,`, `;` и `\t`, который делит заголовок ровно на столько
+/// колонок, сколько полей в [`YPBankCsvFormat`]. Если подходящего разделителя нет или подходят
+/// несколько, возвращается `,`.
+///
+/// ## Пример
+///
+/// ```
+/// use parser::format::csv::detect_delimiter;
+///
+/// let title = "TX_ID;TX_TYPE;FROM_USER_ID;TO_USER_ID;AMOUNT;TIMESTAMP;STATUS;DESCRIPTION";
+/// assert_eq!(detect_delimiter(title), ';');
+/// ```
+pub fn detect_delimiter(first_line: &str) -> char {
+    let expected = YPBankCsvFormat::fields().len();
+    let mut matching = DELIMITER_CANDIDATES
+        .into_iter()
+        .filter(|&delimiter| first_line.split(delimiter).count() == expected);
+
+    match (matching.next(), matching.next()) {
+        (Some(delimiter), None) => delimiter,
+        _ => ',',
     }
 }
 
@@ -148,10 +695,17 @@ impl YPBankCsvFormat {
 mod csv_tests {
     use crate::MAX_SIZE_CSV_TXT_BYTES;
     use crate::errors::ParseError;
+    use crate::format::csv::detect_delimiter;
+    use crate::format::options::{
+        CsvHeaderMode, DecimalAmount, FieldMap, ReadOptions, WriteOptions,
+    };
+    use crate::format::tools::LineUtils;
     use crate::models::{TxStatus, TxType, YPBankCsvFormat};
     use crate::traits::YPBankIO;
     use std::io::Cursor;
 
+    const CANONICAL_COLUMNS: [usize; 8] = [0, 1, 2, 3, 4, 5, 6, 7];
+
     fn create_test_csv_record() -> YPBankCsvFormat {
         YPBankCsvFormat {
             tx_id: 123456789,
@@ -215,7 +769,7 @@ mod csv_tests {
     #[test]
     fn test_make_title() {
         // Act
-        let title = YPBankCsvFormat::make_title();
+        let title = YPBankCsvFormat::make_title(',');
 
         // Assert
         assert_eq!(
@@ -230,7 +784,8 @@ mod csv_tests {
         let record = create_test_csv_record();
 
         // Act
-        let csv_line = YPBankCsvFormat::makeup_records(&record);
+        let csv_line =
+            YPBankCsvFormat::makeup_records(&record, &WriteOptions::default(), &CANONICAL_COLUMNS);
 
         // Assert
         let expected = "123456789,TRANSFER,1001,1002,50000,1633046400,SUCCESS,\"Test transaction\"";
@@ -243,7 +798,8 @@ mod csv_tests {
         let record = create_deposit_csv_record();
 
         // Act
-        let csv_line = YPBankCsvFormat::makeup_records(&record);
+        let csv_line =
+            YPBankCsvFormat::makeup_records(&record, &WriteOptions::default(), &CANONICAL_COLUMNS);
 
         // Assert
         let expected = "987654321,DEPOSIT,0,1003,100000,1633046401,PENDING,\"\"";
@@ -257,7 +813,8 @@ mod csv_tests {
         record.description = "Test \"quoted\" transaction".to_string();
 
         // Act
-        let csv_line = YPBankCsvFormat::makeup_records(&record);
+        let csv_line =
+            YPBankCsvFormat::makeup_records(&record, &WriteOptions::default(), &CANONICAL_COLUMNS);
 
         // Assert
         let expected = "123456789,TRANSFER,1001,1002,50000,1633046400,SUCCESS,\"Test \"\"quoted\"\" transaction\"";
@@ -286,6 +843,22 @@ mod csv_tests {
         assert_eq!(record.description, "Test transaction");
     }
 
+    #[test]
+    fn test_read_from_utf8_bom() {
+        // Arrange
+        let csv_data = "\u{FEFF}TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION\n\
+                       123456789,TRANSFER,1001,1002,50000,1633046400,SUCCESS,\"Test transaction\"";
+
+        // Act
+        let streamed = YPBankCsvFormat::read_from(&mut Cursor::new(csv_data)).unwrap();
+        let buffered = YPBankCsvFormat::read_executor(csv_data.to_string()).unwrap();
+
+        // Assert
+        assert_eq!(streamed.len(), 1);
+        assert_eq!(streamed[0].tx_id, 123456789);
+        assert_eq!(buffered, streamed);
+    }
+
     #[test]
     fn test_read_executor_multiple_records() {
         // Arrange
@@ -634,10 +1207,93 @@ mod csv_tests {
 
         // Act: read
         let csv_string = String::from_utf8(buffer).unwrap();
-        let read_records = YPBankCsvFormat::read_executor(csv_string);
+        let read_records = YPBankCsvFormat::read_executor(csv_string.clone()).unwrap();
+
+        // Assert
+        assert!(csv_string.contains("with comma\nand newline\"\n"));
+        assert_eq!(read_records, vec![record]);
+    }
+
+    #[test]
+    fn test_write_read_round_trip_tab_in_description() {
+        // Arrange
+        let mut record = create_test_csv_record();
+        record.description = "tab\there".to_string();
+        let mut buffer = Vec::new();
+
+        // Act
+        YPBankCsvFormat::write_to(&mut buffer, &[record.clone()]).unwrap();
+        let read_records = YPBankCsvFormat::read_from(&mut Cursor::new(buffer)).unwrap();
+
+        // Assert
+        assert_eq!(read_records, vec![record]);
+    }
+
+    #[test]
+    fn test_read_multiline_quoted_description() {
+        // Arrange
+        let csv_data = "TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION\n\
+                        1,DEPOSIT,0,1001,500,1633046400,SUCCESS,\"First line\n\
+                        second line\"\n\
+                        2,DEPOSIT,0,1001,600,1633046401,SUCCESS,\"Salary\"\n";
+
+        // Act
+        let executor = YPBankCsvFormat::read_executor(csv_data.to_string()).unwrap();
+        let streamed = YPBankCsvFormat::read_from(&mut Cursor::new(csv_data)).unwrap();
 
         // Assert
-        assert!(read_records.is_err());
+        assert_eq!(executor.len(), 2);
+        assert_eq!(executor[0].description, "First line\nsecond line");
+        assert_eq!(executor[1].amount, 600);
+        assert_eq!(streamed, executor);
+    }
+
+    #[test]
+    fn test_read_line_numbers_after_multiline_record() {
+        // Arrange
+        let csv_data = "TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION\n\
+                        1,DEPOSIT,0,1001,500,1633046400,SUCCESS,\"First\n\
+                        second\n\
+                        third\"\n\
+                        2,DEPOSIT,0,1001,600,1633046401,SUCCESS\n\
+                        3,DEPOSIT,0,1001,700,1633046402,SUCCESS,\"Salary\"\n";
+
+        // Act
+        let (records, errors) = YPBankCsvFormat::read_lenient(&mut Cursor::new(csv_data)).unwrap();
+        let strict = YPBankCsvFormat::read_executor(csv_data.to_string());
+
+        // Assert
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].tx_id, 3);
+        assert!(matches!(
+            errors.as_slice(),
+            [ParseError::ParseError { line: 4, .. }]
+        ));
+        assert!(matches!(
+            strict,
+            Err(ParseError::ParseError { line: 4, .. })
+        ));
+    }
+
+    #[test]
+    fn test_read_unclosed_quote_reports_opening_line() {
+        // Arrange
+        let csv_data = "TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION\n\
+                        1,DEPOSIT,0,1001,500,1633046400,SUCCESS,\"Salary\"\n\
+                        2,DEPOSIT,0,1001,500,1633046400,SUCCESS,\"First line\n\
+                        second line\n";
+
+        // Act
+        let executor = YPBankCsvFormat::read_executor(csv_data.to_string());
+        let is_complete = YPBankCsvFormat::is_complete(&mut Cursor::new(csv_data)).unwrap();
+
+        // Assert
+        assert!(matches!(
+            executor,
+            Err(ParseError::ParseError { line: 2, column: 41, ref message })
+                if message.contains("не закрыто")
+        ));
+        assert!(!is_complete);
     }
 
     #[test]
@@ -732,7 +1388,8 @@ mod csv_tests {
         let line = "123456789,TRANSFER,1001,1002,50000,1633046400,SUCCESS,\"Test transaction\"";
 
         // Act
-        let result = YPBankCsvFormat::parse_data_line(&title_data, line, 1);
+        let result =
+            YPBankCsvFormat::parse_data_line(&title_data, line, 1, &ReadOptions::default());
 
         // Assert
         assert!(result.is_ok());
@@ -752,7 +1409,8 @@ mod csv_tests {
         let line = "123456789,TRANSFER,1001,1002,50000,1633046400,SUCCESS"; // Missing description
 
         // Act
-        let result = YPBankCsvFormat::parse_data_line(&title_data, line, 1);
+        let result =
+            YPBankCsvFormat::parse_data_line(&title_data, line, 1, &ReadOptions::default());
 
         // Assert
         assert!(result.is_err());
@@ -768,10 +1426,103 @@ mod csv_tests {
         let line = ",,,,,,,"; // All empty fields
 
         // Act
-        let result = YPBankCsvFormat::parse_data_line(&title_data, line, 1);
+        let result =
+            YPBankCsvFormat::parse_data_line(&title_data, line, 1, &ReadOptions::default());
 
         // Assert
-        assert!(result.is_err()); // Должно быть ошибкой парсинга чисел
+        assert!(matches!(
+            result,
+            Err(ParseError::ParseError { ref message, line: 1, .. })
+                if message == "Все поля записи csv пустые"
+        ));
+    }
+
+    #[test]
+    fn test_split_csv_line_field_count() {
+        // Act & Assert
+        assert_eq!("".split_csv_line(',').unwrap(), [""]);
+        assert_eq!(",,,,,,,".split_csv_line(',').unwrap(), vec![""; 8]);
+        assert_eq!("a,\"b,c\",".split_csv_line(',').unwrap(), ["a", "b,c", ""]);
+        assert_eq!("a , \"\" ".split_csv_line(',').unwrap(), ["a", ""]);
+    }
+
+    #[test]
+    fn test_split_csv_line_error_position() {
+        // Arrange
+        let cases = [
+            ("1,ab\"c", 0, 5),
+            ("1,\"ab\"c,2", 0, 7),
+            ("1,\"ab\nc\"\n,\"x", 2, 2),
+        ];
+
+        for (line, expected_line, expected_column) in cases {
+            // Act
+            let result = line.split_csv_line(',');
+
+            // Assert
+            assert!(
+                matches!(
+                    result,
+                    Err(ParseError::ParseError { line, column, .. })
+                        if line == expected_line && column == expected_column
+                ),
+                "{line:?}: {result:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_read_quoted_and_unquoted_description() {
+        // Arrange
+        let csv_data = "TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION\n\
+                       1,DEPOSIT,0,1001,500,1633046400,SUCCESS,plain text\n\
+                       2,DEPOSIT,0,1001,500,1633046400,SUCCESS,\"has, comma\"\n\
+                       3,DEPOSIT,0,1001,500,1633046400,SUCCESS,  plain text  \n\
+                       4,DEPOSIT,0,1001,500,1633046400,SUCCESS, \"  padded \" \n\
+                       5,DEPOSIT,0,1001,500,1633046400,SUCCESS,\"plain text\"";
+
+        // Act
+        let result = YPBankCsvFormat::read_executor(csv_data.to_string()).unwrap();
+
+        // Assert
+        let descriptions: Vec<&str> = result.iter().map(|r| r.description.as_str()).collect();
+        assert_eq!(
+            descriptions,
+            [
+                "plain text",
+                "has, comma",
+                "plain text",
+                "  padded ",
+                "plain text"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_data_line_field_count_and_empty_description() {
+        // Arrange
+        let title_data: Vec<String> = YPBankCsvFormat::fields()
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let options = ReadOptions::default();
+
+        // Act
+        let short = YPBankCsvFormat::parse_data_line(&title_data, "1,DEPOSIT", 3, &options);
+        let unquoted_empty = YPBankCsvFormat::parse_data_line(
+            &title_data,
+            "1,DEPOSIT,0,1001,500,1633046400,SUCCESS,",
+            3,
+            &options,
+        );
+
+        // Assert
+        assert!(matches!(
+            short,
+            Err(ParseError::ParseError { ref message, line: 3, .. })
+                if message.starts_with("Число полей (2) не совпадает с заголовком (8)")
+        ));
+        assert_eq!(unquoted_empty.unwrap().description, "");
     }
 
     #[test]
@@ -801,7 +1552,8 @@ mod csv_tests {
         record.description = "Test; with; semicolons".to_string();
 
         // Act
-        let csv_line = YPBankCsvFormat::makeup_records(&record);
+        let csv_line =
+            YPBankCsvFormat::makeup_records(&record, &WriteOptions::default(), &CANONICAL_COLUMNS);
 
         // Assert
         // Точки с запятой не экранируются, так как разделитель - запятая
@@ -828,4 +1580,433 @@ mod csv_tests {
         assert_eq!(read_records.len(), 1);
         assert_eq!(read_records[0].description, "Test; with; semicolons");
     }
+
+    #[test]
+    fn test_parse_data_line_description_in_middle() {
+        // Arrange
+        let title: Vec<String> = [
+            "TX_ID",
+            "TX_TYPE",
+            "DESCRIPTION",
+            "FROM_USER_ID",
+            "TO_USER_ID",
+            "AMOUNT",
+            "TIMESTAMP",
+            "STATUS",
+        ]
+        .map(String::from)
+        .to_vec();
+        let line =
+            "123456789,TRANSFER,\"Test, \"\"quoted\"\"\" ,1001,1002,50000,1633046400,SUCCESS";
+
+        // Act
+        let record =
+            YPBankCsvFormat::parse_data_line(&title, line, 1, &ReadOptions::default()).unwrap();
+
+        // Assert
+        let mut expected = create_test_csv_record();
+        expected.description = "Test, \"quoted\"".to_string();
+        assert_eq!(record, expected);
+    }
+
+    #[test]
+    fn test_parse_data_line_malformed_quotes() {
+        // Arrange
+        let title: Vec<String> = YPBankCsvFormat::fields().map(String::from).to_vec();
+        let cases = [
+            "123456789,TRANSFER,1001,1002,50000,1633046400,SUCCESS,\"Unclosed",
+            "123456789,TRANSFER,1001,1002,50000,1633046400,SUCCESS,\"Closed\" tail",
+        ];
+
+        for line in cases {
+            // Act
+            let result = YPBankCsvFormat::parse_data_line(&title, line, 1, &ReadOptions::default());
+
+            // Assert
+            assert!(matches!(result, Err(ParseError::ParseError { .. })));
+        }
+    }
+
+    #[test]
+    fn test_read_lenient_skips_malformed_row() {
+        // Arrange
+        let csv_data = "TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION\n\
+                       1,TRANSFER,1001,1002,50000,1633046400,SUCCESS,\"First\"\n\
+                       2,TRANSFER,1001,1002,50000,1633046400,\"Short\"\n\
+                       3,DEPOSIT,0,1003,100000,1633046401,PENDING,\"Third\"\n\
+                       4,WITHDRAWAL,1004,0,25000,1633046402,FAILURE,\"Fourth\"\n";
+
+        // Act
+        let (records, errors) = YPBankCsvFormat::read_lenient(&mut Cursor::new(csv_data)).unwrap();
+
+        // Assert
+        let ids: Vec<u64> = records.iter().map(|r| r.tx_id).collect();
+        assert_eq!(ids, vec![1, 3, 4]);
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0], ParseError::ParseError { line: 2, .. }));
+    }
+
+    #[test]
+    fn test_read_lenient_stops_at_error_cap() {
+        // Arrange
+        let mut csv_data = String::from(
+            "TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION\n\
+             1,TRANSFER,1001,1002,50000,1633046400,SUCCESS,\"First\"\n",
+        );
+        for _ in 0..100 {
+            csv_data.push_str("2,TRANSFER,1001,1002,50000,\"Broken\"\n");
+        }
+        csv_data.push_str("3,DEPOSIT,0,1003,100000,1633046401,PENDING,\"Last\"\n");
+        let options = ReadOptions {
+            max_errors: Some(5),
+            ..ReadOptions::default()
+        };
+
+        // Act
+        let (records, errors) =
+            YPBankCsvFormat::read_lenient_with(&mut Cursor::new(csv_data), &options).unwrap();
+
+        // Assert
+        assert_eq!(records.len(), 1);
+        assert_eq!(errors.len(), 6);
+        assert!(matches!(errors[4], ParseError::ParseError { line: 6, .. }));
+        assert!(matches!(
+            errors[5],
+            ParseError::ErrorLimitExceeded { limit: 5 }
+        ));
+    }
+
+    #[test]
+    fn test_decimal_amount_read_write() {
+        // Arrange
+        let options = ReadOptions {
+            decimal_amount: Some(DecimalAmount { minor_digits: 2 }),
+            ..ReadOptions::default()
+        };
+        let csv_data = "TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION\n\
+                       123456789,TRANSFER,1001,1002,123.45,1633046400,SUCCESS,\"Test transaction\"";
+        let invalid = csv_data.replace("123.45", "123.456");
+
+        // Act
+        let records = YPBankCsvFormat::read_executor_with(csv_data.to_string(), &options).unwrap();
+        let rejected = YPBankCsvFormat::read_executor_with(invalid, &options);
+        let mut buffer = Vec::new();
+        let write_options = WriteOptions {
+            decimal_amount: options.decimal_amount,
+            ..WriteOptions::default()
+        };
+        YPBankCsvFormat::write_to_with(&mut buffer, &records, &write_options).unwrap();
+
+        // Assert
+        assert_eq!(records[0].amount, 12345);
+        assert!(matches!(rejected, Err(ParseError::ParseError { .. })));
+        assert_eq!(String::from_utf8(buffer).unwrap().trim(), csv_data);
+    }
+
+    #[test]
+    fn test_read_executor_with_field_map() {
+        // Arrange
+        let field_map = FieldMap::new()
+            .with("ID", "TX_ID")
+            .with("TYPE", "TX_TYPE")
+            .with("FROM", "FROM_USER_ID")
+            .with("TO", "TO_USER_ID")
+            .with("SUM", "AMOUNT")
+            .with("TIME", "TIMESTAMP")
+            .with("RESULT", "STATUS")
+            .with("COMMENT", "DESCRIPTION");
+        let options = ReadOptions {
+            field_map: Some(field_map),
+            ..ReadOptions::default()
+        };
+        let csv_data = "ID,TYPE,FROM,TO,SUM,TIME,RESULT,COMMENT\n\
+                       123456789,TRANSFER,1001,1002,50000,1633046400,SUCCESS,\"Test transaction\"";
+
+        // Act
+        let mapped = YPBankCsvFormat::read_executor_with(csv_data.to_string(), &options).unwrap();
+        let unmapped = YPBankCsvFormat::read_executor(csv_data.to_string());
+
+        // Assert
+        assert_eq!(mapped, vec![create_test_csv_record()]);
+        assert!(matches!(unmapped, Err(ParseError::ParseError { .. })));
+    }
+
+    #[test]
+    fn test_read_executor_numeric_enum_codes() {
+        // Arrange
+        let options = ReadOptions {
+            numeric_enums: true,
+            ..ReadOptions::default()
+        };
+        let csv_data = "TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION\n\
+                       123456789,1,1001,1002,50000,1633046400,0,\"Test transaction\"\n\
+                       123456789,TRANSFER,1001,1002,50000,1633046400,SUCCESS,\"Test transaction\"";
+        let unknown_code = csv_data.replacen(",1,", ",7,", 1);
+
+        // Act
+        let records = YPBankCsvFormat::read_executor_with(csv_data.to_string(), &options).unwrap();
+        let rejected = YPBankCsvFormat::read_executor_with(unknown_code, &options);
+        let without_option = YPBankCsvFormat::read_executor(csv_data.to_string());
+
+        // Assert
+        assert_eq!(
+            records,
+            vec![create_test_csv_record(), create_test_csv_record()]
+        );
+        assert!(matches!(
+            rejected,
+            Err(ParseError::IncorrectField { ref key, .. }) if key == "TX_TYPE"
+        ));
+        assert!(without_option.is_err());
+    }
+
+    #[test]
+    fn test_read_executor_incorrect_field_column() {
+        // Arrange
+        let csv_data = "TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION\n\
+                       123456789,TRANSFER,1001,1002,50000,1633046400,SUCCESS,\"Test transaction\"\n";
+        let bad_tx_type = csv_data.replacen("TRANSFER", "GIFT", 1);
+        let bad_status = csv_data.replacen("SUCCESS", "LOST", 1);
+
+        // Act
+        let tx_type_error = YPBankCsvFormat::read_executor(bad_tx_type).unwrap_err();
+        let status_error = YPBankCsvFormat::read_executor(bad_status).unwrap_err();
+
+        // Assert
+        assert_eq!(
+            tx_type_error,
+            ParseError::incorrect_field("TX_TYPE").at_field(1, 2)
+        );
+        assert_eq!(
+            status_error,
+            ParseError::incorrect_field("STATUS").at_field(1, 7)
+        );
+        assert!(status_error.to_string().ends_with("(строка 1, колонка 7)"));
+    }
+
+    #[test]
+    fn test_write_with_column_order_and_read_any_order() {
+        // Arrange
+        let order = [
+            "DESCRIPTION",
+            "STATUS",
+            "TX_ID",
+            "AMOUNT",
+            "TX_TYPE",
+            "TIMESTAMP",
+            "TO_USER_ID",
+            "FROM_USER_ID",
+        ];
+        let write_options = WriteOptions {
+            column_order: Some(order.map(String::from).to_vec()),
+            ..WriteOptions::default()
+        };
+        let read_options = ReadOptions {
+            header_mode: CsvHeaderMode::AnyOrder,
+            ..ReadOptions::default()
+        };
+        let records = vec![create_test_csv_record(), create_deposit_csv_record()];
+
+        // Act
+        let mut buffer = Vec::new();
+        YPBankCsvFormat::write_to_with(&mut buffer, &records, &write_options).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+        let read_back = YPBankCsvFormat::read_executor_with(output.clone(), &read_options).unwrap();
+        let strict = YPBankCsvFormat::read_executor(output.clone());
+
+        // Assert
+        assert!(output.starts_with("DESCRIPTION,STATUS,TX_ID,AMOUNT,"));
+        assert_eq!(read_back, records);
+        assert!(strict.is_err());
+    }
+
+    #[test]
+    fn test_write_with_invalid_column_order() {
+        // Arrange
+        let mut unknown: Vec<String> = YPBankCsvFormat::fields().map(String::from).to_vec();
+        unknown[0] = "ID".to_string();
+        let mut duplicated = unknown.clone();
+        duplicated[0] = "TX_TYPE".to_string();
+        let missing = unknown[1..].to_vec();
+
+        for order in [unknown, duplicated, missing] {
+            let options = WriteOptions {
+                column_order: Some(order),
+                ..WriteOptions::default()
+            };
+
+            // Act
+            let result =
+                YPBankCsvFormat::write_to_with(Vec::new(), &[create_test_csv_record()], &options);
+
+            // Assert
+            assert!(matches!(result, Err(ParseError::IncorrectField { .. })));
+        }
+    }
+
+    #[test]
+    fn test_detect_delimiter_semicolon() {
+        // Arrange
+        let title = "TX_ID;TX_TYPE;FROM_USER_ID;TO_USER_ID;AMOUNT;TIMESTAMP;STATUS;DESCRIPTION";
+
+        // Act & Assert
+        assert_eq!(detect_delimiter(title), ';');
+    }
+
+    #[test]
+    fn test_detect_delimiter_tab() {
+        // Arrange
+        let title =
+            "TX_ID\tTX_TYPE\tFROM_USER_ID\tTO_USER_ID\tAMOUNT\tTIMESTAMP\tSTATUS\tDESCRIPTION";
+
+        // Act & Assert
+        assert_eq!(detect_delimiter(title), '\t');
+    }
+
+    #[test]
+    fn test_detect_delimiter_defaults_to_comma() {
+        // Arrange
+        let canonical = YPBankCsvFormat::make_title(',');
+        let ambiguous = "A,B;C,D;E,F;G,H;I,J;K,L;M,N;O";
+
+        // Act & Assert
+        assert_eq!(detect_delimiter(&canonical), ',');
+        assert_eq!(detect_delimiter(ambiguous), ',');
+        assert_eq!(detect_delimiter("TX_ID|TX_TYPE"), ',');
+    }
+
+    #[test]
+    fn test_read_iter_yields_records_lazily() {
+        // Arrange
+        let csv_data = "TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION\n\
+                        1,DEPOSIT,0,1001,500,1633046400,SUCCESS,\"Salary\"\n\
+                        2,WITHDRAWAL,1001,0,200,1633046401,PENDING,\"ATM\"\n";
+
+        // Act
+        let mut records = YPBankCsvFormat::read_iter(Cursor::new(csv_data));
+
+        // Assert
+        assert_eq!(records.next().unwrap().unwrap().tx_id, 1);
+        assert_eq!(records.next().unwrap().unwrap().tx_id, 2);
+        assert!(records.next().is_none());
+        assert!(records.next().is_none());
+    }
+
+    #[test]
+    fn test_read_iter_reports_line_and_stops() {
+        // Arrange
+        let csv_data = "TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION\n\
+                        1,DEPOSIT,0,1001,500,1633046400,SUCCESS,\"Salary\"\n\
+                        2,WITHDRAWAL,1001,0\n\
+                        3,DEPOSIT,0,1001,500,1633046400,SUCCESS,\"Bonus\"\n";
+
+        // Act
+        let records: Vec<_> = YPBankCsvFormat::read_iter(Cursor::new(csv_data)).collect();
+
+        // Assert
+        assert_eq!(records.len(), 2);
+        assert!(records[0].is_ok());
+        assert!(matches!(
+            records[1],
+            Err(ParseError::ParseError { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_read_iter_invalid_header() {
+        // Arrange
+        let csv_data = "ID,TYPE\n1,DEPOSIT\n";
+
+        // Act
+        let records: Vec<_> = YPBankCsvFormat::read_iter(Cursor::new(csv_data)).collect();
+
+        // Assert
+        assert_eq!(records.len(), 1);
+        assert!(matches!(
+            records[0],
+            Err(ParseError::ParseError { line: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_delimiter_semicolon_and_tab_round_trip() {
+        for delimiter in [';', '\t'] {
+            // Arrange
+            let mut record = create_test_csv_record();
+            record.description = format!("Rent{delimiter} May, \"paid\"");
+            let records = vec![record, create_deposit_csv_record()];
+            let mut buffer = Vec::new();
+
+            // Act
+            YPBankCsvFormat::write_to_with_delimiter(&mut buffer, &records, delimiter).unwrap();
+            let text = String::from_utf8(buffer).unwrap();
+            let result =
+                YPBankCsvFormat::read_executor_with_delimiter(text.clone(), delimiter).unwrap();
+
+            // Assert
+            assert_eq!(detect_delimiter(text.lines().next().unwrap()), delimiter);
+            assert_eq!(result, records, "разделитель {delimiter:?}");
+        }
+    }
+
+    #[test]
+    fn test_delimiter_semicolon_quoted_description() {
+        // Arrange
+        let csv_data = "TX_ID;TX_TYPE;FROM_USER_ID;TO_USER_ID;AMOUNT;TIMESTAMP;STATUS;DESCRIPTION\n\
+                        1;DEPOSIT;0;1001;500;1633046400;SUCCESS;\"a;b;c\"\n\
+                        2;WITHDRAWAL;1001;0;200;1633046401;PENDING;\"\"";
+
+        // Act
+        let result =
+            YPBankCsvFormat::read_executor_with_delimiter(csv_data.to_string(), ';').unwrap();
+        let as_comma = YPBankCsvFormat::read_executor(csv_data.to_string());
+
+        // Assert
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].description, "a;b;c");
+        assert_eq!(result[1].description, "");
+        assert!(as_comma.is_err());
+    }
+
+    #[test]
+    fn test_delimiter_tab_quoted_description() {
+        // Arrange
+        let csv_data = "TX_ID\tTX_TYPE\tFROM_USER_ID\tTO_USER_ID\tAMOUNT\tTIMESTAMP\tSTATUS\tDESCRIPTION\n\
+                        1\tDEPOSIT\t0\t1001\t500\t1633046400\tSUCCESS\t\"a\tb\"";
+
+        // Act
+        let result =
+            YPBankCsvFormat::read_executor_with_delimiter(csv_data.to_string(), '\t').unwrap();
+
+        // Assert
+        assert_eq!(result[0].description, "a\tb");
+    }
+
+    #[test]
+    fn test_read_from_limited_stops_at_first_extra_record() {
+        // Arrange
+        let records = vec![
+            create_test_csv_record(),
+            create_deposit_csv_record(),
+            create_withdrawal_csv_record(),
+        ];
+        let mut buffer = Vec::new();
+        YPBankCsvFormat::write_to(&mut buffer, &records).unwrap();
+        buffer.extend_from_slice("not,a,valid,record\n".as_bytes());
+
+        // Act
+        let over_limit = YPBankCsvFormat::read_from_limited(&mut Cursor::new(&buffer), Some(2));
+        let unlimited = YPBankCsvFormat::read_from_limited(&mut Cursor::new(&buffer), None);
+
+        // Assert
+        assert!(matches!(
+            over_limit,
+            Err(ParseError::RecordLimitExceeded { limit: 2 })
+        ));
+        assert!(unlimited.is_err());
+        assert!(!matches!(
+            unlimited,
+            Err(ParseError::RecordLimitExceeded { .. })
+        ));
+    }
 }
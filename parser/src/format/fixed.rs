@@ -0,0 +1,262 @@
+//! Запись отчёта в формате с фиксированной шириной колонок.
+//!
+//! Формат предназначен только для записи (выгрузка в унаследованные системы): каждое поле
+//! занимает заданное в [`FixedWidthSpec`] число байт, дополняется пробелами справа, разделители
+//! между полями не используются. Каждая запись завершается переводом строки.
+//!
+//! # Примеры
+//!
+//! ```no_run
+//! use std::fs::File;
+//! use parser::format::fixed::{FixedWidthSpec, write_fixed_width};
+//! use parser::models::YPBankTransaction;
+//!
+//! let records: Vec<YPBankTransaction> = Vec::new();
+//! let mut file = File::create("report.txt").unwrap();
+//! write_fixed_width(&mut file, &records, &FixedWidthSpec::default()).unwrap();
+//! ```
+
+use crate::errors::ParseError;
+use crate::models::YPBankTransaction;
+use std::io::{BufWriter, Write};
+
+/// Поведение при описании, не помещающемся в отведённую колонку.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DescriptionOverflow {
+    /// Описание обрезается по границе символа `UTF-8`.
+    #[default]
+    Truncate,
+    /// Запись прерывается ошибкой.
+    Error,
+}
+
+/// Ширина колонок (в байтах) для формата с фиксированной шириной.
+///
+/// Числовые поля и перечисления обрезать нельзя: значение, не помещающееся в колонку, всегда
+/// приводит к ошибке. Для описания поведение задаётся [`FixedWidthSpec::description_overflow`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixedWidthSpec {
+    /// Ширина колонки `TX_ID`.
+    pub tx_id: usize,
+    /// Ширина колонки `TX_TYPE`.
+    pub tx_type: usize,
+    /// Ширина колонки `FROM_USER_ID`.
+    pub from_user_id: usize,
+    /// Ширина колонки `TO_USER_ID`.
+    pub to_user_id: usize,
+    /// Ширина колонки `AMOUNT`.
+    pub amount: usize,
+    /// Ширина колонки `TIMESTAMP`.
+    pub timestamp: usize,
+    /// Ширина колонки `STATUS`.
+    pub status: usize,
+    /// Ширина колонки `DESCRIPTION`.
+    pub description: usize,
+    /// Поведение при слишком длинном описании.
+    pub description_overflow: DescriptionOverflow,
+}
+
+impl Default for FixedWidthSpec {
+    /// Ширина колонок, достаточная для любых значений числовых полей и перечислений.
+    fn default() -> Self {
+        Self {
+            tx_id: 20,
+            tx_type: 10,
+            from_user_id: 20,
+            to_user_id: 20,
+            amount: 20,
+            timestamp: 20,
+            status: 7,
+            description: 40,
+            description_overflow: DescriptionOverflow::default(),
+        }
+    }
+}
+
+impl FixedWidthSpec {
+    /// Ширина записи в байтах без учёта перевода строки.
+    pub fn record_width(&self) -> usize {
+        self.tx_id
+            + self.tx_type
+            + self.from_user_id
+            + self.to_user_id
+            + self.amount
+            + self.timestamp
+            + self.status
+            + self.description
+    }
+}
+
+/// Записывает транзакции в формате с фиксированной шириной колонок.
+///
+/// ## Returns
+///
+/// Пустой `Result` либо [`ParseError::ParseError`] с номером записи (начиная с 1), если
+/// значение не помещается в колонку.
+pub fn write_fixed_width<W: Write>(
+    writer: W,
+    records: &[YPBankTransaction],
+    spec: &FixedWidthSpec,
+) -> Result<(), ParseError> {
+    let mut buf_writer = BufWriter::new(writer);
+    for (i, record) in records.iter().enumerate() {
+        writeln!(buf_writer, "{}", makeup_record(record, spec, i + 1)?)?;
+    }
+
+    buf_writer
+        .flush()
+        .map_err(|e| ParseError::io_error(e, "Ошибка записи данных"))?;
+
+    Ok(())
+}
+
+/// Формирует строку записи ровно в [`FixedWidthSpec::record_width`] байт.
+fn makeup_record(
+    record: &YPBankTransaction,
+    spec: &FixedWidthSpec,
+    count_record: usize,
+) -> Result<String, ParseError> {
+    let description = record.description.as_deref().unwrap_or_default();
+    let description = match spec.description_overflow {
+        DescriptionOverflow::Truncate => truncate_to(description, spec.description),
+        DescriptionOverflow::Error => description,
+    };
+
+    let fields = [
+        ("TX_ID", record.tx_id.to_string(), spec.tx_id),
+        ("TX_TYPE", record.tx_type.to_string(), spec.tx_type),
+        (
+            "FROM_USER_ID",
+            record.from_user_id.to_string(),
+            spec.from_user_id,
+        ),
+        ("TO_USER_ID", record.to_user_id.to_string(), spec.to_user_id),
+        ("AMOUNT", record.amount.to_string(), spec.amount),
+        ("TIMESTAMP", record.timestamp.to_string(), spec.timestamp),
+        ("STATUS", record.status.to_string(), spec.status),
+        ("DESCRIPTION", description.to_string(), spec.description),
+    ];
+
+    let mut line = String::with_capacity(spec.record_width());
+    for (name, value, width) in fields {
+        if value.len() > width {
+            return Err(ParseError::parse_err(
+                format!("Значение поля {name} не помещается в {width} байт: {value}"),
+                count_record,
+                line.len(),
+            ));
+        }
+        line.push_str(&value);
+        line.extend(std::iter::repeat_n(' ', width - value.len()));
+    }
+
+    Ok(line)
+}
+
+/// Обрезает строку до `max_bytes` байт по границе символа.
+fn truncate_to(value: &str, max_bytes: usize) -> &str {
+    if value.len() <= max_bytes {
+        return value;
+    }
+
+    let end = (0..=max_bytes)
+        .rev()
+        .find(|&i| value.is_char_boundary(i))
+        .unwrap_or(0);
+    &value[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TxType;
+    use crate::testing::transaction;
+
+    fn compact_spec(description_overflow: DescriptionOverflow) -> FixedWidthSpec {
+        FixedWidthSpec {
+            tx_id: 6,
+            tx_type: 9,
+            from_user_id: 4,
+            to_user_id: 4,
+            amount: 6,
+            timestamp: 11,
+            status: 8,
+            description: 6,
+            description_overflow,
+        }
+    }
+
+    #[test]
+    fn test_write_fixed_width_columns() {
+        // Arrange
+        let records = vec![
+            YPBankTransaction {
+                description: Some("Rent".to_string()),
+                ..transaction(1001, TxType::Transfer, -500)
+            },
+            YPBankTransaction {
+                description: Some("Привет мир".to_string()),
+                ..transaction(1001, TxType::Transfer, -500)
+            },
+        ];
+        let spec = compact_spec(DescriptionOverflow::Truncate);
+        let mut output = Vec::new();
+
+        // Act
+        write_fixed_width(&mut output, &records, &spec).unwrap();
+
+        // Assert
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|line| line.len() == spec.record_width()));
+
+        let row = lines[0];
+        assert_eq!(&row[0..6], "1001  ");
+        assert_eq!(&row[6..15], "TRANSFER ");
+        assert_eq!(&row[15..19], "1001");
+        assert_eq!(&row[19..23], "1002");
+        assert_eq!(&row[23..29], "-500  ");
+        assert_eq!(&row[29..40], "1633046400 ");
+        assert_eq!(&row[40..48], "SUCCESS ");
+        assert_eq!(&row[48..54], "Rent  ");
+
+        // Кириллица занимает по 2 байта: в 6 байт помещаются 3 символа.
+        assert_eq!(&lines[1][48..54], "При");
+    }
+
+    #[test]
+    fn test_write_fixed_width_description_overflow_error() {
+        // Arrange
+        let records = vec![YPBankTransaction {
+            description: Some("Monthly rent".to_string()),
+            ..transaction(1001, TxType::Transfer, -500)
+        }];
+        let spec = compact_spec(DescriptionOverflow::Error);
+
+        // Act
+        let result = write_fixed_width(Vec::new(), &records, &spec);
+
+        // Assert
+        assert!(matches!(
+            result,
+            Err(ParseError::ParseError { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_write_fixed_width_numeric_overflow_error() {
+        // Arrange
+        let records = vec![transaction(1001, TxType::Transfer, -500)];
+        let spec = FixedWidthSpec {
+            tx_id: 3,
+            ..FixedWidthSpec::default()
+        };
+
+        // Act
+        let result = write_fixed_width(Vec::new(), &records, &spec);
+
+        // Assert
+        assert!(matches!(result, Err(ParseError::ParseError { .. })));
+    }
+}
@@ -0,0 +1,395 @@
+//! Запись и чтение файлов формата *.json.
+//!
+//! Предоставляет низкоуровневые методы чтения (парсинга) и записи данных через трейт
+//! [`YPBankIO`]. Для чтения и записи используются стандартные трейты ввода/вывода
+//! [`std::io::Read`] и [`Write`].
+//!
+//! * [`YPBankJsonFormat::read_from`] — чтение массива `JSON`-объектов и распаковка в отдельные
+//!   экземпляры [`YPBankJsonFormat`] каждой записи.
+//! * [`YPBankJsonFormat::write_to`] — запись элементов [`YPBankJsonFormat`] массивом
+//!   с отступами.
+//!
+//! Значения полей при чтении приводятся к строкам и разбираются так же, как в `csv` и `txt`,
+//! поэтому настройки [`ReadOptions`] действуют и здесь. Номер строки в ошибках разбора полей —
+//! номер записи в массиве (начиная с 1). Из настроек [`WriteOptions`] на запись влияет только
+//! [`WriteOptions::json_fields`] — перечень выводимых полей.
+//!
+//! # Примеры
+//!
+//! ```no_run
+//! use std::fs::File;
+//! use crate::parser::traits::YPBankIO;
+//! use parser::models::YPBankJsonFormat;
+//!
+//! let mut file = File::open("data.json").unwrap();
+//! let data = YPBankJsonFormat::read_from(&mut file).unwrap();
+//!
+//! let mut file_target = File::create("data_target.json").unwrap();
+//! YPBankJsonFormat::write_to(&mut file_target, &data).unwrap();
+//! ```
+
+use crate::errors::ParseError;
+use crate::format::options::{ReadOptions, WriteOptions};
+use crate::models::YPBankJsonFormat;
+use crate::traits::YPBankIO;
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::io::{self, BufWriter, Write};
+
+impl YPBankIO for YPBankJsonFormat {
+    type DataFormat = YPBankJsonFormat;
+
+    fn read_executor_with(
+        buffer: String,
+        options: &ReadOptions,
+    ) -> Result<Vec<Self::DataFormat>, ParseError> {
+        let objects: Vec<Map<String, Value>> = serde_json::from_str(&buffer).map_err(|e| {
+            ParseError::parse_err(format!("Ошибка разбора json: {e}"), e.line(), e.column())
+        })?;
+
+        objects
+            .into_iter()
+            .enumerate()
+            .map(|(i, object)| Self::record_from_object(object, i + 1, options))
+            .collect()
+    }
+
+    /// Записывает элементы массивом `JSON` с отступами. При заданном
+    /// [`WriteOptions::json_fields`] объекты содержат только перечисленные поля.
+    fn write_to_with<W: Write>(
+        writer: W,
+        records: &[Self::DataFormat],
+        options: &WriteOptions,
+    ) -> Result<(), ParseError> {
+        let keys = Self::selected_keys(options)?;
+        let mut buf_writer = BufWriter::new(writer);
+        match keys {
+            None => serde_json::to_writer_pretty(&mut buf_writer, records),
+            Some(keys) => records
+                .iter()
+                .map(|record| {
+                    serde_json::to_value(record).map(|object| SelectedFields {
+                        object,
+                        keys: &keys,
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .and_then(|objects| serde_json::to_writer_pretty(&mut buf_writer, &objects)),
+        }
+        .map_err(|e| ParseError::io_error(io::Error::from(e), "Ошибка записи данных"))?;
+        writeln!(buf_writer)?;
+
+        buf_writer
+            .flush()
+            .map_err(|e| ParseError::io_error(e, "Ошибка записи данных"))?;
+
+        Ok(())
+    }
+}
+
+impl YPBankJsonFormat {
+    /// Записывает данные `json`, оставляя в объектах только поля `fields` (см.
+    /// [`WriteOptions::json_fields`]). `None` — все поля.
+    pub fn write_to_with_fields<W: Write>(
+        writer: W,
+        records: &[Self],
+        fields: Option<&[&str]>,
+    ) -> Result<(), ParseError> {
+        let options = WriteOptions {
+            json_fields: fields.map(|fields| fields.iter().map(|&name| name.to_string()).collect()),
+            ..WriteOptions::default()
+        };
+        Self::write_to_with(writer, records, &options)
+    }
+
+    /// Ключи выводимых полей в каноническом порядке. `None` — перечень полей не задан.
+    fn selected_keys(options: &WriteOptions) -> Result<Option<Vec<String>>, ParseError> {
+        let Some(names) = &options.json_fields else {
+            return Ok(None);
+        };
+
+        let fields = Self::fields();
+        let mut selected = vec![false; fields.len()];
+        for name in names {
+            let position = fields
+                .iter()
+                .position(|field| field.eq_ignore_ascii_case(name.trim()))
+                .ok_or_else(|| ParseError::incorrect_field(name.as_str()))?;
+            selected[position] = true;
+        }
+
+        Ok(Some(
+            fields
+                .iter()
+                .zip(selected)
+                .filter(|(_, selected)| *selected)
+                .map(|(field, _)| field.to_lowercase())
+                .collect(),
+        ))
+    }
+
+    /// Собирает запись из `JSON`-объекта. `count_record` — номер записи для сообщений об ошибках.
+    ///
+    /// Ключи приводятся к верхнему регистру. Строки передаются как есть, числа — своим
+    /// текстовым представлением, `null` — пустой строкой.
+    fn record_from_object(
+        object: Map<String, Value>,
+        count_record: usize,
+        options: &ReadOptions,
+    ) -> Result<Self, ParseError> {
+        let mut fields = HashMap::with_capacity(object.len());
+        for (key, value) in object {
+            let key = options.canonical_key(key.to_uppercase());
+            if !Self::has_field_from_str(&key) {
+                return Err(ParseError::parse_err(
+                    format!("Некорректный ключ {key} в записи json"),
+                    count_record,
+                    0,
+                ));
+            }
+
+            let value = match value {
+                Value::String(value) => value,
+                Value::Number(value) => value.to_string(),
+                Value::Null => String::new(),
+                other => {
+                    return Err(ParseError::parse_err(
+                        format!("Недопустимое значение поля {key}: {other}"),
+                        count_record,
+                        0,
+                    ));
+                }
+            };
+            fields.insert(key, value);
+        }
+
+        options.prepare_fields(&mut fields)?;
+
+        Self::new_from_map(&fields)
+    }
+}
+
+/// Объект записи, из которого сериализуются только поля `keys` в заданном порядке.
+struct SelectedFields<'a> {
+    object: Value,
+    keys: &'a [String],
+}
+
+impl Serialize for SelectedFields<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.keys.len()))?;
+        for key in self.keys {
+            map.serialize_entry(key, &self.object[key.as_str()])?;
+        }
+        map.end()
+    }
+}
+
+/// Сериализует значение строкой его [`Display`]-представления (для перечислений).
+pub(crate) fn serialize_display<T: Display, S: Serializer>(
+    value: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{TxStatus, TxType};
+    use std::io::Cursor;
+
+    fn create_json_record(tx_id: u64, description: &str) -> YPBankJsonFormat {
+        YPBankJsonFormat {
+            tx_id,
+            tx_type: TxType::Transfer,
+            from_user_id: 1001,
+            to_user_id: 1002,
+            amount: 50000,
+            timestamp: 1633046400,
+            status: TxStatus::Success,
+            description: description.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_read_executor_single_record() {
+        // Arrange
+        let json_data = r#"[
+            {
+                "tx_id": 123456789,
+                "tx_type": "TRANSFER",
+                "from_user_id": 1001,
+                "to_user_id": 1002,
+                "amount": 50000,
+                "timestamp": 1633046400,
+                "status": "SUCCESS",
+                "description": "Test transaction"
+            }
+        ]"#;
+
+        // Act
+        let result = YPBankJsonFormat::read_executor(json_data.to_string()).unwrap();
+
+        // Assert
+        assert_eq!(
+            result,
+            vec![create_json_record(123456789, "Test transaction")]
+        );
+    }
+
+    #[test]
+    fn test_write_to_pretty_uppercase_enums() {
+        // Arrange
+        let records = vec![create_json_record(1, "Rent")];
+        let mut output = Vec::new();
+
+        // Act
+        YPBankJsonFormat::write_to(&mut output, &records).unwrap();
+
+        // Assert
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with("[\n  {\n    \"tx_id\": 1,"));
+        assert!(output.contains("\"tx_type\": \"TRANSFER\""));
+        assert!(output.contains("\"status\": \"SUCCESS\""));
+        assert!(output.contains("\"amount\": 50000"));
+    }
+
+    #[test]
+    fn test_write_to_with_fields_whitelist() {
+        // Arrange
+        let records = vec![create_json_record(1, "Rent"), create_json_record(2, "Fee")];
+        let mut output = Vec::new();
+
+        // Act
+        YPBankJsonFormat::write_to_with_fields(&mut output, &records, Some(&["AMOUNT", "tx_id"]))
+            .unwrap();
+
+        // Assert
+        let objects: Vec<Map<String, Value>> = serde_json::from_slice(&output).unwrap();
+        assert_eq!(objects.len(), 2);
+        for (object, record) in objects.iter().zip(&records) {
+            let keys: Vec<&str> = object.keys().map(String::as_str).collect();
+            assert_eq!(keys, ["amount", "tx_id"]);
+            assert_eq!(object["tx_id"], record.tx_id);
+            assert_eq!(object["amount"], record.amount);
+        }
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.find("\"tx_id\"").unwrap() < output.find("\"amount\"").unwrap());
+    }
+
+    #[test]
+    fn test_write_to_with_fields_none_and_unknown() {
+        // Arrange
+        let records = vec![create_json_record(1, "Rent")];
+        let mut all_fields = Vec::new();
+        let mut plain = Vec::new();
+        YPBankJsonFormat::write_to(&mut plain, &records).unwrap();
+
+        // Act
+        YPBankJsonFormat::write_to_with_fields(&mut all_fields, &records, None).unwrap();
+        let unknown = YPBankJsonFormat::write_to_with_fields(
+            Vec::new(),
+            &records,
+            Some(&["tx_id", "currency"]),
+        );
+
+        // Assert
+        assert_eq!(all_fields, plain);
+        assert!(matches!(unknown, Err(ParseError::IncorrectField { .. })));
+    }
+
+    #[test]
+    fn test_write_read_round_trip() {
+        // Arrange
+        let records = vec![
+            create_json_record(1, "Test \"quoted\", with comma\nand newline"),
+            create_json_record(2, ""),
+            YPBankJsonFormat {
+                tx_type: TxType::Deposit,
+                from_user_id: 0,
+                status: TxStatus::Pending,
+                amount: u64::MAX,
+                ..create_json_record(3, "Привет")
+            },
+        ];
+        let mut buffer = Vec::new();
+
+        // Act
+        YPBankJsonFormat::write_to(&mut buffer, &records).unwrap();
+        let result = YPBankJsonFormat::read_from(&mut Cursor::new(buffer)).unwrap();
+
+        // Assert
+        assert_eq!(result, records);
+    }
+
+    #[test]
+    fn test_read_executor_null_description() {
+        // Arrange
+        let json_data = r#"[{"tx_id": 1, "tx_type": "DEPOSIT", "from_user_id": 0,
+            "to_user_id": 1001, "amount": 500, "timestamp": 1633046400,
+            "status": "SUCCESS", "description": null}]"#;
+
+        // Act
+        let result = YPBankJsonFormat::read_executor(json_data.to_string()).unwrap();
+
+        // Assert
+        assert_eq!(result[0].description, "");
+    }
+
+    #[test]
+    fn test_read_executor_invalid_json() {
+        // Arrange
+        let json_data = "[{\"tx_id\": 1,\n\"tx_type\": }]";
+
+        // Act
+        let result = YPBankJsonFormat::read_executor(json_data.to_string());
+
+        // Assert
+        assert!(matches!(
+            result,
+            Err(ParseError::ParseError { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_read_executor_unknown_key() {
+        // Arrange
+        let json_data = r#"[{"tx_id": 1, "currency": "RUB"}]"#;
+
+        // Act
+        let result = YPBankJsonFormat::read_executor(json_data.to_string());
+
+        // Assert
+        assert!(matches!(
+            result,
+            Err(ParseError::ParseError { line: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_read_executor_invalid_status() {
+        // Arrange
+        let json_data = r#"[{"tx_id": 1, "tx_type": "DEPOSIT", "from_user_id": 0,
+            "to_user_id": 1001, "amount": 500, "timestamp": 1633046400,
+            "status": "DONE", "description": ""}]"#;
+
+        // Act
+        let result = YPBankJsonFormat::read_executor(json_data.to_string());
+
+        // Assert
+        assert!(matches!(result, Err(ParseError::IncorrectField { .. })));
+    }
+
+    #[test]
+    fn test_read_from_empty_array() {
+        // Act
+        let result = YPBankJsonFormat::read_from(&mut Cursor::new("[]"));
+
+        // Assert
+        assert!(matches!(result, Err(ParseError::EmptyData)));
+    }
+}
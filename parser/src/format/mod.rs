@@ -1,5 +1,9 @@
 //! Обеспечивающие обработку форматов модули (чтение и парсинг, запись).
 pub mod bin;
+pub mod canonical_json;
 pub mod csv;
+pub mod fixed;
+pub mod json;
+pub mod options;
 pub mod text;
-mod tools;
+pub(crate) mod tools;
@@ -0,0 +1,322 @@
+//! Настройки чтения и записи текстовых форматов (`csv`, `txt`).
+//!
+//! Значения по умолчанию ([`ReadOptions::default`], [`WriteOptions::default`]) соответствуют
+//! поведению [`YPBankIO::read_from`](crate::traits::YPBankIO::read_from) и
+//! [`YPBankIO::write_to`](crate::traits::YPBankIO::write_to).
+
+use crate::errors::ParseError;
+use crate::models::{TxStatus, TxType};
+use std::collections::HashMap;
+
+/// Представление суммы десятичной строкой (например, `123.45`).
+///
+/// Внутри библиотеки сумма по-прежнему хранится целым числом минимальных единиц (копеек,
+/// центов): при чтении `123.45` с `minor_digits: 2` превращается в `12345`, при записи —
+/// обратно.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecimalAmount {
+    /// Количество знаков после десятичной точки.
+    pub minor_digits: u32,
+}
+
+impl DecimalAmount {
+    /// Разбирает десятичную строку в целое число минимальных единиц.
+    ///
+    /// Целая сумма без точки допускается. Отрицательные значения и дробная часть длиннее
+    /// `minor_digits` отклоняются.
+    ///
+    /// ## Пример
+    ///
+    /// ```
+    /// use parser::format::options::DecimalAmount;
+    ///
+    /// let decimal = DecimalAmount { minor_digits: 2 };
+    /// assert_eq!(decimal.parse("123.45").unwrap(), 12345);
+    /// assert_eq!(decimal.parse("7").unwrap(), 700);
+    /// assert!(decimal.parse("123.456").is_err());
+    /// ```
+    pub fn parse(&self, value: &str) -> Result<u64, ParseError> {
+        let incorrect = || ParseError::incorrect_field("AMOUNT");
+
+        let (whole, fraction) = match value.split_once('.') {
+            Some((whole, fraction)) if !fraction.is_empty() => (whole, fraction),
+            Some(_) => return Err(incorrect()),
+            None => (value, ""),
+        };
+
+        if fraction.len() > self.minor_digits as usize {
+            return Err(ParseError::parse_err(
+                format!(
+                    "Сумма {value} содержит больше {} знаков после точки",
+                    self.minor_digits
+                ),
+                0,
+                0,
+            ));
+        }
+
+        let all_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+        if whole.is_empty() || !all_digits(whole) || !all_digits(fraction) {
+            return Err(incorrect());
+        }
+
+        let scale = self.scale()?;
+        let padding = 10u64.pow(self.minor_digits - fraction.len() as u32);
+        let whole: u64 = whole.parse().map_err(|_| incorrect())?;
+        let fraction: u64 = if fraction.is_empty() {
+            0
+        } else {
+            fraction.parse().map_err(|_| incorrect())?
+        };
+
+        whole
+            .checked_mul(scale)
+            .and_then(|w| w.checked_add(fraction * padding))
+            .ok_or_else(|| ParseError::over_flow_size("decimal", "u64", value))
+    }
+
+    /// Форматирует целое число минимальных единиц десятичной строкой.
+    ///
+    /// ```
+    /// use parser::format::options::DecimalAmount;
+    ///
+    /// let decimal = DecimalAmount { minor_digits: 2 };
+    /// assert_eq!(decimal.format(12345), "123.45");
+    /// assert_eq!(decimal.format(5), "0.05");
+    /// ```
+    pub fn format(&self, amount: u64) -> String {
+        if self.minor_digits == 0 {
+            return amount.to_string();
+        }
+
+        let digits = self.minor_digits as usize;
+        let padded = format!("{amount:0>width$}", width = digits + 1);
+        let (whole, fraction) = padded.split_at(padded.len() - digits);
+        format!("{whole}.{fraction}")
+    }
+
+    /// Множитель для перевода целой части в минимальные единицы.
+    fn scale(&self) -> Result<u64, ParseError> {
+        10u64
+            .checked_pow(self.minor_digits)
+            .ok_or_else(|| ParseError::over_flow_size("decimal", "u64", self.minor_digits))
+    }
+}
+
+/// Соответствие внешних имён полей каноническим (`TX_ID`, `AMOUNT` и т.д.).
+///
+/// Позволяет читать файлы, в которых колонки `csv` или ключи `txt` названы иначе, без
+/// переименования исходных данных. Имена сравниваются без учёта регистра; поля, не указанные
+/// в соответствии, используются как есть.
+///
+/// ## Пример
+///
+/// ```
+/// use parser::format::options::FieldMap;
+///
+/// let field_map = FieldMap::new().with("ID", "TX_ID").with("Sum", "AMOUNT");
+///
+/// assert_eq!(field_map.canonical("id"), "TX_ID");
+/// assert_eq!(field_map.canonical("SUM"), "AMOUNT");
+/// assert_eq!(field_map.canonical("STATUS"), "STATUS");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FieldMap {
+    names: HashMap<String, String>,
+}
+
+impl FieldMap {
+    /// Создаёт пустое соответствие.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Добавляет соответствие внешнего имени `external` каноническому `canonical`.
+    pub fn with(mut self, external: &str, canonical: &str) -> Self {
+        self.names
+            .insert(external.to_uppercase(), canonical.to_uppercase());
+        self
+    }
+
+    /// Возвращает каноническое имя поля для внешнего имени `name`.
+    pub fn canonical(&self, name: &str) -> String {
+        let upper = name.to_uppercase();
+        self.names.get(&upper).cloned().unwrap_or(upper)
+    }
+}
+
+/// Требования к заголовку `csv` при чтении.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CsvHeaderMode {
+    /// Заголовок должен совпадать с каноническим, включая порядок колонок.
+    #[default]
+    Strict,
+    /// Колонки могут идти в любом порядке, но каждое поле должно быть указано ровно один раз.
+    AnyOrder,
+}
+
+/// Настройки чтения текстовых форматов.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReadOptions {
+    /// Максимальное количество записей. `None` — без ограничений.
+    pub max_records: Option<usize>,
+
+    /// Суммы записаны десятичными строками. `None` — целыми числами.
+    pub decimal_amount: Option<DecimalAmount>,
+
+    /// Соответствие внешних имён полей каноническим. `None` — требуются канонические имена.
+    pub field_map: Option<FieldMap>,
+
+    /// Допускать числовые коды в `TX_TYPE` и `STATUS` (например, `1` для `TRANSFER`). Значения
+    /// из одних цифр разбираются как коды, остальные — как имена.
+    pub numeric_enums: bool,
+
+    /// Требования к заголовку `csv`.
+    pub header_mode: CsvHeaderMode,
+
+    /// Максимальное количество ошибок при чтении в режиме восстановления
+    /// ([`YPBankCsvFormat::read_lenient_with`](crate::models::YPBankCsvFormat::read_lenient_with)).
+    /// `None` — без ограничений.
+    pub max_errors: Option<usize>,
+
+    /// Разделитель колонок `csv`. `None` — запятая.
+    pub delimiter: Option<char>,
+}
+
+impl ReadOptions {
+    /// Разделитель колонок `csv` с учётом значения по умолчанию.
+    pub(crate) fn csv_delimiter(&self) -> char {
+        self.delimiter.unwrap_or(',')
+    }
+
+    /// Каноническое имя поля с учётом [`ReadOptions::field_map`].
+    pub(crate) fn canonical_key(&self, name: String) -> String {
+        match &self.field_map {
+            Some(field_map) => field_map.canonical(&name),
+            None => name,
+        }
+    }
+
+    /// Приводит значения полей к виду, который ожидает `new_from_map` структуры формата.
+    pub(crate) fn prepare_fields(
+        &self,
+        fields: &mut HashMap<String, String>,
+    ) -> Result<(), ParseError> {
+        if let Some(decimal) = self.decimal_amount {
+            if let Some(amount) = fields.get_mut("AMOUNT") {
+                *amount = decimal.parse(amount)?.to_string();
+            }
+        }
+
+        if self.numeric_enums {
+            replace_numeric_code(fields, "TX_TYPE", |code| {
+                TxType::from_u8(code).map(|t| t.to_string())
+            })?;
+            replace_numeric_code(fields, "STATUS", |code| {
+                TxStatus::from_u8(code).map(|s| s.to_string())
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Заменяет числовой код перечисления в поле `key` его именем.
+fn replace_numeric_code(
+    fields: &mut HashMap<String, String>,
+    key: &str,
+    name_of: impl Fn(u8) -> Option<String>,
+) -> Result<(), ParseError> {
+    let Some(value) = fields.get_mut(key) else {
+        return Ok(());
+    };
+
+    if value.is_empty() || !value.chars().all(|c| c.is_ascii_digit()) {
+        return Ok(());
+    }
+
+    *value = value
+        .parse::<u8>()
+        .ok()
+        .and_then(name_of)
+        .ok_or_else(|| ParseError::incorrect_field(key))?;
+
+    Ok(())
+}
+
+/// Настройки записи текстовых форматов.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WriteOptions {
+    /// Записывать суммы десятичными строками. `None` — целыми числами.
+    pub decimal_amount: Option<DecimalAmount>,
+
+    /// Порядок колонок `csv` (имена полей). `None` — канонический порядок.
+    pub column_order: Option<Vec<String>>,
+
+    /// Разделитель колонок `csv`. `None` — запятая.
+    pub delimiter: Option<char>,
+
+    /// Записывать `TIMESTAMP` в `txt` строкой ISO-8601 в UTC (`2021-10-01T00:00:00Z`, см.
+    /// [`format_iso8601`](crate::utils::format_iso8601)) для просмотра человеком. `false` — числом секунд. Чтение `txt`
+    /// принимает оба вида.
+    pub iso_timestamps: bool,
+
+    /// Поля записей `json` (имена без учёта регистра). `None` — все поля. Поля выводятся
+    /// в каноническом порядке, неизвестное имя — ошибка [`ParseError::IncorrectField`].
+    pub json_fields: Option<Vec<String>>,
+}
+
+impl WriteOptions {
+    /// Разделитель колонок `csv` с учётом значения по умолчанию.
+    pub(crate) fn csv_delimiter(&self) -> char {
+        self.delimiter.unwrap_or(',')
+    }
+
+    /// Строковое представление суммы для записи.
+    pub(crate) fn format_amount(&self, amount: u64) -> String {
+        match self.decimal_amount {
+            Some(decimal) => decimal.format(amount),
+            None => amount.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decimal_amount_parse() {
+        // Arrange
+        let decimal = DecimalAmount { minor_digits: 2 };
+
+        // Act & Assert
+        assert_eq!(decimal.parse("123.45").unwrap(), 12345);
+        assert_eq!(decimal.parse("123.4").unwrap(), 12340);
+        assert_eq!(decimal.parse("0.05").unwrap(), 5);
+        assert_eq!(decimal.parse("123").unwrap(), 12300);
+        assert!(matches!(
+            decimal.parse("123.456"),
+            Err(ParseError::ParseError { .. })
+        ));
+        for invalid in ["", "12.", ".5", "-1.00", "1,00", "1.2a"] {
+            assert!(decimal.parse(invalid).is_err(), "{invalid}");
+        }
+        assert!(matches!(
+            decimal.parse("184467440737095516.16"),
+            Err(ParseError::OverflowSize { .. })
+        ));
+    }
+
+    #[test]
+    fn test_decimal_amount_format() {
+        // Arrange
+        let decimal = DecimalAmount { minor_digits: 2 };
+
+        // Act & Assert
+        assert_eq!(decimal.format(12345), "123.45");
+        assert_eq!(decimal.format(100), "1.00");
+        assert_eq!(decimal.format(0), "0.00");
+        assert_eq!(DecimalAmount { minor_digits: 0 }.format(42), "42");
+    }
+}
@@ -6,8 +6,33 @@
 //!
 //! * [`YPBankTextFormat::read_from`] — чтение (парсинг) данных в формате CSV и распаковка в
 //!   отдельные экземпляры [`YPBankTextFormat`] каждой записи
+//! * [`YPBankTextFormat::read_iter`] — потоковое чтение: записи читаются по одной, без
+//!   загрузки всего файла в память.
 //! * [`YPBankTextFormat::write_to`] — запись предоставленных элементов [`YPBankTextFormat`].
 //!
+//! ## Кавычки в описании
+//!
+//! Значение `DESCRIPTION` записывается в двойных кавычках, а каждая кавычка внутри описания
+//! удваивается: `"hi"` сохраняется как `DESCRIPTION: """hi"""`. При чтении обрамляющие кавычки
+//! снимаются и пары внутренних кавычек заменяются одной, поэтому любое описание (в том числе
+//! состоящее только из кавычек) переживает запись и чтение без изменений. Одиночная кавычка
+//! внутри значения — ошибка формата строки.
+//!
+//! ## Управляющие символы в описании
+//!
+//! Каждое поле занимает одну строку, поэтому перевод строки, возврат каретки и табуляция в
+//! описании записываются последовательностями `\n`, `\r` и `\t`, а обратная косая черта —
+//! как `\\`. Описание `line1` + перевод строки + `line2` сохраняется как
+//! `DESCRIPTION: "line1\nline2"` и читается обратно без изменений. Неизвестная
+//! последовательность после `\` читается как есть, поэтому файлы, записанные до появления
+//! экранирования, по-прежнему читаются (`C:\data` остаётся `C:\data`).
+//!
+//! ## Метка времени
+//!
+//! По умолчанию `TIMESTAMP` записывается числом секунд от начала эпохи UNIX. С настройкой
+//! [`WriteOptions::iso_timestamps`] — строкой ISO-8601 в UTC (`TIMESTAMP: 2021-10-01T00:00:00Z`).
+//! При чтении принимаются оба вида.
+//!
 //! # Примеры
 //!
 //! ```no_run
@@ -22,13 +47,24 @@
 //! YPBankTextFormat::write_to(&mut file_target, &data);
 //! ```
 
+use crate::MAX_SIZE_CSV_TXT_BYTES;
 use crate::errors::ParseError;
-use crate::format::tools::LineUtils;
-use crate::models::YPBankTextFormat;
-use crate::traits::YPBankIO;
+use crate::format::options::{ReadOptions, WriteOptions};
+use crate::format::tools::{
+    LineUtils, collect_records, normalize_line_endings, validate_exceed_max_bytes,
+};
+use crate::models::{YPBankTextFormat, YPBankTextRecord, YPBankTransaction};
+use crate::traits::{TxKind, YPBankIO};
+use crate::utils::{format_iso8601, parse_iso8601};
 use regex::Regex;
-use std::collections::HashMap;
-use std::io::{BufWriter, Write};
+use std::borrow::Borrow;
+use std::collections::{HashMap, VecDeque};
+use std::io::{self, BufRead, BufReader, BufWriter, Lines, Read, Write};
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+/// Формирование записи из полей блока: ключ — имя поля в верхнем регистре, значение — строка.
+pub(crate) type FieldsBuilder<T> = fn(&HashMap<String, String>) -> Result<T, ParseError>;
 
 impl YPBankIO for YPBankTextFormat {
     /// Парсинг (чтение) данных в формате `txt`.
@@ -36,71 +72,156 @@ impl YPBankIO for YPBankTextFormat {
     /// Возвращает вектор экземпляров `YPBankTextFormat`, содержащих все записи из источника.
     type DataFormat = YPBankTextFormat;
 
-    fn read_executor(buffer: String) -> Result<Vec<YPBankTextFormat>, ParseError> {
-        let mut transaction: Vec<YPBankTextFormat> = Vec::new();
-
-        let mut block_buffer: Vec<String> = Vec::new();
-        for (count, line) in buffer.lines().enumerate() {
-            if line.is_empty_line() {
-                continue;
-            }
+    fn read_executor_with(
+        buffer: String,
+        options: &ReadOptions,
+    ) -> Result<Vec<YPBankTextFormat>, ParseError> {
+        Self::parse_records(buffer, options, YPBankTextFormat::new_from_map)
+    }
 
-            match (block_buffer.is_empty(), line.is_hash_marker()) {
-                (true, true) => {
-                    // Начало блока.
-                    let title = Self::parse_title(line, count)?;
-                    block_buffer.push(title);
-                }
-                (false, true) => {
-                    // Буфер собрали. Надо отдать его на обработку и обнулить.
-                    let block_data = Self::parse_block(&block_buffer, count)?;
-                    transaction.push(block_data);
-                    block_buffer.clear(); // Обработанные данные.
-
-                    let title = Self::parse_title(line, count)?; // Новый цикл.
-                    block_buffer.push(title);
-                }
-                (false, false) => {
-                    // Внутри блока.
-                    block_buffer.push(line.to_string());
-                }
-                (true, false) => {
-                    return Err(ParseError::parse_err(
-                        format!("Некорректная строка: {line}"),
-                        count + 1,
-                        0,
-                    ));
-                }
-            }
-        }
+    /// Добавить записи на основе предоставленного экземпляра `YPBankTextFormat`.
+    fn write_to_with<W: Write>(
+        writer: W,
+        records: &[Self::DataFormat],
+        options: &WriteOptions,
+    ) -> Result<(), ParseError> {
+        Self::write_iter_with(writer, records.iter().map(Ok), options)?;
 
-        if !block_buffer.is_empty() {
-            let block_data = Self::parse_block(&block_buffer, buffer.lines().count())?;
-            transaction.push(block_data);
-        }
+        Ok(())
+    }
+}
 
-        Ok(transaction)
+impl YPBankTextFormat {
+    /// Потоковая запись: каждая запись из `records` пишется сразу после получения, поэтому
+    /// данные не накапливаются в памяти. Первая ошибка источника прерывает запись.
+    ///
+    /// Возвращает число записанных записей.
+    pub fn write_iter<W: Write>(
+        writer: W,
+        records: impl IntoIterator<Item = Result<Self, ParseError>>,
+    ) -> Result<usize, ParseError> {
+        Self::write_iter_with(writer, records, &WriteOptions::default())
     }
 
-    /// Добавить записи на основе предоставленного экземпляра `YPBankTextFormat`.
-    fn write_to<W: Write>(writer: W, records: &[Self::DataFormat]) -> Result<(), ParseError> {
+    /// Потоковая запись с настройками `options`, см. [`YPBankTextFormat::write_iter`].
+    pub(crate) fn write_iter_with<W: Write, B: Borrow<Self>>(
+        writer: W,
+        records: impl IntoIterator<Item = Result<B, ParseError>>,
+        options: &WriteOptions,
+    ) -> Result<usize, ParseError> {
         let mut buf_writer = BufWriter::new(writer);
+        let mut count = 0;
         for record in records {
-            writeln!(buf_writer, "{}", Self::makeup_records(record))?;
+            writeln!(
+                buf_writer,
+                "{}",
+                Self::makeup_records(record?.borrow(), options)
+            )?;
+            count += 1;
         }
 
-        Ok(())
+        buf_writer
+            .flush()
+            .map_err(|e| ParseError::io_error(e, "Ошибка записи данных"))?;
+
+        Ok(count)
+    }
+
+    /// Разбор данных на блоки записей. Каждый блок превращается в запись функцией `build`,
+    /// получающей поля блока (см. [`YPBankTextFormat::new_from_map`]).
+    ///
+    /// Разбор прекращается на первом блоке сверх [`ReadOptions::max_records`] с ошибкой
+    /// [`ParseError::RecordLimitExceeded`].
+    pub(crate) fn parse_records<T>(
+        buffer: String,
+        options: &ReadOptions,
+        build: FieldsBuilder<T>,
+    ) -> Result<Vec<T>, ParseError> {
+        let records = TextBlocks::new(buffer.lines().map(|line| Ok(line.to_string())))
+            .map(|block| Self::parse_block(&block?, options, build));
+        collect_records(records, options.max_records)
+    }
+
+    /// Потоковое чтение данных в формате `txt`: каждый вызов `next()` читает и разбирает
+    /// один блок записи.
+    ///
+    /// В отличие от [`YPBankTextFormat::read_from`], данные не загружаются в память целиком
+    /// и ограничение на их объём не действует. Окончания строк `\r\n` и одиночный `\r`
+    /// допускаются, как и при чтении целиком. После любой ошибки итерация завершается.
+    ///
+    /// ## Пример
+    ///
+    /// ```no_run
+    /// use std::fs::File;
+    /// use parser::models::YPBankTextFormat;
+    ///
+    /// let file = File::open("data.txt").unwrap();
+    /// for record in YPBankTextFormat::read_iter(file) {
+    ///     println!("{}", record.unwrap().tx_id);
+    /// }
+    /// ```
+    pub fn read_iter<R: Read>(reader: R) -> impl Iterator<Item = Result<Self, ParseError>> {
+        Self::read_iter_positioned(reader, ReadOptions::default())
+            .map(|item| item.map(|(_, record)| record))
+    }
+
+    /// Потоковое чтение с настройками `options`: вместе с записью возвращается номер строки
+    /// заголовка её блока (начиная с 1). Ограничение [`ReadOptions::max_records`] не
+    /// проверяется.
+    pub(crate) fn read_iter_positioned<R: Read>(
+        reader: R,
+        options: ReadOptions,
+    ) -> impl Iterator<Item = Result<(usize, Self), ParseError>> {
+        TextRecordIter {
+            blocks: TextBlocks::new(BufReader::new(reader).lines()),
+            options,
+            finished: false,
+        }
+    }
+
+    /// Проверяет, что данные завершаются целой записью.
+    ///
+    /// Разбирается только последний блок: оборванный блок лишён части полей или содержит
+    /// неполную строку.
+    pub(crate) fn is_complete<R: Read>(reader: &mut R) -> Result<bool, ParseError> {
+        let mut buffer = String::new();
+        BufReader::new(reader)
+            .read_to_string(&mut buffer)
+            .map_err(|e| ParseError::io_error(e, "Ошибка парсинга данных"))?;
+        validate_exceed_max_bytes(buffer.len(), MAX_SIZE_CSV_TXT_BYTES)?;
+        let buffer = normalize_line_endings(buffer);
+
+        let lines: Vec<&str> = buffer.strip_bom().lines().collect();
+        let is_title = |line: &&str| Self::parse_title(line).is_some();
+        let Some(title_index) = lines.iter().rposition(is_title) else {
+            return Ok(lines
+                .iter()
+                .all(|line| line.is_empty_line() || line.is_hash_marker()));
+        };
+
+        let block: Vec<(usize, String)> = (title_index + 1..)
+            .zip(&lines[title_index..])
+            .filter(|(_, line)| !line.is_empty_line() && (!line.is_hash_marker() || is_title(line)))
+            .map(|(line_number, line)| (line_number, line.to_string()))
+            .collect();
+        let options = ReadOptions::default();
+
+        Ok(Self::parse_block(&block, &options, YPBankTextFormat::new_from_map).is_ok())
     }
-}
 
-impl YPBankTextFormat {
     /// Парсинг отдельного блока информации.
     ///
     /// # Аргументы
     ///
-    /// * `block` — вектор со строками блока для парсинга. Нулевая запись вектора это технические
-    ///   данные. Например, вид операции из заголовка блока.
-    /// * `end_line` — номер последней линии блока.
+    /// * `block` — вектор со строками блока для парсинга и их номерами в исходных данных
+    ///   (начиная с 1). Нулевая запись вектора это технические данные. Например, вид операции
+    ///   из заголовка блока.
+    /// * `options` — настройки чтения.
+    /// * `build` — формирование записи из полей блока.
+    ///
+    /// Если в блоке нет какого-либо из полей, возвращается [`ParseError::ParseError`] со списком
+    /// отсутствующих полей и номером строки заголовка блока. Некорректное значение присутствующего
+    /// поля — ошибка [`ParseError::IncorrectField`].
     ///
     /// ## Образец блока:
     /// ```plain
@@ -114,17 +235,24 @@ impl YPBankTextFormat {
     /// AMOUNT: 100
     /// STATUS: FAILURE
     /// ```
-    fn parse_block(block: &[String], end_line: usize) -> Result<YPBankTextFormat, ParseError> {
+    fn parse_block<T>(
+        block: &[(usize, String)],
+        options: &ReadOptions,
+        build: FieldsBuilder<T>,
+    ) -> Result<T, ParseError> {
         let mut fields = HashMap::new();
-        let first_line = end_line - block.len();
+        let mut field_lines = HashMap::new();
+        let first_line = block[0].0;
 
-        for (count, line) in (1..).zip(block[1..].iter()) {
+        for (line_number, line) in &block[1..] {
+            let line_number = *line_number;
             if let Some((key, value)) = line.split_into_key_value() {
+                let key = options.canonical_key(key);
                 // Подбор и проверка полей.
                 if !YPBankTextFormat::has_field_from_str(&key) {
                     return Err(ParseError::parse_err(
                         format!("Некорректный ключ {key} в строке: {line}"),
-                        first_line + count,
+                        line_number,
                         0,
                     ));
                 }
@@ -133,58 +261,111 @@ impl YPBankTextFormat {
                 if fields.contains_key(&key) {
                     return Err(ParseError::parse_err(
                         format!("Дублирование ключа: {key} в строке: {line}"),
-                        first_line + count,
+                        line_number,
                         0,
                     ));
                 }
 
+                field_lines.insert(key.clone(), line_number);
                 fields.insert(key, value);
             } else {
                 return Err(ParseError::parse_err(
                     format!("Неверный формат строки txt: {}", line),
-                    first_line + count,
+                    line_number,
                     0,
                 ));
             }
         }
 
-        let result = YPBankTextFormat::new_from_map(&fields)?;
+        // Отсутствие поля сообщается отдельно от некорректного значения.
+        let missing: Vec<&str> = YPBankTextFormat::fields()
+            .into_iter()
+            .filter(|name| !fields.contains_key(*name))
+            .collect();
+        if !missing.is_empty() {
+            return Err(ParseError::parse_err(
+                format!(
+                    "В записи отсутствуют обязательные поля: {}",
+                    missing.join(", ")
+                ),
+                first_line,
+                0,
+            ));
+        }
+
+        // Метка времени может быть записана строкой ISO-8601 (WriteOptions::iso_timestamps).
+        if let Some(timestamp) = fields.get_mut("TIMESTAMP") {
+            if let Some(seconds) = parse_iso8601(timestamp) {
+                *timestamp = seconds.to_string();
+            }
+        }
+
+        // Некорректное поле сообщается с номером строки, на которой оно записано.
+        let locate = |err: ParseError| {
+            let line = match &err {
+                ParseError::IncorrectField { key, .. } => {
+                    field_lines.get(key).copied().unwrap_or(first_line)
+                }
+                _ => 0,
+            };
+            err.at_field(line, 0)
+        };
+
+        options.prepare_fields(&mut fields).map_err(locate)?;
 
-        Ok(result)
+        build(&fields).map_err(locate)
     }
 
     /// Парсинг заголовка сообщения.
     ///
-    /// Возвращает `String` с названием операции, если парсинг успешен или `ParseError`,
-    /// если возникли ошибки.
+    /// Возвращает `String` с названием операции, если строка — заголовок записи, или `None`,
+    /// если нет (например, это комментарий).
     ///
     /// ## Образец заголовка
     ///
     /// ```plain
-    /// ## Record 1 (DEPOSIT)
+    /// # Record 1 (DEPOSIT)
     /// ```
-    fn parse_title(line: &str, count_line: usize) -> Result<String, ParseError> {
-        let re = Regex::new(r#"^#\s*Record\s+\d+\s*\((?P<tx_type>[^)]+)\)$"#)
-            .expect("Ошибка в регулярном выражении парсинга заголовка блоков формата TXT");
-
-        re.captures(line)
+    pub(crate) fn parse_title(line: &str) -> Option<String> {
+        static TITLE_RE: LazyLock<Regex> = LazyLock::new(|| {
+            Regex::new(r#"^#\s*Record\s+\d+\s*\((?P<tx_type>[^)]+)\)$"#)
+                .expect("Ошибка в регулярном выражении парсинга заголовка блоков формата TXT")
+        });
+
+        TITLE_RE
+            .captures(line)
             .and_then(|caps| caps.name("tx_type"))
             .map(|m| m.as_str().to_string())
-            .ok_or_else(|| {
-                ParseError::parse_err(
-                    format!("Некорректная строка заголовка: {}", line),
-                    count_line,
-                    0,
-                )
-            })
     }
 
     /// Подготовить единицу записи к публикации.
-    fn makeup_records(records: &YPBankTextFormat) -> String {
-        let mut copy_records = records.clone();
-        copy_records.description = copy_records.description.escaped_quote();
-
-        format!("{}\n{}", Self::make_title(records), copy_records)
+    ///
+    /// Поля выводятся в порядке [`YPBankTextFormat`], значение `AMOUNT` — с учётом
+    /// [`WriteOptions::decimal_amount`], `TIMESTAMP` — с учётом [`WriteOptions::iso_timestamps`].
+    fn makeup_records(records: &YPBankTextFormat, options: &WriteOptions) -> String {
+        let description = records.description.escaped_control().escaped_quote();
+        let timestamp = if options.iso_timestamps {
+            format_iso8601(records.timestamp)
+        } else {
+            records.timestamp.to_string()
+        };
+        let fields = [
+            ("TX_ID", records.tx_id.to_string()),
+            ("TX_TYPE", records.tx_type.to_string()),
+            ("FROM_USER_ID", records.from_user_id.to_string()),
+            ("TO_USER_ID", records.to_user_id.to_string()),
+            ("AMOUNT", options.format_amount(records.amount)),
+            ("TIMESTAMP", timestamp),
+            ("STATUS", records.status.to_string()),
+            ("DESCRIPTION", format!("\"{description}\"")),
+        ];
+
+        let body: String = fields
+            .iter()
+            .map(|(key, value)| format!("{key}: {value}\n"))
+            .collect();
+
+        format!("{}\n{}", Self::make_title(records), body)
     }
 
     /// Формирует заголовок блока записи.
@@ -200,10 +381,234 @@ impl YPBankTextFormat {
     }
 }
 
+/// Блоки записей `txt` из последовательности строк: строки блока вместе с их номерами
+/// (начиная с 1), первой идёт вид операции из заголовка блока (см.
+/// [`YPBankTextFormat::parse_block`]).
+///
+/// Пустые строки и комментарии пропускаются. Строка вне блока — ошибка.
+///
+/// Файлы, отредактированные вручную в Windows, могут смешивать окончания строк: одиночный
+/// `\r` внутри строки тоже разделяет строки, как в [`normalize_line_endings`].
+struct TextBlocks<I> {
+    lines: I,
+    /// Оставшиеся части последней прочитанной строки, разделённые одиночным `\r`.
+    pending: VecDeque<String>,
+    /// Номер последней прочитанной строки.
+    line_number: usize,
+    /// Заголовок следующего блока, прочитанный при завершении предыдущего.
+    next_title: Option<(usize, String)>,
+}
+
+impl<I: Iterator<Item = io::Result<String>>> TextBlocks<I> {
+    fn new(lines: I) -> Self {
+        Self {
+            lines,
+            pending: VecDeque::new(),
+            line_number: 0,
+            next_title: None,
+        }
+    }
+
+    /// Следующая строка данных. Окончание `\r\n` уже отброшено при разбиении на строки, поэтому
+    /// оставшиеся `\r` — одиночные и разделяют строку на несколько.
+    fn next_line(&mut self) -> Option<io::Result<String>> {
+        if let Some(line) = self.pending.pop_front() {
+            return Some(Ok(line));
+        }
+
+        let line = match self.lines.next()? {
+            Ok(line) => line,
+            Err(e) => return Some(Err(e)),
+        };
+        if !line.contains('\r') {
+            return Some(Ok(line));
+        }
+
+        let mut parts = line.split('\r').map(str::to_string);
+        let first = parts.next().unwrap_or_default();
+        self.pending.extend(parts);
+        Some(Ok(first))
+    }
+}
+
+impl<I: Iterator<Item = io::Result<String>>> Iterator for TextBlocks<I> {
+    type Item = Result<Vec<(usize, String)>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut block: Vec<(usize, String)> = self.next_title.take().into_iter().collect();
+        while let Some(line) = self.next_line() {
+            let line = match line {
+                Ok(line) => line,
+                Err(e) => return Some(Err(ParseError::io_error(e, "Ошибка парсинга данных"))),
+            };
+            self.line_number += 1;
+            let line_number = self.line_number;
+            let line = match line_number {
+                1 => line.strip_bom(),
+                _ => line.as_str(),
+            };
+            if line.is_empty_line() {
+                continue;
+            }
+
+            // Строка с `#`, не являющаяся заголовком записи, — комментарий.
+            let title = match line.is_hash_marker() {
+                true => match YPBankTextFormat::parse_title(line) {
+                    Some(title) => Some(title),
+                    None => continue,
+                },
+                false => None,
+            };
+
+            match (block.is_empty(), title) {
+                // Начало блока.
+                (true, Some(title)) => block.push((line_number, title)),
+                // Блок собран, заголовок относится к следующему.
+                (false, Some(title)) => {
+                    self.next_title = Some((line_number, title));
+                    return Some(Ok(block));
+                }
+                // Внутри блока.
+                (false, None) => block.push((line_number, line.to_string())),
+                (true, None) => {
+                    return Some(Err(ParseError::parse_err(
+                        format!("Некорректная строка: {line}"),
+                        line_number,
+                        0,
+                    )));
+                }
+            }
+        }
+
+        (!block.is_empty()).then_some(Ok(block))
+    }
+}
+
+/// Потоковый разбор записей `txt`, см. [`YPBankTextFormat::read_iter`].
+struct TextRecordIter<R> {
+    blocks: TextBlocks<Lines<BufReader<R>>>,
+    options: ReadOptions,
+    /// Итерация завершена: данные закончились или возникла ошибка.
+    finished: bool,
+}
+
+impl<R: Read> Iterator for TextRecordIter<R> {
+    type Item = Result<(usize, YPBankTextFormat), ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let item = self.blocks.next().map(|block| {
+            let block = block?;
+            let record = YPBankTextFormat::parse_block(
+                &block,
+                &self.options,
+                YPBankTextFormat::new_from_map,
+            )?;
+            Ok((block[0].0, record))
+        });
+        self.finished = !matches!(item, Some(Ok(_)));
+        item
+    }
+}
+
+impl<K: TxKind, S: TxKind> YPBankTextRecord<K, S> {
+    /// Чтение данных в формате `txt` с пользовательскими перечислениями типа и статуса.
+    ///
+    /// ## Пример
+    ///
+    /// ```no_run
+    /// use std::fs::File;
+    /// use parser::models::{TxStatus, YPBankTextRecord};
+    /// use parser::traits::TxDisplay;
+    ///
+    /// #[repr(u8)]
+    /// #[derive(Debug, TxDisplay, Clone, PartialEq)]
+    /// enum BankTxType {
+    ///     Deposit = 0,
+    ///     Fee = 10,
+    /// }
+    ///
+    /// let mut file = File::open("data.txt").unwrap();
+    /// let records = YPBankTextRecord::<BankTxType, TxStatus>::read_from(&mut file).unwrap();
+    /// ```
+    pub fn read_from<R: Read>(reader: &mut R) -> Result<Vec<Self>, ParseError> {
+        Self::read_from_with(reader, &ReadOptions::default())
+    }
+
+    /// Чтение как [`YPBankTextRecord::read_from`] с настройками [`ReadOptions`].
+    ///
+    /// Числовые коды ([`ReadOptions::numeric_enums`]) сопоставляются со встроенными
+    /// [`TxType`](crate::models::TxType) и [`TxStatus`](crate::models::TxStatus).
+    pub fn read_from_with<R: Read>(
+        reader: &mut R,
+        options: &ReadOptions,
+    ) -> Result<Vec<Self>, ParseError> {
+        let mut buffer = String::new();
+        BufReader::new(reader)
+            .read_to_string(&mut buffer)
+            .map_err(|e| ParseError::io_error(e, "Ошибка парсинга данных"))?;
+        validate_exceed_max_bytes(buffer.len(), MAX_SIZE_CSV_TXT_BYTES)?;
+
+        let records = YPBankTextFormat::parse_records(buffer, options, Self::new_from_map)?;
+        if records.is_empty() {
+            return Err(ParseError::empty_data());
+        }
+
+        Ok(records)
+    }
+}
+
+impl FromStr for YPBankTransaction {
+    type Err = ParseError;
+
+    /// Разбирает одну запись `txt` (заголовок `# Record N (TYPE)` и поля) в универсальную
+    /// структуру. Знак суммы определяется типом операции, как при чтении файла.
+    ///
+    /// Если в строке больше одной записи, возвращается ошибка с номером строки заголовка второй.
+    ///
+    /// ## Пример
+    ///
+    /// ```
+    /// use parser::models::YPBankTransaction;
+    ///
+    /// let block = "# Record 1 (WITHDRAWAL)\n\
+    ///              TX_ID: 1\nTX_TYPE: WITHDRAWAL\nFROM_USER_ID: 1001\nTO_USER_ID: 0\n\
+    ///              AMOUNT: 500\nTIMESTAMP: 1633046400\nSTATUS: SUCCESS\nDESCRIPTION: \"ATM\"\n";
+    ///
+    /// let tx: YPBankTransaction = block.parse().unwrap();
+    /// assert_eq!(tx.amount, -500);
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = normalize_line_endings(s.to_string());
+        let mut records = YPBankTextFormat::read_executor(s.clone())?;
+        if records.len() > 1 {
+            let second_title = s
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| YPBankTextFormat::parse_title(line).is_some())
+                .nth(1)
+                .map_or(0, |(i, _)| i + 1);
+            return Err(ParseError::parse_err(
+                format!("Ожидалась одна запись txt, найдено: {}", records.len()),
+                second_title,
+                0,
+            ));
+        }
+
+        let record = records.pop().ok_or_else(ParseError::empty_data)?;
+        YPBankTransaction::try_from(record)
+    }
+}
+
 #[cfg(test)]
 mod text_tests {
-    use crate::models::{TxStatus, TxType, YPBankTextFormat};
-    use crate::traits::YPBankIO;
+    use crate::errors::ParseError;
+    use crate::format::options::{DecimalAmount, ReadOptions, WriteOptions};
+    use crate::models::{TxStatus, TxType, YPBankTextFormat, YPBankTextRecord, YPBankTransaction};
+    use crate::traits::{TxDisplay, TxKind, YPBankIO};
 
     // ==================== Test Data Factories ====================
 
@@ -349,12 +754,12 @@ mod text_tests {
                 "# Record 999999999 (WITHDRAWAL)",
             ];
 
-            for (i, title) in valid_titles.iter().enumerate() {
+            for title in valid_titles {
                 // Act
-                let result = YPBankTextFormat::parse_title(title, i);
+                let result = YPBankTextFormat::parse_title(title);
 
                 // Assert
-                assert!(result.is_ok(), "Failed for: {}", title);
+                assert!(result.is_some(), "Failed for: {}", title);
                 let tx_type = result.unwrap();
                 assert!(!tx_type.is_empty());
             }
@@ -375,13 +780,13 @@ mod text_tests {
                 ("## Record 1 (DEPOSIT)", "Два ##"),
             ];
 
-            for (i, (title, description)) in test_cases.iter().enumerate() {
+            for (title, description) in test_cases {
                 // Act
-                let result = YPBankTextFormat::parse_title(title, i);
+                let result = YPBankTextFormat::parse_title(title);
 
                 // Assert
                 assert!(
-                    result.is_err(),
+                    result.is_none(),
                     "Should fail for: {} - {}",
                     title,
                     description
@@ -401,7 +806,7 @@ mod text_tests {
             let record = create_test_text_record();
 
             // Act
-            let formatted = YPBankTextFormat::makeup_records(&record);
+            let formatted = YPBankTextFormat::makeup_records(&record, &WriteOptions::default());
 
             // Assert
             let lines: Vec<&str> = formatted.trim().lines().collect();
@@ -421,7 +826,7 @@ mod text_tests {
             let record = create_deposit_text_record();
 
             // Act
-            let formatted = YPBankTextFormat::makeup_records(&record);
+            let formatted = YPBankTextFormat::makeup_records(&record, &WriteOptions::default());
 
             // Assert
             assert!(formatted.contains("DESCRIPTION: \"\""));
@@ -443,9 +848,14 @@ mod text_tests {
                 ),
                 (
                     "Test\nwith\nnewlines",
-                    "DESCRIPTION: \"Test\nwith\nnewlines\"",
+                    "DESCRIPTION: \"Test\\nwith\\nnewlines\"",
                     "переносы строк в описании",
                 ),
+                (
+                    "Tab\there, C:\\temp",
+                    "DESCRIPTION: \"Tab\\there, C:\\\\temp\"",
+                    "табуляция и обратная косая черта в описании",
+                ),
                 (
                     "Time: 12:00:00",
                     "DESCRIPTION: \"Time: 12:00:00\"",
@@ -459,7 +869,7 @@ mod text_tests {
                 record.description = description.to_string();
 
                 // Act
-                let formatted = YPBankTextFormat::makeup_records(&record);
+                let formatted = YPBankTextFormat::makeup_records(&record, &WriteOptions::default());
 
                 // Assert
                 assert!(
@@ -478,6 +888,70 @@ mod text_tests {
     mod reading_tests {
         use super::*;
 
+        #[test]
+        fn test_read_executor_comments_between_blocks() {
+            // Arrange
+            let input = format!(
+                "# Выгрузка за октябрь\n{}\n# Далее депозит\n\n{}# Конец файла\n",
+                sample_transfer_block(),
+                sample_deposit_block()
+            );
+
+            // Act
+            let result = YPBankTextFormat::read_executor(input).unwrap();
+
+            // Assert
+            assert_eq!(result.len(), 2);
+            assert_eq!(result[0].tx_type, TxType::Transfer);
+            assert_eq!(result[1].tx_type, TxType::Deposit);
+        }
+
+        #[test]
+        fn test_read_executor_comment_inside_block() {
+            // Arrange
+            let input = sample_transfer_block().replacen(
+                "TIMESTAMP:",
+                "# Сумма уточнена вручную\n#TODO: сверить\nTIMESTAMP:",
+                1,
+            );
+
+            // Act
+            let result = YPBankTextFormat::read_executor(input).unwrap();
+
+            // Assert
+            assert_eq!(result.len(), 1);
+            assert_eq!(result[0].description, "Test transaction");
+            assert!(
+                YPBankTextFormat::is_complete(
+                    &mut format!("{}# хвост\n", sample_transfer_block()).as_bytes()
+                )
+                .unwrap()
+            );
+        }
+
+        #[test]
+        fn test_read_truncated_quoted_description() {
+            // Arrange
+            let block = sample_transfer_block().replace("DESCRIPTION: \"Test transaction\"\n", "")
+                + "DESCRIPTION: \"Test transaction\"\n";
+            let cut = block.len() - "saction\"\n".len();
+            let truncated = [&block[..cut], &block[..block.len() - 2]];
+
+            for input in truncated {
+                // Act
+                let result = YPBankTextFormat::read_executor(input.to_string());
+                let complete = YPBankTextFormat::is_complete(&mut input.as_bytes()).unwrap();
+
+                // Assert
+                assert!(
+                    matches!(result, Err(ParseError::ParseError { line: 9, .. })),
+                    "{input}"
+                );
+                assert!(!complete, "{input}");
+            }
+            assert!(YPBankTextFormat::is_complete(&mut block.as_bytes()).unwrap());
+        }
+
         #[test]
         fn test_read_executor_single_record() {
             // Arrange
@@ -741,6 +1215,237 @@ mod text_tests {
                 );
             }
         }
+
+        #[test]
+        fn test_read_executor_windows_line_endings() {
+            // Arrange
+            let unix = format!("{}\n{}", sample_transfer_block(), sample_deposit_block());
+            let windows = unix.replace('\n', "\r\n");
+            let mixed = unix.replacen('\n', "\r", 3);
+
+            // Act
+            let expected = YPBankTextFormat::read_executor(unix).unwrap();
+            let from_windows = YPBankTextFormat::read_executor(windows).unwrap();
+            let from_mixed = YPBankTextFormat::read_executor(mixed).unwrap();
+
+            // Assert
+            assert_eq!(from_windows, expected);
+            assert_eq!(from_mixed, expected);
+            assert_record_matches(&from_windows[0], &create_test_text_record());
+            assert_record_matches(&from_windows[1], &create_deposit_text_record());
+        }
+
+        #[test]
+        fn test_read_executor_utf8_bom() {
+            // Arrange
+            let data = format!("\u{FEFF}{}", sample_transfer_block());
+
+            // Act
+            let result = YPBankTextFormat::read_from(&mut std::io::Cursor::new(data)).unwrap();
+
+            // Assert
+            assert_eq!(result.len(), 1);
+            assert_record_matches(&result[0], &create_test_text_record());
+        }
+
+        #[test]
+        fn test_read_executor_decimal_amount() {
+            // Arrange
+            let options = ReadOptions {
+                decimal_amount: Some(DecimalAmount { minor_digits: 2 }),
+                ..ReadOptions::default()
+            };
+            let input = sample_transfer_block().replace("AMOUNT: 50000", "AMOUNT: 123.45");
+            let invalid = sample_transfer_block().replace("AMOUNT: 50000", "AMOUNT: 123.456");
+
+            // Act
+            let result = YPBankTextFormat::read_executor_with(input, &options).unwrap();
+            let rejected = YPBankTextFormat::read_executor_with(invalid, &options);
+            let mut buffer = Vec::new();
+            let write_options = WriteOptions {
+                decimal_amount: options.decimal_amount,
+                ..WriteOptions::default()
+            };
+            YPBankTextFormat::write_to_with(&mut buffer, &result, &write_options).unwrap();
+
+            // Assert
+            assert_eq!(result[0].amount, 12345);
+            assert!(rejected.is_err());
+            assert!(
+                String::from_utf8(buffer)
+                    .unwrap()
+                    .contains("\nAMOUNT: 123.45\n")
+            );
+        }
+
+        #[test]
+        fn test_read_executor_iso_timestamp() {
+            // Arrange
+            let iso = sample_transfer_block()
+                .replace("TIMESTAMP: 1633046400", "TIMESTAMP: 2021-10-01T00:00:00Z");
+            let epoch = sample_transfer_block()
+                .replace("TIMESTAMP: 1633046400", "TIMESTAMP: 1970-01-01T00:00:00Z");
+            let invalid = sample_transfer_block()
+                .replace("TIMESTAMP: 1633046400", "TIMESTAMP: 2021-02-29T00:00:00Z");
+
+            // Act
+            let from_iso = YPBankTextFormat::read_executor(iso).unwrap();
+            let from_epoch = YPBankTextFormat::read_executor(epoch).unwrap();
+            let rejected = YPBankTextFormat::read_executor(invalid);
+
+            // Assert
+            assert_eq!(from_iso, vec![create_test_text_record()]);
+            assert_eq!(from_epoch[0].timestamp, 0);
+            assert!(matches!(rejected, Err(ParseError::IncorrectField { .. })));
+        }
+
+        #[test]
+        fn test_write_iso_timestamps_round_trip() {
+            // Arrange
+            let records = vec![
+                create_test_text_record(),
+                YPBankTextFormat {
+                    timestamp: 0,
+                    ..create_deposit_text_record()
+                },
+            ];
+            let options = WriteOptions {
+                iso_timestamps: true,
+                ..WriteOptions::default()
+            };
+            let mut buffer = Vec::new();
+
+            // Act
+            YPBankTextFormat::write_to_with(&mut buffer, &records, &options).unwrap();
+            let output = String::from_utf8(buffer).unwrap();
+            let result = YPBankTextFormat::read_executor(output.clone()).unwrap();
+
+            // Assert
+            assert!(output.contains("\nTIMESTAMP: 2021-10-01T00:00:00Z\n"));
+            assert!(output.contains("\nTIMESTAMP: 1970-01-01T00:00:00Z\n"));
+            assert_eq!(result, records);
+        }
+
+        #[test]
+        fn test_transaction_from_str_single_block() {
+            // Act
+            let result: YPBankTransaction = sample_transfer_block().parse().unwrap();
+
+            // Assert
+            assert_eq!(
+                result,
+                YPBankTransaction::try_from(create_test_text_record()).unwrap()
+            );
+            assert_eq!(result.amount, -50000);
+        }
+
+        #[test]
+        fn test_transaction_from_str_rejects_two_blocks() {
+            // Arrange
+            let input = format!("{}\n{}", sample_transfer_block(), sample_deposit_block());
+
+            // Act
+            let result = input.parse::<YPBankTransaction>();
+            let empty = "".parse::<YPBankTransaction>();
+
+            // Assert
+            assert!(matches!(
+                result,
+                Err(ParseError::ParseError { line: 11, .. })
+            ));
+            assert!(empty.is_err());
+        }
+
+        #[test]
+        fn test_read_executor_crlf_multiple_blocks() {
+            // Arrange
+            let unix = format!(
+                "# export\n{}\n# note\n{}\n{}",
+                sample_transfer_block(),
+                sample_deposit_block(),
+                sample_withdrawal_block()
+            );
+            let crlf = unix.replace('\n', "\r\n");
+
+            // Act
+            let expected = YPBankTextFormat::read_executor(unix).unwrap();
+            let result = YPBankTextFormat::read_executor(crlf.clone()).unwrap();
+            let from_str = crlf.replace('\n', "").parse::<YPBankTransaction>();
+
+            // Assert
+            assert_eq!(result.len(), 3);
+            assert_eq!(result, expected);
+            assert!(
+                result
+                    .iter()
+                    .all(|record| !record.description.contains('\r'))
+            );
+            assert!(matches!(
+                from_str,
+                Err(ParseError::ParseError { line: 13, .. })
+            ));
+        }
+
+        #[test]
+        fn test_read_iter_matches_read_executor() {
+            // Arrange
+            let unix = format!(
+                "\u{feff}# export\n{}\n# note\n{}",
+                sample_transfer_block(),
+                sample_deposit_block()
+            );
+            let crlf = unix.replace('\n', "\r\n");
+
+            // Act
+            let expected = YPBankTextFormat::read_executor(unix).unwrap();
+            let streamed: Vec<(usize, YPBankTextFormat)> =
+                YPBankTextFormat::read_iter_positioned(crlf.as_bytes(), ReadOptions::default())
+                    .collect::<Result<_, _>>()
+                    .unwrap();
+            let broken: Vec<_> = YPBankTextFormat::read_iter(
+                format!("{}\nstray", sample_transfer_block()).as_bytes(),
+            )
+            .collect();
+
+            // Assert
+            let lines: Vec<usize> = streamed.iter().map(|(line, _)| *line).collect();
+            let records: Vec<YPBankTextFormat> =
+                streamed.into_iter().map(|(_, record)| record).collect();
+            assert_eq!(records, expected);
+            assert_eq!(lines[0], 2);
+            assert_eq!(broken.len(), 1);
+            assert!(broken[0].is_err());
+        }
+
+        #[test]
+        fn test_read_iter_cr_only_line_endings() {
+            // Arrange
+            let unix = format!(
+                "# export\n{}\n# note\n{}",
+                sample_transfer_block(),
+                sample_deposit_block()
+            );
+            let cr_only = unix.replace('\n', "\r");
+
+            // Act
+            let expected: Vec<(usize, YPBankTextFormat)> =
+                YPBankTextFormat::read_iter_positioned(unix.as_bytes(), ReadOptions::default())
+                    .collect::<Result<_, _>>()
+                    .unwrap();
+            let streamed: Vec<(usize, YPBankTextFormat)> =
+                YPBankTextFormat::read_iter_positioned(cr_only.as_bytes(), ReadOptions::default())
+                    .collect::<Result<_, _>>()
+                    .unwrap();
+            let records: Vec<YPBankTextFormat> = YPBankTextFormat::read_iter(cr_only.as_bytes())
+                .collect::<Result<_, _>>()
+                .unwrap();
+            let buffered = YPBankTextFormat::read_executor(cr_only).unwrap();
+
+            // Assert
+            assert_eq!(streamed.len(), 2);
+            assert_eq!(streamed, expected);
+            assert_eq!(records, buffered);
+        }
     }
 
     // ==================== Error Handling Tests ====================
@@ -790,6 +1495,83 @@ mod text_tests {
             }
         }
 
+        #[test]
+        fn test_read_executor_reports_exact_line_across_blocks() {
+            // Arrange: блоки по 9 строк, разделённые пустой строкой, плюс комментарий
+            // в начале. Третий блок начинается со строки 22, его строка TO_USER_ID — 25-я.
+            let broken = sample_withdrawal_block().replace("TO_USER_ID: 0", "TO_USER_ID 0");
+            let input = format!(
+                "# Выгрузка\n{}\n{}\n{}",
+                sample_transfer_block(),
+                sample_deposit_block(),
+                broken
+            );
+            assert_eq!(input.lines().nth(24), Some("TO_USER_ID 0"));
+
+            // Act
+            let result = YPBankTextFormat::read_executor(input);
+
+            // Assert
+            assert!(matches!(
+                result,
+                Err(ParseError::ParseError { line: 25, .. })
+            ));
+        }
+
+        /// Блок записи без строки `AMOUNT` либо с заданным значением `AMOUNT`.
+        fn block_with_amount(amount: Option<&str>) -> String {
+            let mut block = "\n\n# Record 1 (DEPOSIT)\n\
+                TX_TYPE: DEPOSIT\n\
+                TO_USER_ID: 1\n\
+                FROM_USER_ID: 0\n\
+                TIMESTAMP: 1633036860000\n\
+                DESCRIPTION: \"Test\"\n\
+                TX_ID: 1234567890000000\n\
+                STATUS: SUCCESS\n"
+                .to_string();
+            if let Some(amount) = amount {
+                block.push_str(&format!("AMOUNT: {amount}\n"));
+            }
+            block
+        }
+
+        #[test]
+        fn test_read_executor_missing_amount() {
+            // Arrange
+            let input = block_with_amount(None);
+
+            // Act
+            let result = YPBankTextFormat::read_executor(input);
+
+            // Assert
+            let Err(ParseError::ParseError { message, line, .. }) = result else {
+                panic!("ожидалась ошибка разбора, получено: {result:?}");
+            };
+            assert_eq!(message, "В записи отсутствуют обязательные поля: AMOUNT");
+            assert_eq!(line, 3);
+        }
+
+        #[test]
+        fn test_read_executor_malformed_amount() {
+            // Arrange
+            let input = block_with_amount(Some("notanumber"));
+
+            // Act
+            let result = YPBankTextFormat::read_executor(input);
+
+            // Assert
+            assert_eq!(
+                result,
+                Err(ParseError::incorrect_field("AMOUNT").at_field(11, 0))
+            );
+            assert_ne!(
+                result.unwrap_err().to_string(),
+                YPBankTextFormat::read_executor(block_with_amount(None))
+                    .unwrap_err()
+                    .to_string()
+            );
+        }
+
         /// Проверка корректности срабатывания ограничения на большие входные
         /// данные.
         #[test]
@@ -1022,6 +1804,10 @@ mod text_tests {
                 "Test\nwith\nnewlines",
                 "Time: 12:00:00",
                 "Test \"quoted\", with comma\nand newline",
+                "line1\nline2",
+                "Tab\tand\r\ncarriage return",
+                "Backslash \\ and \\n literal",
+                "\\",
             ];
 
             for description in test_cases {
@@ -1039,16 +1825,80 @@ mod text_tests {
                 let result = YPBankTextFormat::read_executor(text_string);
 
                 // Assert
-                if description.contains('\n') {
-                    // Переносы строк могут вызывать проблемы при чтении
-                    assert!(result.is_err() || result.unwrap()[0].description == description);
-                } else {
-                    let read_records = result.unwrap();
-                    assert_eq!(read_records.len(), 1);
-                    assert_eq!(read_records[0].description, description);
-                }
+                let read_records = result.unwrap();
+                assert_eq!(read_records.len(), 1);
+                assert_eq!(read_records[0].description, description);
+            }
+        }
+
+        #[test]
+        fn test_write_read_round_trip_quote_boundaries() {
+            for description in ["\"", "\"\"", "\"hi\"", "\"\"\"", "\"start", "end\""] {
+                // Arrange
+                let mut record = create_test_text_record();
+                record.description = description.to_string();
+
+                // Act
+                let mut buffer = Vec::new();
+                YPBankTextFormat::write_to(&mut buffer, &[record]).unwrap();
+                let read_records =
+                    YPBankTextFormat::read_executor(String::from_utf8(buffer).unwrap()).unwrap();
+
+                // Assert
+                assert_eq!(read_records[0].description, description);
             }
         }
+
+        #[test]
+        fn test_read_unpaired_inner_quote_is_error() {
+            // Arrange
+            let input = "# Record 1 (TRANSFER)\n\
+                        TX_TYPE: TRANSFER\n\
+                        FROM_USER_ID: 1001\n\
+                        TO_USER_ID: 1002\n\
+                        TIMESTAMP: 1633046400\n\
+                        DESCRIPTION: \"Test \"quoted\" text\"\n\
+                        TX_ID: 1234567890000000\n\
+                        AMOUNT: 50000\n\
+                        STATUS: SUCCESS\n";
+
+            // Act
+            let result = YPBankTextFormat::read_executor(input.to_string());
+
+            // Assert
+            assert!(matches!(
+                result,
+                Err(ParseError::ParseError { line: 6, .. })
+            ));
+        }
+
+        #[test]
+        fn test_read_old_style_backslash_description() {
+            // Arrange
+            let input = |description: &str| {
+                format!(
+                    "# Record 1 (TRANSFER)\n\
+                    TX_TYPE: TRANSFER\n\
+                    FROM_USER_ID: 1001\n\
+                    TO_USER_ID: 1002\n\
+                    TIMESTAMP: 1633046400\n\
+                    DESCRIPTION: \"{description}\"\n\
+                    TX_ID: 1234567890000000\n\
+                    AMOUNT: 50000\n\
+                    STATUS: SUCCESS\n"
+                )
+            };
+
+            // Act
+            let path = YPBankTextFormat::read_executor(input("C:\\data\\files")).unwrap();
+            let trailing = YPBankTextFormat::read_executor(input("ends with \\")).unwrap();
+            let mixed = YPBankTextFormat::read_executor(input("C:\\path\\new"));
+
+            // Assert
+            assert_eq!(path[0].description, "C:\\data\\files");
+            assert_eq!(trailing[0].description, "ends with \\");
+            assert!(mixed.is_ok());
+        }
     }
 
     // ==================== Integration Tests ====================
@@ -1122,4 +1972,68 @@ mod text_tests {
             assert_eq!(normalized1, normalized2);
         }
     }
+
+    #[test]
+    fn test_read_custom_tx_kind() {
+        // Arrange
+        #[repr(u8)]
+        #[derive(Debug, TxDisplay, Clone, PartialEq)]
+        enum BankTxType {
+            Deposit = 0,
+            Transfer = 1,
+            Withdrawal = 2,
+            Fee = 10,
+        }
+
+        let data = "# Record 1 (FEE)\n\
+            TX_ID: 1000000000000001\n\
+            TX_TYPE: FEE\n\
+            FROM_USER_ID: 1001\n\
+            TO_USER_ID: 0\n\
+            AMOUNT: 150\n\
+            TIMESTAMP: 1633046400\n\
+            STATUS: SUCCESS\n\
+            DESCRIPTION: \"Monthly fee\"\n";
+
+        // Act
+        let records =
+            YPBankTextRecord::<BankTxType, TxStatus>::read_from(&mut data.as_bytes()).unwrap();
+        let standard = YPBankTextFormat::read_executor(data.to_string());
+
+        // Assert
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].tx_type, BankTxType::Fee);
+        assert_eq!(TxKind::as_u8(&records[0].tx_type), 10);
+        assert_eq!(records[0].status, TxStatus::Success);
+        assert_eq!(records[0].description, "Monthly fee");
+        assert!(matches!(standard, Err(ParseError::IncorrectField { .. })));
+    }
+
+    #[test]
+    fn test_read_from_limited_stops_at_first_extra_record() {
+        // Arrange
+        let records = vec![
+            create_test_text_record(),
+            create_deposit_text_record(),
+            create_withdrawal_text_record(),
+        ];
+        let mut buffer = Vec::new();
+        YPBankTextFormat::write_to(&mut buffer, &records).unwrap();
+        buffer.extend_from_slice("# Record 4 (DEPOSIT)\nNOT A FIELD\n".as_bytes());
+
+        // Act
+        let over_limit = YPBankTextFormat::read_from_limited(&mut buffer.as_slice(), Some(2));
+        let unlimited = YPBankTextFormat::read_from_limited(&mut buffer.as_slice(), None);
+
+        // Assert
+        assert!(matches!(
+            over_limit,
+            Err(ParseError::RecordLimitExceeded { limit: 2 })
+        ));
+        assert!(unlimited.is_err());
+        assert!(!matches!(
+            unlimited,
+            Err(ParseError::RecordLimitExceeded { .. })
+        ));
+    }
 }
@@ -20,9 +20,12 @@ pub trait LineUtils {
     fn is_hash_marker(&self) -> bool;
     fn split_into_key_value(&self) -> Option<(String, String)>;
     fn is_eq(&self, other: &str) -> bool;
-    fn split_csv_line(&self) -> Option<Vec<String>>;
-    fn clean_quote(&self) -> String;
+    fn split_csv_line(&self, delimiter: char) -> Result<Vec<String>, ParseError>;
+    fn clean_quote(&self) -> Option<String>;
     fn escaped_quote(&self) -> String;
+    fn escaped_control(&self) -> String;
+    fn clean_control(&self) -> String;
+    fn strip_bom(&self) -> &str;
 }
 
 impl<T: AsRef<str>> LineUtils for T {
@@ -38,66 +41,130 @@ impl<T: AsRef<str>> LineUtils for T {
     /// Возвращает два значения `ключ` и `значение` для строки вида
     /// `key:parameter`.
     ///
-    /// `Key` будет преобразован в `uppercase`.
+    /// `Key` будет преобразован в `uppercase`. Со значения снимаются обрамляющие кавычки
+    /// ([`LineUtils::clean_quote`]) и экранирование управляющих символов
+    /// ([`LineUtils::clean_control`]). Завершающие `\r` (окончания строк Windows) отбрасываются
+    /// до разбора, даже если строка не прошла нормализацию.
     fn split_into_key_value(&self) -> Option<(String, String)> {
-        let (k, v) = self.as_ref().split_once(':')?;
+        let line = self.as_ref().trim_end_matches('\r');
+        let (k, v) = line.split_once(':')?;
         let key = k.trim().to_uppercase();
         let value = v.trim();
         if key.is_empty() || value.is_empty() {
             return None;
         }
 
-        let val_clean = value.clean_quote();
+        let val_clean = value.clean_quote()?.clean_control();
 
         Some((key, val_clean))
     }
 
+    /// Отбрасывает метку порядка байтов UTF-8 (`U+FEFF`) в начале строки, если она есть.
+    ///
+    /// Метку добавляют, например, при экспорте из Excel; частью данных она не является.
+    fn strip_bom(&self) -> &str {
+        let line = self.as_ref();
+        line.strip_prefix('\u{FEFF}').unwrap_or(line)
+    }
+
     /// Проверить соответствие строк, исключая пробелы и другие избыточные символы.
     fn is_eq(&self, other: &str) -> bool {
         self.as_ref().trim().eq(other.trim())
     }
 
-    /// Парсер строк csv-записей. Разбирает строку на блоки, разделённые запятыми. Особое внимание
-    /// к последнему блоку, который должен быть в кавычках, а внутри также может содержать запятые,
-    /// лишние кавычки.
+    /// Парсер строк csv-записей. Разбирает строку на блоки, разделённые `delimiter` (обычно
+    /// запятой). Поле в кавычках может стоять в любой позиции и содержать разделитель и
+    /// экранированные (удвоенные) кавычки. После закрывающей кавычки допускаются только пробелы
+    /// и разделитель перед следующим полем.
+    ///
+    /// Поле в кавычках может содержать переводы строк (запись `csv` из нескольких строк).
+    ///
+    /// Полей всегда на одно больше, чем разделителей вне кавычек: пустая строка — одно пустое
+    /// поле, строка `,,` — три пустых поля. Количество полей с заголовком не сверяется.
     ///
-    /// Корректность (длина, наличие всех блоков) собранной строки не проверяет.
-    fn split_csv_line(&self) -> Option<Vec<String>> {
+    /// Кавычки обязательны, только если значение содержит разделитель, кавычку или перевод
+    /// строки; в остальном поля с кавычками и без читаются одинаково, в любой позиции. Значение
+    /// без кавычек обрезается по краям (пробелы вокруг разделителя незначимы), значение
+    /// в кавычках сохраняется как есть, вместе с пробелами по краям.
+    ///
+    /// При нарушении структуры возвращается [`ParseError::ParseError`], где `line` — номер
+    /// строки внутри записи (с нуля), а `column` — позиция символа в этой строке (с 1):
+    /// незакрытая кавычка, кавычка внутри поля без кавычек или посторонний символ после
+    /// закрывающей кавычки.
+    fn split_csv_line(&self, delimiter: char) -> Result<Vec<String>, ParseError> {
         let mut fields = Vec::new();
         let mut buffer = String::new();
         let mut chars = self.as_ref().chars().peekable();
+        let mut after_quote = false;
+        // Позиция последнего прочитанного символа: строка внутри записи и номер в строке.
+        let mut pos = (0, 0);
+        let step = |pos: &mut (usize, usize), ch: char| match ch {
+            '\n' => *pos = (pos.0 + 1, 0),
+            _ => pos.1 += 1,
+        };
 
         while let Some(ch) = chars.next() {
+            step(&mut pos, ch);
+            if after_quote {
+                // Поле в кавычках уже сохранено: ждём разделитель следующего поля.
+                match ch {
+                    c if c == delimiter => after_quote = false,
+                    c if c.is_whitespace() => {}
+                    _ => {
+                        return Err(ParseError::parse_err(
+                            format!("Посторонний символ после закрывающей кавычки: {ch}"),
+                            pos.0,
+                            pos.1,
+                        ));
+                    }
+                }
+                continue;
+            }
+
             match ch {
                 '"' => {
-                    // Начало поля с кавычками — предполагаем, что description
                     if !buffer.trim().is_empty() {
-                        // Так не может или не должно быть: буфер очищается при запятой, а мы
-                        // обнаружили его на кавычке: значит строка уже неточная.
-                        return None;
+                        return Err(ParseError::parse_err(
+                            "Кавычка внутри поля без кавычек",
+                            pos.0,
+                            pos.1,
+                        ));
                     }
 
+                    // Пробелы перед открывающей кавычкой в значение не входят.
+                    buffer.clear();
+                    let opening = pos;
+                    let mut closed = false;
                     while let Some(c) = chars.next() {
+                        step(&mut pos, c);
                         match c {
                             '"' => {
                                 if let Some('"') = chars.peek() {
+                                    step(&mut pos, '"');
                                     chars.next();
                                     buffer.push('"');
                                 } else {
+                                    closed = true;
                                     break;
                                 }
                             }
-                            '\t' | '\n' => continue,
                             _ => buffer.push(c),
                         }
                     }
 
-                    fields.push(buffer.trim().to_string());
-                    // После description больше ничего не ожидается.
-                    return Some(fields);
+                    if !closed {
+                        return Err(ParseError::parse_err(
+                            "Поле в кавычках не закрыто до конца данных",
+                            opening.0,
+                            opening.1,
+                        ));
+                    }
+
+                    fields.push(std::mem::take(&mut buffer));
+                    after_quote = true;
                 }
 
-                ',' => {
+                c if c == delimiter => {
                     fields.push(buffer.trim().to_string());
                     buffer.clear();
                 }
@@ -106,23 +173,39 @@ impl<T: AsRef<str>> LineUtils for T {
             }
         }
 
-        if !buffer.trim().is_empty() {
+        if !after_quote {
             fields.push(buffer.trim().to_string());
         }
 
-        if fields.len() < 2 { None } else { Some(fields) }
+        Ok(fields)
     }
 
-    /// Очищает строковые данные от кавычек, если есть. Возвращает без них, если найдены, или
-    /// оригинальную строку, если кавычек не было.
-    fn clean_quote(&self) -> String {
-        let mut line = self.as_ref();
+    /// Снимает экранирование, выполненное [`LineUtils::escaped_quote`] и обрамляющими кавычками.
+    ///
+    /// Если строка заключена в кавычки, они удаляются, а каждая пара внутренних кавычек `""`
+    /// заменяется одной. Одиночная внутренняя кавычка означает нарушенное экранирование,
+    /// тогда возвращается `None`. Строка, которая начинается с кавычки, но не закрыта ею
+    /// (например, оборванная запись), — тоже `None`. Строка без открывающей кавычки
+    /// возвращается без изменений.
+    fn clean_quote(&self) -> Option<String> {
+        let line = self.as_ref();
+        if !line.starts_with('"') {
+            return Some(line.to_string());
+        }
+        if line.len() < 2 || !line.ends_with('"') {
+            return None;
+        }
 
-        if line.starts_with('"') && line.ends_with('"') && line.len() >= 2 {
-            line = &line[1..line.len() - 1];
+        let mut cleaned = String::with_capacity(line.len() - 2);
+        let mut chars = line[1..line.len() - 1].chars();
+        while let Some(c) = chars.next() {
+            if c == '"' && chars.next() != Some('"') {
+                return None;
+            }
+            cleaned.push(c);
         }
 
-        line.replace("\"\"", "\"")
+        Some(cleaned)
     }
 
     /// Экранирование кавычек в переданной строке.
@@ -130,6 +213,58 @@ impl<T: AsRef<str>> LineUtils for T {
         let line = self.as_ref();
         line.replace('"', "\"\"")
     }
+
+    /// Экранирование обратной косой черты и управляющих символов, разрывающих строку:
+    /// `\` → `\\`, перевод строки → `\n`, возврат каретки → `\r`, табуляция → `\t`.
+    fn escaped_control(&self) -> String {
+        let line = self.as_ref();
+        let mut escaped = String::with_capacity(line.len());
+        for c in line.chars() {
+            match c {
+                '\\' => escaped.push_str("\\\\"),
+                '\n' => escaped.push_str("\\n"),
+                '\r' => escaped.push_str("\\r"),
+                '\t' => escaped.push_str("\\t"),
+                c => escaped.push(c),
+            }
+        }
+
+        escaped
+    }
+
+    /// Снимает экранирование, выполненное [`LineUtils::escaped_control`].
+    ///
+    /// Неизвестная последовательность и одиночная `\` в конце строки сохраняются как есть:
+    /// так читаются файлы, записанные до появления экранирования (например, путь
+    /// `C:\data\files`).
+    fn clean_control(&self) -> String {
+        let line = self.as_ref();
+        if !line.contains('\\') {
+            return line.to_string();
+        }
+
+        let mut cleaned = String::with_capacity(line.len());
+        let mut chars = line.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                cleaned.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('\\') => cleaned.push('\\'),
+                Some('n') => cleaned.push('\n'),
+                Some('r') => cleaned.push('\r'),
+                Some('t') => cleaned.push('\t'),
+                Some(other) => {
+                    cleaned.push('\\');
+                    cleaned.push(other);
+                }
+                None => cleaned.push('\\'),
+            }
+        }
+
+        cleaned
+    }
 }
 
 /// Проверяет, что `bytes` в пределах `max_bytes`. При превышении возвращает
@@ -140,3 +275,33 @@ pub fn validate_exceed_max_bytes(bytes: usize, max_bytes: usize) -> Result<(), P
         false => Ok(()),
     }
 }
+
+/// Собирает записи из `records`, но не более `max_records`: как только получена запись сверх
+/// ограничения, чтение прекращается с ошибкой [`ParseError::RecordLimitExceeded`]. Первая
+/// ошибка разбора прерывает сбор. `None` снимает ограничение.
+pub(crate) fn collect_records<T>(
+    records: impl Iterator<Item = Result<T, ParseError>>,
+    max_records: Option<usize>,
+) -> Result<Vec<T>, ParseError> {
+    let mut collected = Vec::new();
+    for record in records {
+        let record = record?;
+        if let Some(limit) = max_records.filter(|&limit| collected.len() == limit) {
+            return Err(ParseError::record_limit(limit));
+        }
+        collected.push(record);
+    }
+
+    Ok(collected)
+}
+
+/// Приводит окончания строк `\r\n` (Windows) и одиночные `\r` к `\n`.
+///
+/// Если символов `\r` в тексте нет, строка возвращается без копирования.
+pub fn normalize_line_endings(text: String) -> String {
+    if !text.contains('\r') {
+        return text;
+    }
+
+    text.replace("\r\n", "\n").replace('\r', "\n")
+}
@@ -0,0 +1,223 @@
+//! Чтение и запись файлов, сжатых gzip.
+//!
+//! Доступно с функцией (feature) `gzip`. Функции оборачивают источник в [`GzDecoder`], а приёмник
+//! в [`GzEncoder`] и передают их обычным функциям чтения и записи ([`read_csv`], [`write_bin`]
+//! и т.д.). Ограничения на объём данных `csv` и `txt` действуют для распакованных данных.
+//!
+//! [`read_sniffed`] определяет формат по содержимому и распаковывает данные, только если
+//! они начинаются с сигнатуры gzip.
+
+use crate::errors::ParseError;
+use crate::models::{YPBankBinFormat, YPBankCsvFormat, YPBankTextFormat, YPBankTransaction};
+use crate::{
+    SNIFF_SIZE, read_bin, read_csv, read_text, sniff_prefix, write_bin, write_csv, write_text,
+};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use std::io::{Read, Write};
+
+/// Считывает сжатые gzip данные в формате `csv`, см. [`read_csv`].
+///
+/// ## Пример
+///
+/// ```no_run
+/// use std::fs::File;
+/// use parser::read_csv_gz;
+///
+/// let mut file = File::open("data.csv.gz").unwrap();
+/// let data = read_csv_gz(&mut file);
+/// ```
+pub fn read_csv_gz<R: Read>(readers: &mut R) -> Result<Vec<YPBankCsvFormat>, ParseError> {
+    read_csv(&mut GzDecoder::new(readers))
+}
+
+/// Записывает данные в формате `csv` со сжатием gzip, см. [`write_csv`].
+pub fn write_csv_gz<W: Write>(
+    writer: &mut W,
+    records: &[YPBankCsvFormat],
+) -> Result<(), ParseError> {
+    write_compressed(writer, |encoder| write_csv(encoder, records))
+}
+
+/// Считывает сжатые gzip данные в бинарном формате (`bin`), см. [`read_bin`].
+pub fn read_bin_gz<R: Read>(readers: &mut R) -> Result<Vec<YPBankBinFormat>, ParseError> {
+    read_bin(&mut GzDecoder::new(readers))
+}
+
+/// Записывает данные в бинарном формате (`bin`) со сжатием gzip, см. [`write_bin`].
+pub fn write_bin_gz<W: Write>(
+    writer: &mut W,
+    records: &[YPBankBinFormat],
+) -> Result<(), ParseError> {
+    write_compressed(writer, |encoder| write_bin(encoder, records))
+}
+
+/// Считывает сжатые gzip данные в `txt`-формате, см. [`read_text`].
+pub fn read_text_gz<R: Read>(readers: &mut R) -> Result<Vec<YPBankTextFormat>, ParseError> {
+    read_text(&mut GzDecoder::new(readers))
+}
+
+/// Записывает данные в текстовом формате (`txt`) со сжатием gzip, см. [`write_text`].
+pub fn write_text_gz<W: Write>(
+    writer: &mut W,
+    records: &[YPBankTextFormat],
+) -> Result<(), ParseError> {
+    write_compressed(writer, |encoder| write_text(encoder, records))
+}
+
+/// Сигнатура потока gzip.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Считывает транзакции, определяя формат данных по содержимому (см. [`sniff_format`]).
+///
+/// Если данные начинаются с сигнатуры gzip, они распаковываются, и формат определяется уже
+/// по распакованным данным. Источник не обязан поддерживать `Seek`.
+///
+/// [`sniff_format`]: crate::sniff_format
+///
+/// ## Пример
+///
+/// ```no_run
+/// use std::fs::File;
+/// use parser::read_sniffed;
+///
+/// let mut file = File::open("data.bin.gz").unwrap();
+/// let transactions = read_sniffed(&mut file);
+/// ```
+pub fn read_sniffed<R: Read>(readers: &mut R) -> Result<Vec<YPBankTransaction>, ParseError> {
+    let mut magic = Vec::with_capacity(GZIP_MAGIC.len());
+    readers
+        .take(GZIP_MAGIC.len() as u64)
+        .read_to_end(&mut magic)
+        .map_err(|e| ParseError::io_error(e, "Ошибка чтения начала данных"))?;
+    let mut source = magic.as_slice().chain(readers);
+
+    if magic == GZIP_MAGIC {
+        read_sniffed_plain(&mut GzDecoder::new(source))
+    } else {
+        read_sniffed_plain(&mut source)
+    }
+}
+
+/// Определяет формат несжатых данных по их началу и разбирает их.
+fn read_sniffed_plain<R: Read>(readers: &mut R) -> Result<Vec<YPBankTransaction>, ParseError> {
+    let mut prefix = Vec::with_capacity(SNIFF_SIZE);
+    readers
+        .take(SNIFF_SIZE as u64)
+        .read_to_end(&mut prefix)
+        .map_err(|e| ParseError::io_error(e, "Ошибка чтения начала данных"))?;
+
+    let format = sniff_prefix(&prefix)?;
+    format.to_transaction(&mut prefix.as_slice().chain(readers))
+}
+
+/// Передаёт `write` приёмник со сжатием и дописывает завершение потока gzip.
+fn write_compressed<W: Write>(
+    writer: &mut W,
+    write: impl FnOnce(&mut GzEncoder<&mut W>) -> Result<(), ParseError>,
+) -> Result<(), ParseError> {
+    let mut encoder = GzEncoder::new(writer, Compression::default());
+    write(&mut encoder)?;
+    encoder
+        .finish()
+        .map_err(|e| ParseError::io_error(e, "Ошибка записи данных"))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{TxStatus, TxType, YPBankTransaction};
+    use std::io::Cursor;
+
+    fn create_csv_records() -> Vec<YPBankCsvFormat> {
+        (1..=3)
+            .map(|tx_id| YPBankCsvFormat {
+                tx_id,
+                tx_type: TxType::Deposit,
+                from_user_id: 0,
+                to_user_id: 1001,
+                amount: 500 * tx_id,
+                timestamp: 1633046400 + tx_id,
+                status: TxStatus::Success,
+                description: format!("Record number {tx_id}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_csv_gz_round_trip() {
+        // Arrange
+        let records = create_csv_records();
+        let mut buffer = Vec::new();
+
+        // Act
+        write_csv_gz(&mut buffer, &records).unwrap();
+        let result = read_csv_gz(&mut Cursor::new(&buffer)).unwrap();
+
+        // Assert
+        assert_eq!(&buffer[..2], [0x1f, 0x8b]);
+        assert_eq!(result, records);
+    }
+
+    #[test]
+    fn test_bin_gz_round_trip() {
+        // Arrange
+        let records: Vec<YPBankBinFormat> = create_csv_records()
+            .into_iter()
+            .map(|record| {
+                YPBankTransaction::try_from(record)
+                    .and_then(YPBankBinFormat::try_from)
+                    .unwrap()
+            })
+            .collect();
+        let mut buffer = Vec::new();
+
+        // Act
+        write_bin_gz(&mut buffer, &records).unwrap();
+        let result = read_bin_gz(&mut Cursor::new(&buffer)).unwrap();
+
+        // Assert
+        assert_eq!(result, records);
+    }
+
+    #[test]
+    fn test_read_sniffed_gzipped_bin() {
+        // Arrange
+        let records: Vec<YPBankBinFormat> = create_csv_records()
+            .into_iter()
+            .map(|record| {
+                YPBankTransaction::try_from(record)
+                    .and_then(YPBankBinFormat::try_from)
+                    .unwrap()
+            })
+            .collect();
+        let mut plain = Vec::new();
+        write_bin(&mut plain, &records).unwrap();
+        let mut compressed = Vec::new();
+        write_bin_gz(&mut compressed, &records).unwrap();
+
+        // Act
+        let result = read_sniffed(&mut Cursor::new(&compressed)).unwrap();
+
+        // Assert
+        let expected = read_sniffed(&mut Cursor::new(&plain)).unwrap();
+        assert_eq!(expected.len(), records.len());
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_read_gz_rejects_plain_data() {
+        // Arrange
+        let mut plain = Vec::new();
+        write_csv(&mut plain, &create_csv_records()).unwrap();
+
+        // Act
+        let result = read_csv_gz(&mut Cursor::new(plain));
+
+        // Assert
+        assert!(matches!(result, Err(ParseError::IOError { .. })));
+    }
+}
@@ -0,0 +1,225 @@
+//! Индекс для быстрого поиска по набору транзакций.
+//!
+//! [`TransactionIndex`] строится один раз по прочитанному набору и позволяет многократно искать
+//! транзакции по идентификатору, пользователю и статусу без повторного перебора всех записей.
+//!
+//! Если нужен только поиск по `TX_ID`, достаточно словаря [`index_by_id`] (или
+//! [`load_index`](crate::load_index) для чтения сразу из источника).
+
+use crate::errors::ParseError;
+use crate::models::{TxStatus, YPBankTransaction};
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+
+/// Поведение [`index_by_id`] при повторяющемся `TX_ID`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuplicateIdPolicy {
+    /// Повтор идентификатора — ошибка.
+    #[default]
+    Error,
+    /// Сохраняется последняя запись с этим идентификатором.
+    KeepLast,
+}
+
+/// Строит словарь транзакций по `TX_ID`.
+///
+/// ## Returns
+///
+/// Словарь либо, при [`DuplicateIdPolicy::Error`], [`ParseError::ParseError`] с номером
+/// повторной записи (начиная с 1).
+pub fn index_by_id(
+    transactions: Vec<YPBankTransaction>,
+    policy: DuplicateIdPolicy,
+) -> Result<HashMap<u64, YPBankTransaction>, ParseError> {
+    let mut ids = HashMap::with_capacity(transactions.len());
+    for (position, tx) in transactions.into_iter().enumerate() {
+        match ids.entry(tx.tx_id) {
+            Entry::Vacant(entry) => {
+                entry.insert(tx);
+            }
+            Entry::Occupied(mut entry) => match policy {
+                DuplicateIdPolicy::KeepLast => {
+                    entry.insert(tx);
+                }
+                DuplicateIdPolicy::Error => {
+                    return Err(ParseError::parse_err(
+                        format!("Дублирование TX_ID: {}", tx.tx_id),
+                        position + 1,
+                        0,
+                    ));
+                }
+            },
+        }
+    }
+
+    Ok(ids)
+}
+
+/// Индекс по набору транзакций.
+///
+/// Поиск по пользователю и статусу возвращает позиции записей в исходном наборе (в порядке
+/// следования), сами записи доступны через [`TransactionIndex::transactions`].
+///
+/// ## Пример
+///
+/// ```
+/// use parser::index::TransactionIndex;
+/// use parser::models::{TxStatus, TxType, YPBankTransaction};
+///
+/// let index = TransactionIndex::new(vec![YPBankTransaction {
+///     tx_id: 1,
+///     tx_type: TxType::Deposit,
+///     from_user_id: 0,
+///     to_user_id: 1001,
+///     amount: 500,
+///     timestamp: 1633046400,
+///     status: TxStatus::Success,
+///     description: None,
+/// }]);
+///
+/// assert_eq!(index.by_id(1).unwrap().amount, 500);
+/// assert_eq!(index.by_user(1001), &[0]);
+/// assert!(index.by_status(TxStatus::Failure).is_empty());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TransactionIndex {
+    transactions: Vec<YPBankTransaction>,
+    ids: HashMap<u64, usize>,
+    users: HashMap<u64, Vec<usize>>,
+    statuses: HashMap<TxStatus, Vec<usize>>,
+}
+
+impl TransactionIndex {
+    /// Строит индекс по набору транзакций.
+    ///
+    /// При повторяющихся `TX_ID` поиск по идентификатору возвращает первую запись. Нулевой
+    /// идентификатор пользователя (отсутствующая сторона пополнения или списания) не индексируется.
+    pub fn new(transactions: Vec<YPBankTransaction>) -> Self {
+        let mut ids = HashMap::with_capacity(transactions.len());
+        let mut users: HashMap<u64, Vec<usize>> = HashMap::new();
+        let mut statuses: HashMap<TxStatus, Vec<usize>> = HashMap::new();
+
+        for (position, tx) in transactions.iter().enumerate() {
+            ids.entry(tx.tx_id).or_insert(position);
+
+            if tx.from_user_id != 0 {
+                users.entry(tx.from_user_id).or_default().push(position);
+            }
+            if tx.to_user_id != 0 && tx.to_user_id != tx.from_user_id {
+                users.entry(tx.to_user_id).or_default().push(position);
+            }
+
+            statuses
+                .entry(tx.status.clone())
+                .or_default()
+                .push(position);
+        }
+
+        Self {
+            transactions,
+            ids,
+            users,
+            statuses,
+        }
+    }
+
+    /// Возвращает транзакцию по её `TX_ID`.
+    pub fn by_id(&self, tx_id: u64) -> Option<&YPBankTransaction> {
+        self.ids.get(&tx_id).map(|&pos| &self.transactions[pos])
+    }
+
+    /// Возвращает позиции транзакций, в которых пользователь участвует как отправитель или
+    /// получатель.
+    pub fn by_user(&self, user_id: u64) -> &[usize] {
+        self.users.get(&user_id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Возвращает позиции транзакций с заданным статусом.
+    pub fn by_status(&self, status: TxStatus) -> &[usize] {
+        self.statuses.get(&status).map_or(&[], Vec::as_slice)
+    }
+
+    /// Исходный набор транзакций.
+    pub fn transactions(&self) -> &[YPBankTransaction] {
+        &self.transactions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TxType;
+    use crate::testing::transaction;
+
+    fn create_index() -> TransactionIndex {
+        TransactionIndex::new(vec![
+            transaction(10, TxType::Deposit, 100),
+            YPBankTransaction {
+                status: TxStatus::Pending,
+                ..transaction(11, TxType::Transfer, -100)
+            },
+            transaction(12, TxType::Withdrawal, -100),
+        ])
+    }
+
+    #[test]
+    fn test_index_by_id() {
+        // Arrange
+        let index = create_index();
+
+        // Act
+        let found = index.by_id(11);
+        let missing = index.by_id(99);
+
+        // Assert
+        assert_eq!(found.unwrap().tx_type, TxType::Transfer);
+        assert!(missing.is_none());
+    }
+
+    #[test]
+    fn test_index_by_user() {
+        // Arrange
+        let index = create_index();
+
+        // Act & Assert
+        assert_eq!(index.by_user(1001), &[1, 2]);
+        assert_eq!(index.by_user(1002), &[0, 1]);
+        assert!(index.by_user(0).is_empty());
+    }
+
+    #[test]
+    fn test_index_by_status() {
+        // Arrange
+        let index = create_index();
+
+        // Act & Assert
+        assert_eq!(index.by_status(TxStatus::Success), &[0, 2]);
+        assert_eq!(index.by_status(TxStatus::Pending), &[1]);
+        assert!(index.by_status(TxStatus::Failure).is_empty());
+    }
+
+    #[test]
+    fn test_index_by_id_duplicate_policy() {
+        // Arrange
+        let transactions = vec![
+            transaction(10, TxType::Deposit, 100),
+            transaction(11, TxType::Transfer, -100),
+            YPBankTransaction {
+                status: TxStatus::Failure,
+                ..transaction(10, TxType::Deposit, 100)
+            },
+        ];
+
+        // Act
+        let strict = index_by_id(transactions.clone(), DuplicateIdPolicy::Error);
+        let last = index_by_id(transactions, DuplicateIdPolicy::KeepLast).unwrap();
+
+        // Assert
+        assert!(matches!(
+            strict,
+            Err(ParseError::ParseError { line: 3, .. })
+        ));
+        assert_eq!(last.len(), 2);
+        assert_eq!(last[&10].status, TxStatus::Failure);
+    }
+}
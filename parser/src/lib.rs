@@ -33,18 +33,42 @@
 //! ```
 #![warn(missing_docs)]
 
+// Позволяет derive-макросам ссылаться на `::parser` и внутри самой библиотеки.
+extern crate self as parser;
+
 #[macro_use]
 pub mod errors;
+pub mod analytics;
+#[cfg(feature = "arrow")]
+pub mod columnar;
+pub mod diff;
 pub mod format;
+#[cfg(feature = "gzip")]
+pub mod gzip;
+pub mod index;
+pub mod lint;
 pub mod models;
+pub mod table;
+pub mod testing;
 pub mod traits;
 pub mod utils;
 
-use crate::models::{YPBankBinFormat, YPBankCsvFormat, YPBankTextFormat, YPBankTransaction};
+#[cfg(feature = "arrow")]
+pub use crate::columnar::{from_arrow, to_arrow};
+use crate::format::tools::LineUtils;
+#[cfg(feature = "gzip")]
+pub use crate::gzip::{
+    read_bin_gz, read_csv_gz, read_sniffed, read_text_gz, write_bin_gz, write_csv_gz, write_text_gz,
+};
+use crate::index::{DuplicateIdPolicy, index_by_id};
+use crate::models::{
+    YPBankBinFormat, YPBankCsvFormat, YPBankJsonFormat, YPBankTextFormat, YPBankTransaction,
+};
 use crate::traits::YPBankIO;
 use errors::ParseError;
+use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
-use std::io::{Read, Write};
+use std::io::{Read, Seek, SeekFrom, Write};
 
 /// Значение MiB.
 const MI_B: usize = 1_048_576;
@@ -53,6 +77,145 @@ pub const MAX_SIZE_BIN_BYTES: usize = 8 * MI_B;
 /// Максимальный размер входящего потока для CSV и TXT.
 pub const MAX_SIZE_CSV_TXT_BYTES: usize = 4 * MI_B;
 
+/// Проверяет, что данные из `readers` совпадают с ожидаемым набором транзакций.
+///
+/// Предназначена для интеграционных тестов кода, формирующего файлы: данные разбираются,
+/// приводятся к каноническому виду и сравниваются позиционно с `expected`.
+///
+/// ## Пример
+///
+/// ```
+/// use parser::models::{TxStatus, TxType, YPBankTransaction};
+/// use parser::{YPFormatSupported, assert_file_matches};
+///
+/// let expected = vec![YPBankTransaction {
+///     tx_id: 1,
+///     tx_type: TxType::Deposit,
+///     from_user_id: 0,
+///     to_user_id: 1001,
+///     amount: 500,
+///     timestamp: 1633046400,
+///     status: TxStatus::Success,
+///     description: None,
+/// }];
+///
+/// let mut file = Vec::new();
+/// YPFormatSupported::Csv.convert_transactions(&mut file, &expected).unwrap();
+///
+/// assert_file_matches(&mut file.as_slice(), YPFormatSupported::Csv, &expected).unwrap();
+/// ```
+///
+/// ## Panics
+///
+/// Паникует, если данные не удалось разобрать: для проверки это уже провал.
+///
+/// ## Returns
+///
+/// Пустой `Result` при совпадении, либо список расхождений [`diff::RecordDiff`].
+pub fn assert_file_matches<R: Read>(
+    readers: &mut R,
+    format: YPFormatSupported,
+    expected: &[YPBankTransaction],
+) -> Result<(), Vec<diff::RecordDiff>> {
+    let actual = format
+        .to_transaction(readers)
+        .unwrap_or_else(|err| panic!("Не удалось разобрать данные ({format}): {err}"));
+
+    let diffs = diff::diff_records(expected, &actual);
+    if diffs.is_empty() { Ok(()) } else { Err(diffs) }
+}
+
+/// Оценивает размер данных в байтах, которые будут записаны при конвертации `records` в формат
+/// `format`.
+///
+/// Оценка вычисляется без кодирования записей и подходит для предварительного выделения буферов
+/// или проверки свободного места. Для форматов по умолчанию она совпадает с фактическим
+/// размером.
+///
+/// ## Пример
+///
+/// ```
+/// use parser::models::{TxStatus, TxType, YPBankTransaction};
+/// use parser::{YPFormatSupported, estimate_output_size};
+///
+/// let records = vec![YPBankTransaction {
+///     tx_id: 1,
+///     tx_type: TxType::Deposit,
+///     from_user_id: 0,
+///     to_user_id: 1001,
+///     amount: 500,
+///     timestamp: 1633046400,
+///     status: TxStatus::Success,
+///     description: None,
+/// }];
+///
+/// assert_eq!(estimate_output_size(&records, YPFormatSupported::Binary), 72);
+/// ```
+pub fn estimate_output_size(records: &[YPBankTransaction], format: YPFormatSupported) -> usize {
+    // Описание в кавычках, экранированное так же, как при записи формата.
+    let quoted_len = |tx: &YPBankTransaction, escape: fn(&str) -> String| {
+        tx.description.as_deref().map_or(0, |d| escape(d).len()) + 2
+    };
+
+    match format {
+        YPFormatSupported::Binary => {
+            let body: usize = records
+                .iter()
+                .map(|tx| {
+                    let desc_len = tx.description.as_deref().map_or(0, str::len);
+                    4 + 4 + format::bin::FIXED_BODY_SIZE + desc_len + format::bin::CRC_SIZE
+                })
+                .sum();
+            format::bin::HEADER_SIZE + body
+        }
+        YPFormatSupported::Csv => {
+            let header = YPBankCsvFormat::fields().join(",").len() + 1;
+            let body: usize = records
+                .iter()
+                .map(|tx| {
+                    digits(tx.tx_id)
+                        + tx.tx_type.to_string().len()
+                        + digits(tx.from_user_id)
+                        + digits(tx.to_user_id)
+                        + digits(tx.amount.unsigned_abs())
+                        + digits(tx.timestamp)
+                        + tx.status.to_string().len()
+                        + quoted_len(tx, |d| d.escaped_quote())
+                        + 7 // запятые
+                        + 1 // перевод строки
+                })
+                .sum();
+            header + body
+        }
+        YPFormatSupported::Text => records
+            .iter()
+            .map(|tx| {
+                let tx_type = tx.tx_type.to_string().len();
+                let title =
+                    "# Record  ()\n".len() + digits(tx.tx_id % 1_000_000_000_000_000) + tx_type;
+                let keys: usize = YPBankTextFormat::fields()
+                    .iter()
+                    .map(|key| key.len() + ": \n".len())
+                    .sum();
+                let values = digits(tx.tx_id)
+                    + tx_type
+                    + digits(tx.from_user_id)
+                    + digits(tx.to_user_id)
+                    + digits(tx.amount.unsigned_abs())
+                    + digits(tx.timestamp)
+                    + tx.status.to_string().len()
+                    + quoted_len(tx, |d| d.escaped_control().escaped_quote());
+                title + keys + values + 1 // пустая строка между блоками
+            })
+            .sum(),
+    }
+}
+
+/// Количество десятичных знаков в числе.
+fn digits(value: u64) -> usize {
+    value.checked_ilog10().map_or(1, |log| log as usize + 1)
+}
+
 /// Трейт предоставляющий дополнительные методы для векторов, содержащих структуры данных
 /// в форматах обработки файлов.
 pub trait Transaction {
@@ -146,6 +309,42 @@ pub fn write_csv<W: Write>(writer: &mut W, records: &[YPBankCsvFormat]) -> Resul
     YPBankCsvFormat::write_to(writer, records)
 }
 
+/// Считывает данные в формате `json`.
+///
+/// Обёртка для низкоуровневого метода [`YPBankJsonFormat::read_from`].
+///
+/// ## Пример
+///
+/// ```no_run
+/// use std::fs::File;
+/// use parser::read_json;
+///
+/// let mut file = File::open("data.json").unwrap();
+/// let data = read_json(&mut file);
+/// ```
+///
+/// ## Returns
+///
+/// Вектор с элементами [`YPBankJsonFormat`] при успешном разборе, либо [`ParseError`] в случае
+/// ошибки.
+pub fn read_json<R: Read>(readers: &mut R) -> Result<Vec<YPBankJsonFormat>, ParseError> {
+    YPBankJsonFormat::read_from(readers)
+}
+
+/// Записывает данные в формате `json`.
+///
+/// Обёртка для низкоуровневого метода [`YPBankJsonFormat::write_to`].
+///
+/// ## Returns
+///
+/// При успешной записи пустой `Result`, и [`ParseError`] в случае ошибки.
+pub fn write_json<W: Write>(
+    writer: &mut W,
+    records: &[YPBankJsonFormat],
+) -> Result<(), ParseError> {
+    YPBankJsonFormat::write_to(writer, records)
+}
+
 /// Считывает данные в бинарном формате (`bin`).
 ///
 /// Обёртка для низкоуровневого метода [`YPBankBinFormat::read_from`].
@@ -196,6 +395,7 @@ pub fn read_bin<R: Read>(readers: &mut R) -> Result<Vec<YPBankBinFormat>, ParseE
 ///         status: TxStatus::Success,
 ///         desc_len: 0,
 ///         description: None,
+///         raw_description: None,
 ///     },
 /// ];
 ///
@@ -276,11 +476,285 @@ pub fn write_text<R: Write>(
     YPBankTextFormat::write_to(writer, records)
 }
 
+/// Проверяет, что данные в формате `format` завершаются на границе записи.
+///
+/// Помогает обнаружить прерванную запись файла: последняя запись оборвана (неполное тело
+/// бинарной записи, обрезанная строка `csv`, блок `txt` без части полей). Записи целиком не
+/// разбираются: проверяются только заголовки бинарных записей или последняя запись текстовых
+/// форматов.
+///
+/// ## Пример
+///
+/// ```no_run
+/// use std::fs::File;
+/// use parser::{YPFormatSupported, is_complete};
+///
+/// let mut file = File::open("data.bin").unwrap();
+/// if !is_complete(&mut file, YPFormatSupported::Binary).unwrap() {
+///     eprintln!("Файл оборван");
+/// }
+/// ```
+///
+/// ## Returns
+///
+/// `true`, если последняя запись целая (в том числе для данных без записей), иначе `false`.
+/// Повреждения, не связанные с обрывом (например, некорректный заголовок `csv`), возвращаются
+/// как [`ParseError`].
+pub fn is_complete<R: Read>(
+    readers: &mut R,
+    format: YPFormatSupported,
+) -> Result<bool, ParseError> {
+    match format {
+        YPFormatSupported::Text => YPBankTextFormat::is_complete(readers),
+        YPFormatSupported::Csv => YPBankCsvFormat::is_complete(readers),
+        YPFormatSupported::Binary => YPBankBinFormat::is_complete(readers),
+    }
+}
+
+/// Считывает данные в формате `format` и применяет `f` к каждой транзакции по мере
+/// преобразования в [`YPBankTransaction`].
+///
+/// Позволяет сразу получить собственные типы вызывающей стороны без промежуточного вектора
+/// [`YPBankTransaction`]. Записи читаются потоком (`read_iter` формата), поэтому в памяти
+/// накапливаются только результаты `f`, а ограничение на объём данных не действует. При первой
+/// ошибке чтения или преобразования обработка прекращается; источник без записей — ошибка
+/// [`ParseError::EmptyData`].
+///
+/// ## Пример
+///
+/// ```no_run
+/// use std::fs::File;
+/// use parser::{YPFormatSupported, read_map};
+///
+/// let mut file = File::open("data.csv").unwrap();
+/// let ids: Vec<u64> = read_map(&mut file, YPFormatSupported::Csv, |tx| tx.tx_id).unwrap();
+/// ```
+pub fn read_map<R, T, F>(
+    readers: &mut R,
+    format: YPFormatSupported,
+    f: F,
+) -> Result<Vec<T>, ParseError>
+where
+    R: Read,
+    F: FnMut(YPBankTransaction) -> T,
+{
+    match format {
+        YPFormatSupported::Text => map_records(YPBankTextFormat::read_iter(readers), f),
+        YPFormatSupported::Csv => map_records(YPBankCsvFormat::read_iter(readers), f),
+        YPFormatSupported::Binary => map_records(YPBankBinFormat::read_iter(readers), f),
+    }
+}
+
+/// Преобразует записи формата из потока в [`YPBankTransaction`] и применяет к ним `f`.
+fn map_records<S, T, F>(
+    records: impl Iterator<Item = Result<S, ParseError>>,
+    mut f: F,
+) -> Result<Vec<T>, ParseError>
+where
+    YPBankTransaction: TryFrom<S, Error = ParseError>,
+    F: FnMut(YPBankTransaction) -> T,
+{
+    let mapped: Vec<T> = records
+        .map(|record| YPBankTransaction::try_from(record?).map(&mut f))
+        .collect::<Result<_, _>>()?;
+    if mapped.is_empty() {
+        return Err(ParseError::empty_data());
+    }
+
+    Ok(mapped)
+}
+
+/// Считывает данные в формате `format` в словарь транзакций по `TX_ID`.
+///
+/// Повторяющийся `TX_ID` — ошибка [`ParseError::ParseError`] с номером повторной записи.
+/// Другое поведение при повторах задаётся в [`load_index_with`].
+///
+/// ## Пример
+///
+/// ```no_run
+/// use std::fs::File;
+/// use parser::{YPFormatSupported, load_index};
+///
+/// let mut file = File::open("data.csv").unwrap();
+/// let index = load_index(&mut file, YPFormatSupported::Csv).unwrap();
+/// let amount = index.get(&1000000000000000).map(|tx| tx.amount);
+/// ```
+pub fn load_index<R: Read>(
+    readers: &mut R,
+    format: YPFormatSupported,
+) -> Result<HashMap<u64, YPBankTransaction>, ParseError> {
+    load_index_with(readers, format, DuplicateIdPolicy::Error)
+}
+
+/// Считывает данные в словарь транзакций по `TX_ID` с заданным поведением при повторах.
+pub fn load_index_with<R: Read>(
+    readers: &mut R,
+    format: YPFormatSupported,
+    policy: DuplicateIdPolicy,
+) -> Result<HashMap<u64, YPBankTransaction>, ParseError> {
+    index_by_id(format.to_transaction(readers)?, policy)
+}
+
+/// Вычисляет хеш содержимого источника данных в указанном формате.
+///
+/// Записи разбираются и приводятся к универсальному виду [`YPBankTransaction`], после чего
+/// хешируется их каноническое представление. Поэтому два источника с одинаковыми транзакциями
+/// в одинаковом порядке дают один и тот же хеш, независимо от особенностей оформления: кавычек,
+/// окончаний строк и даже самого формата.
+///
+/// Используется алгоритм FNV-1a (64 бит): результат стабилен между запусками и платформами, что
+/// позволяет хранить хеш, например, для инвалидации кеша. Криптографической стойкости нет.
+///
+/// ## Пример
+///
+/// ```no_run
+/// use std::fs::File;
+/// use parser::{YPFormatSupported, content_hash};
+///
+/// let mut file = File::open("data.csv").unwrap();
+/// let hash = content_hash(&mut file, YPFormatSupported::Csv).unwrap();
+/// ```
+pub fn content_hash<R: Read>(
+    readers: &mut R,
+    format: YPFormatSupported,
+) -> Result<u64, ParseError> {
+    let transactions = format.to_transaction(readers)?;
+    Ok(transactions_hash(&transactions))
+}
+
+/// Вычисляет хеш последовательности транзакций по тем же правилам, что и [`content_hash`].
+pub fn transactions_hash(transactions: &[YPBankTransaction]) -> u64 {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    transactions
+        .iter()
+        .flat_map(|tx| tx.canonical_bytes())
+        .fold(FNV_OFFSET_BASIS, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+        })
+}
+
+/// Сколько байт начала данных просматривает [`detect_format`] при чтении
+/// [`YPFormatSupported::to_transaction`].
+pub(crate) const SNIFF_SIZE: usize = 512;
+
+/// Определяет формат данных по их началу. Возвращает `None`, если формат неочевиден.
+///
+/// Проверка дешёвая и консервативная: бинарные данные узнаются по `MAGIC` (в том числе
+/// заголовка файла), текстовые — по первой непустой строке. Строка с `#` в начале или парой
+/// `ключ: значение` указывает на `txt`, строка с запятыми без двоеточий — на заголовок `csv`.
+///
+/// ## Пример
+///
+/// ```
+/// use parser::{YPFormatSupported, detect_format};
+///
+/// assert_eq!(detect_format(b"# Record 1 (DEPOSIT)\n"), Some(YPFormatSupported::Text));
+/// assert_eq!(detect_format(b"TX_ID,TX_TYPE\n"), Some(YPFormatSupported::Csv));
+/// assert_eq!(detect_format(b""), None);
+/// ```
+pub fn detect_format(data: &[u8]) -> Option<YPFormatSupported> {
+    if data.starts_with(&format::bin::MAGIC) || data.starts_with(&format::bin::MAGIC_CRC) {
+        return Some(YPFormatSupported::Binary);
+    }
+
+    // Начало может оборвать многобайтовый символ: берётся только корректная часть.
+    let text = match std::str::from_utf8(data) {
+        Ok(text) => text,
+        Err(err) => std::str::from_utf8(&data[..err.valid_up_to()]).ok()?,
+    };
+    let line = text
+        .strip_prefix('\u{FEFF}')
+        .unwrap_or(text)
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())?;
+
+    if line.chars().any(|c| c.is_control() && c != '\t') {
+        None
+    } else if line.starts_with('#') || line.contains(':') {
+        Some(YPFormatSupported::Text)
+    } else if line.contains(',') {
+        Some(YPFormatSupported::Csv)
+    } else {
+        None
+    }
+}
+
+/// Определяет формат данных источника без расширения файла и возвращает позицию чтения туда,
+/// где она была до вызова, чтобы данные можно было разобрать.
+///
+/// В отличие от [`detect_format`], признаки проверяются строго:
+///
+/// * `bin` — данные начинаются с `MAGIC` (в том числе заголовка файла);
+/// * `csv` — первая непустая строка совпадает с эталонным заголовком `csv`;
+/// * `txt` — первая непустая строка, не являющаяся комментарием, — заголовок записи
+///   вида `# Record 1 (DEPOSIT)`.
+///
+/// Иначе возвращается [`ParseError::UnsupportedFormat`].
+///
+/// ## Пример
+///
+/// ```no_run
+/// use std::fs::File;
+/// use parser::sniff_format;
+///
+/// let mut file = File::open("export").unwrap();
+/// let format = sniff_format(&mut file).unwrap();
+/// let transactions = format.to_transaction(&mut file).unwrap();
+/// ```
+pub fn sniff_format<R: Read + Seek>(reader: &mut R) -> Result<YPFormatSupported, ParseError> {
+    let start = reader.stream_position()?;
+    let mut prefix = Vec::with_capacity(SNIFF_SIZE);
+    let read = reader.take(SNIFF_SIZE as u64).read_to_end(&mut prefix);
+    reader.seek(SeekFrom::Start(start))?;
+    read.map_err(|e| ParseError::io_error(e, "Ошибка чтения начала данных"))?;
+
+    sniff_prefix(&prefix)
+}
+
+/// Определяет формат по начальному фрагменту данных по правилам [`sniff_format`].
+pub(crate) fn sniff_prefix(prefix: &[u8]) -> Result<YPFormatSupported, ParseError> {
+    if prefix.starts_with(&format::bin::MAGIC) || prefix.starts_with(&format::bin::MAGIC_CRC) {
+        return Ok(YPFormatSupported::Binary);
+    }
+
+    let text = match std::str::from_utf8(prefix) {
+        Ok(text) => text,
+        Err(err) => std::str::from_utf8(&prefix[..err.valid_up_to()]).unwrap_or_default(),
+    };
+    let mut lines = text
+        .strip_prefix('\u{FEFF}')
+        .unwrap_or(text)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty());
+
+    let csv_header = YPBankCsvFormat::fields().join(",");
+    let first = lines.next();
+    if first == Some(csv_header.as_str()) {
+        return Ok(YPFormatSupported::Csv);
+    }
+
+    let title = first
+        .into_iter()
+        .chain(lines)
+        .find(|line| !line.starts_with('#') || YPBankTextFormat::parse_title(line).is_some());
+    match title {
+        Some(line) if YPBankTextFormat::parse_title(line).is_some() => Ok(YPFormatSupported::Text),
+        _ => Err(ParseError::UnsupportedFormat {
+            invalid_format: "неопознанные данные".to_string(),
+        }),
+    }
+}
+
 /// Поддерживаемые форматы данных, используемые для чтения и записи в случаях, когда возможна
 /// работа с двумя разными типами (например, `csv` и `txt`): конвертация, сравнение.
 ///
 /// При работе с одним типом необходимо использовать прямые методы. Например, [`read_text`] для
 /// чтения в текстовом формате, [`write_bin`] — для записи в бинарном формате, и так далее.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum YPFormatSupported {
     /// Текстовый формат (`*.txt`): человекочитаемый формат, хранящий данные в виде обычного текста.
     Text,
@@ -334,15 +808,95 @@ impl YPFormatSupported {
     /// ## Returns
     ///
     /// В случае успеха возвращён будет набор элементов [`YPBankTransaction`] в векторе. При ошибке
-    /// [`ParseError`].
+    /// [`ParseError`]. Если начало данных явно принадлежит другому формату (см.
+    /// [`detect_format`]), возвращается [`ParseError::InvalidFormat`].
     pub fn to_transaction<R: Read>(
         &self,
         readers: &mut R,
+    ) -> Result<Vec<YPBankTransaction>, ParseError> {
+        let mut prefix = Vec::with_capacity(SNIFF_SIZE);
+        readers
+            .take(SNIFF_SIZE as u64)
+            .read_to_end(&mut prefix)
+            .map_err(|e| ParseError::io_error(e, "Ошибка парсинга данных"))?;
+        self.check_detected(&prefix)?;
+
+        let mut readers = prefix.as_slice().chain(readers);
+        match self {
+            YPFormatSupported::Text => read_text(&mut readers)?.convert_to_transaction(),
+            YPFormatSupported::Csv => read_csv(&mut readers)?.convert_to_transaction(),
+            YPFormatSupported::Binary => read_bin(&mut readers)?.convert_to_transaction(),
+        }
+    }
+
+    /// Сверяет формат с определённым по началу данных `prefix`.
+    fn check_detected(&self, prefix: &[u8]) -> Result<(), ParseError> {
+        match detect_format(prefix) {
+            Some(detected) if detected != *self => Err(ParseError::invalid_format(
+                self.to_string(),
+                detected.to_string(),
+                None,
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Считывает данные как [`YPFormatSupported::to_transaction`], но не более `max_records`
+    /// записей.
+    ///
+    /// Защищает от чрезмерного потребления ресурсов при чтении недоверенных файлов: при
+    /// превышении лимита возвращается [`ParseError::RecordLimitExceeded`]. `None` снимает
+    /// ограничение.
+    pub fn to_transaction_limited<R: Read>(
+        &self,
+        readers: &mut R,
+        max_records: Option<usize>,
     ) -> Result<Vec<YPBankTransaction>, ParseError> {
         match self {
-            YPFormatSupported::Text => read_text(readers)?.convert_to_transaction(),
-            YPFormatSupported::Csv => read_csv(readers)?.convert_to_transaction(),
-            YPFormatSupported::Binary => read_bin(readers)?.convert_to_transaction(),
+            YPFormatSupported::Text => {
+                YPBankTextFormat::read_from_limited(readers, max_records)?.convert_to_transaction()
+            }
+            YPFormatSupported::Csv => {
+                YPBankCsvFormat::read_from_limited(readers, max_records)?.convert_to_transaction()
+            }
+            YPFormatSupported::Binary => {
+                YPBankBinFormat::read_from_limited(readers, max_records)?.convert_to_transaction()
+            }
+        }
+    }
+
+    /// Потоковое чтение транзакций: записи читаются `read_iter` формата и преобразуются
+    /// в [`YPBankTransaction`] по одной, поэтому данные не загружаются в память целиком
+    /// и ограничение на их объём не действует. После первой ошибки итерация завершается.
+    ///
+    /// ## Пример
+    ///
+    /// ```no_run
+    /// use std::fs::File;
+    /// use parser::YPFormatSupported;
+    ///
+    /// let file = File::open("data.csv").unwrap();
+    /// for tx in YPFormatSupported::Csv.read_iter(file) {
+    ///     println!("{}", tx.unwrap().tx_id);
+    /// }
+    /// ```
+    pub fn read_iter<'a, R: Read + 'a>(
+        &self,
+        readers: R,
+    ) -> Box<dyn Iterator<Item = Result<YPBankTransaction, ParseError>> + 'a> {
+        match self {
+            YPFormatSupported::Text => Box::new(
+                YPBankTextFormat::read_iter(readers)
+                    .map(|record| YPBankTransaction::try_from(record?)),
+            ),
+            YPFormatSupported::Csv => Box::new(
+                YPBankCsvFormat::read_iter(readers)
+                    .map(|record| YPBankTransaction::try_from(record?)),
+            ),
+            YPFormatSupported::Binary => Box::new(
+                YPBankBinFormat::read_iter(readers)
+                    .map(|record| YPBankTransaction::try_from(record?)),
+            ),
         }
     }
 
@@ -424,4 +978,605 @@ impl YPFormatSupported {
             }
         }
     }
+
+    /// Запись транзакций [`YPBankTransaction`] в выбранном формате — пара к
+    /// [`YPFormatSupported::to_transaction`].
+    ///
+    /// Каждая транзакция преобразуется в структуру формата через `TryFrom`, как в
+    /// [`YPFormatSupported::convert_transactions`]. Ошибка преобразования (например,
+    /// переполнение суммы) возвращается как [`ParseError`] до записи первой записи.
+    ///
+    /// ## Пример
+    ///
+    /// ```no_run
+    /// use std::fs::File;
+    /// use parser::YPFormatSupported;
+    ///
+    /// let mut source = File::open("data.csv").unwrap();
+    /// let transactions = YPFormatSupported::Csv.to_transaction(&mut source).unwrap();
+    ///
+    /// let mut target = File::create("data.bin").unwrap();
+    /// YPFormatSupported::Binary
+    ///     .write_transactions(&mut target, &transactions)
+    ///     .unwrap();
+    /// ```
+    pub fn write_transactions<W: Write>(
+        &self,
+        writer: &mut W,
+        txs: &[YPBankTransaction],
+    ) -> Result<(), ParseError> {
+        self.convert_transactions(writer, txs)
+    }
+
+    /// Потоковая запись транзакций — пара к [`YPFormatSupported::read_iter`].
+    ///
+    /// В `csv` и `txt` каждая транзакция преобразуется и пишется сразу после получения. Бинарный
+    /// формат объявляет число записей в заголовке файла, поэтому для него транзакции сначала
+    /// накапливаются и пишутся целиком, как в [`YPFormatSupported::write_transactions`]. Первая
+    /// ошибка источника или преобразования прерывает запись.
+    ///
+    /// Возвращает число записанных транзакций.
+    pub fn write_iter<W: Write>(
+        &self,
+        writer: &mut W,
+        txs: impl IntoIterator<Item = Result<YPBankTransaction, ParseError>>,
+    ) -> Result<usize, ParseError> {
+        let txs = txs.into_iter();
+        match self {
+            YPFormatSupported::Text => {
+                YPBankTextFormat::write_iter(writer, txs.map(|tx| tx?.try_into()))
+            }
+            YPFormatSupported::Csv => {
+                YPBankCsvFormat::write_iter(writer, txs.map(|tx| tx?.try_into()))
+            }
+            YPFormatSupported::Binary => {
+                let records = txs
+                    .map(|tx| tx?.try_into())
+                    .collect::<Result<Vec<YPBankBinFormat>, ParseError>>()?;
+                write_bin(writer, &records)?;
+                Ok(records.len())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    const CSV_DATA: &str = "TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION\n\
+                            123456789,TRANSFER,1001,1002,50000,1633046400,SUCCESS,\"Test transaction\"\n\
+                            987654321,DEPOSIT,0,1003,100000,1633046401,PENDING,\"\"\n";
+
+    #[test]
+    fn test_is_complete_per_format() {
+        // Arrange
+        let records = YPFormatSupported::Csv
+            .to_transaction(&mut Cursor::new(CSV_DATA))
+            .unwrap();
+
+        for format in [
+            YPFormatSupported::Text,
+            YPFormatSupported::Csv,
+            YPFormatSupported::Binary,
+        ] {
+            let mut output = Vec::new();
+            format.convert_transactions(&mut output, &records).unwrap();
+            // Обрыв посреди последней записи: отбрасываем хвост вместе с описанием.
+            let truncated = &output[..output.len() - 12];
+
+            // Act
+            let clean = is_complete(&mut output.as_slice(), format).unwrap();
+            let cut = is_complete(&mut &truncated[..], format).unwrap();
+
+            // Assert
+            assert!(clean, "{format}: целый файл");
+            assert!(!cut, "{format}: оборванный файл");
+        }
+    }
+
+    /// Писатель, принимающий данные, но не способный их сбросить.
+    struct FailingFlush;
+
+    impl Write for FailingFlush {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Err(std::io::Error::other("disk full"))
+        }
+    }
+
+    #[test]
+    fn test_write_surfaces_flush_error() {
+        // Arrange
+        let records = YPFormatSupported::Csv
+            .to_transaction(&mut Cursor::new(CSV_DATA))
+            .unwrap();
+
+        for format in [
+            YPFormatSupported::Text,
+            YPFormatSupported::Csv,
+            YPFormatSupported::Binary,
+        ] {
+            // Act
+            let result = format.convert_transactions(&mut FailingFlush, &records);
+
+            // Assert
+            assert!(
+                matches!(result, Err(ParseError::IOError { .. })),
+                "{format}: ошибка сброса должна вернуться"
+            );
+        }
+    }
+
+    #[test]
+    fn test_read_csv_header_only_is_empty_data() {
+        // Arrange
+        let header = CSV_DATA.lines().next().unwrap();
+
+        // Act
+        let result = read_csv(&mut Cursor::new(header));
+
+        // Assert
+        assert!(matches!(result, Err(ParseError::EmptyData)));
+        assert_eq!(
+            ParseError::empty_data().to_string(),
+            "Источник не содержит ни одной записи"
+        );
+    }
+
+    #[test]
+    fn test_load_index_by_tx_id() {
+        // Act
+        let index = load_index(&mut Cursor::new(CSV_DATA), YPFormatSupported::Csv).unwrap();
+
+        // Assert
+        assert_eq!(index.len(), 2);
+        assert_eq!(index[&123456789].amount, -50000);
+        assert_eq!(index[&987654321].to_user_id, 1003);
+    }
+
+    #[test]
+    fn test_load_index_duplicate_tx_id() {
+        // Arrange
+        let duplicate = CSV_DATA.lines().nth(1).unwrap();
+        let data = format!("{CSV_DATA}{duplicate}\n");
+
+        // Act
+        let strict = load_index(&mut Cursor::new(&data), YPFormatSupported::Csv);
+        let last = load_index_with(
+            &mut Cursor::new(&data),
+            YPFormatSupported::Csv,
+            DuplicateIdPolicy::KeepLast,
+        )
+        .unwrap();
+
+        // Assert
+        assert!(matches!(
+            strict,
+            Err(ParseError::ParseError { line: 3, .. })
+        ));
+        assert_eq!(last.len(), 2);
+    }
+
+    #[test]
+    fn test_read_map_projects_tx_id() {
+        // Act
+        let ids = read_map(&mut Cursor::new(CSV_DATA), YPFormatSupported::Csv, |tx| {
+            tx.tx_id
+        })
+        .unwrap();
+
+        // Assert
+        assert_eq!(ids, vec![123456789, 987654321]);
+    }
+
+    #[test]
+    fn test_read_map_streams_every_format() {
+        // Arrange
+        let records = YPFormatSupported::Csv
+            .to_transaction(&mut Cursor::new(CSV_DATA))
+            .unwrap();
+        let csv_header = CSV_DATA.lines().next().unwrap();
+
+        for format in [
+            YPFormatSupported::Text,
+            YPFormatSupported::Csv,
+            YPFormatSupported::Binary,
+        ] {
+            let mut buffer = Vec::new();
+            format.convert_transactions(&mut buffer, &records).unwrap();
+            let empty = match format {
+                YPFormatSupported::Csv => csv_header.as_bytes(),
+                _ => b"",
+            };
+
+            // Act
+            let amounts = read_map(&mut Cursor::new(&buffer), format, |tx| tx.amount).unwrap();
+            let empty = read_map(&mut Cursor::new(empty), format, |tx| tx.amount);
+
+            // Assert
+            assert_eq!(
+                amounts,
+                records.iter().map(|tx| tx.amount).collect::<Vec<_>>()
+            );
+            assert_eq!(empty, Err(ParseError::empty_data()), "{format}");
+        }
+    }
+
+    #[test]
+    fn test_to_transaction_per_format() {
+        // Arrange
+        let records = YPFormatSupported::Csv
+            .to_transaction(&mut Cursor::new(CSV_DATA))
+            .unwrap();
+
+        for (format, name) in [
+            (YPFormatSupported::Text, "txt"),
+            (YPFormatSupported::Csv, "csv"),
+            (YPFormatSupported::Binary, "bin"),
+        ] {
+            let mut buffer = Vec::new();
+            format.convert_transactions(&mut buffer, &records).unwrap();
+
+            // Act
+            let mut result = format.to_transaction(&mut Cursor::new(buffer)).unwrap();
+
+            // Assert: бинарный формат не различает пустое и отсутствующее описание.
+            for (read, original) in result.iter_mut().zip(&records) {
+                if original.description.as_deref() == Some("") {
+                    read.description.get_or_insert_default();
+                }
+            }
+            assert_eq!(format.to_string(), name);
+            assert_eq!(result, records, "{format}");
+        }
+    }
+
+    #[test]
+    fn test_write_transactions_round_trip_every_format() {
+        // Arrange
+        let transactions = testing::generate(20, 267);
+
+        for format in [
+            YPFormatSupported::Text,
+            YPFormatSupported::Csv,
+            YPFormatSupported::Binary,
+        ] {
+            let mut buffer = Vec::new();
+
+            // Act
+            format
+                .write_transactions(&mut buffer, &transactions)
+                .unwrap();
+            let mut result = format.to_transaction(&mut Cursor::new(buffer)).unwrap();
+
+            // Assert: текстовые форматы читают отсутствующее описание как пустую строку.
+            for tx in &mut result {
+                tx.description = tx.description.take().filter(|d| !d.is_empty());
+            }
+            assert_eq!(result, transactions, "{format}");
+        }
+    }
+
+    #[test]
+    fn test_write_to_same_buffer_sequentially() {
+        // Arrange
+        let first: Vec<YPBankTextFormat> = testing::generate(3, 289)
+            .into_iter()
+            .map(YPBankTextFormat::try_from)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let second: Vec<YPBankTextFormat> = testing::generate(2, 290)
+            .into_iter()
+            .map(YPBankTextFormat::try_from)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let mut buffer = Vec::new();
+
+        // Act
+        YPBankTextFormat::write_to(&mut buffer, &first).unwrap();
+        let written = buffer.len();
+        YPBankTextFormat::write_to(&mut buffer, &second).unwrap();
+        let result = YPBankTextFormat::read_from(&mut Cursor::new(buffer)).unwrap();
+
+        // Assert
+        assert!(written > 0);
+        assert_eq!(result.len(), first.len() + second.len());
+        assert_eq!(result[..first.len()], first[..]);
+        assert_eq!(result[first.len()..], second[..]);
+    }
+
+    #[test]
+    fn test_detect_format() {
+        // Arrange
+        let records = YPFormatSupported::Csv
+            .to_transaction(&mut Cursor::new(CSV_DATA))
+            .unwrap();
+        let mut text = Vec::new();
+        YPFormatSupported::Text
+            .write_transactions(&mut text, &records)
+            .unwrap();
+        let mut binary = Vec::new();
+        YPFormatSupported::Binary
+            .write_transactions(&mut binary, &records)
+            .unwrap();
+
+        // Act & Assert
+        assert_eq!(
+            detect_format(CSV_DATA.as_bytes()),
+            Some(YPFormatSupported::Csv)
+        );
+        assert_eq!(detect_format(&text), Some(YPFormatSupported::Text));
+        assert_eq!(detect_format(&binary), Some(YPFormatSupported::Binary));
+        assert_eq!(detect_format(&[0x00, 0xFF, 0x10]), None);
+        assert_eq!(detect_format(b"\n\n"), None);
+    }
+
+    #[test]
+    fn test_sniff_format_rewinds_reader() {
+        // Arrange
+        let records = YPFormatSupported::Csv
+            .to_transaction(&mut Cursor::new(CSV_DATA))
+            .unwrap();
+
+        for format in [
+            YPFormatSupported::Text,
+            YPFormatSupported::Csv,
+            YPFormatSupported::Binary,
+        ] {
+            let mut buffer = Vec::new();
+            format.write_transactions(&mut buffer, &records).unwrap();
+            let mut reader = Cursor::new(buffer);
+
+            // Act
+            let sniffed = sniff_format(&mut reader).unwrap();
+            let result = sniffed.to_transaction(&mut reader).unwrap();
+
+            // Assert
+            assert_eq!(sniffed, format);
+            assert_eq!(result.len(), records.len(), "{format}");
+        }
+    }
+
+    #[test]
+    fn test_sniff_format_text_after_comments() {
+        // Arrange
+        let mut reader = Cursor::new("\u{FEFF}# export 2021-10-01\n\n# Record 1 (DEPOSIT)\n");
+
+        // Act
+        let result = sniff_format(&mut reader);
+
+        // Assert
+        assert_eq!(result.unwrap(), YPFormatSupported::Text);
+        assert_eq!(reader.position(), 0);
+    }
+
+    #[test]
+    fn test_sniff_format_unrecognized() {
+        // Arrange
+        let blobs: [&[u8]; 4] = [
+            &[0x00, 0xFF, 0x10, 0x59],
+            b"",
+            b"TX_ID;TX_TYPE;AMOUNT\n",
+            b"# just a comment\nTX_ID: 1\n",
+        ];
+
+        for blob in blobs {
+            // Act
+            let result = sniff_format(&mut Cursor::new(blob));
+
+            // Assert
+            assert!(
+                matches!(result, Err(ParseError::UnsupportedFormat { .. })),
+                "{blob:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_to_transaction_rejects_other_format() {
+        // Arrange
+        let records = YPFormatSupported::Csv
+            .to_transaction(&mut Cursor::new(CSV_DATA))
+            .unwrap();
+        let mut binary = Vec::new();
+        YPFormatSupported::Binary
+            .write_transactions(&mut binary, &records)
+            .unwrap();
+
+        // Act
+        let bin_as_csv = YPFormatSupported::Csv.to_transaction(&mut Cursor::new(binary));
+        let csv_as_bin = YPFormatSupported::Binary.to_transaction(&mut Cursor::new(CSV_DATA));
+        let csv_as_text = YPFormatSupported::Text.to_transaction(&mut Cursor::new(CSV_DATA));
+
+        // Assert
+        assert!(matches!(
+            bin_as_csv,
+            Err(ParseError::InvalidFormat { ref expected, ref got, .. })
+                if expected == "csv" && got == "bin"
+        ));
+        assert!(matches!(
+            csv_as_bin,
+            Err(ParseError::InvalidFormat { ref got, .. }) if got == "csv"
+        ));
+        assert!(matches!(
+            csv_as_text,
+            Err(ParseError::InvalidFormat { ref got, .. }) if got == "csv"
+        ));
+    }
+
+    #[test]
+    fn test_write_transactions_propagates_overflow() {
+        // Arrange
+        let mut transactions = testing::generate(3, 1);
+        transactions[1].amount = i64::MIN;
+
+        for format in [YPFormatSupported::Text, YPFormatSupported::Csv] {
+            let mut buffer = Vec::new();
+
+            // Act
+            let result = format.write_transactions(&mut buffer, &transactions);
+
+            // Assert
+            assert!(
+                matches!(result, Err(ParseError::OverflowSize { .. })),
+                "{format}"
+            );
+            assert!(buffer.is_empty(), "{format}");
+        }
+    }
+
+    #[test]
+    fn test_read_iter_write_iter_round_trip() {
+        // Arrange
+        let expected = YPFormatSupported::Csv
+            .to_transaction(&mut Cursor::new(CSV_DATA))
+            .unwrap();
+
+        for format in [
+            YPFormatSupported::Text,
+            YPFormatSupported::Csv,
+            YPFormatSupported::Binary,
+        ] {
+            let mut output = Vec::new();
+
+            // Act
+            let written = format
+                .write_iter(
+                    &mut output,
+                    YPFormatSupported::Csv.read_iter(CSV_DATA.as_bytes()),
+                )
+                .unwrap();
+            let result: Vec<YPBankTransaction> = format
+                .read_iter(output.as_slice())
+                .collect::<Result<_, _>>()
+                .unwrap();
+
+            // Assert
+            let mut buffered = Vec::new();
+            format.write_transactions(&mut buffered, &expected).unwrap();
+            assert_eq!(written, expected.len(), "{format}");
+            assert_eq!(output, buffered, "{format}");
+            assert_eq!(result.len(), expected.len(), "{format}");
+            assert_eq!(result[0], expected[0], "{format}");
+        }
+    }
+
+    #[test]
+    fn test_to_transaction_limited() {
+        // Act
+        let at_limit =
+            YPFormatSupported::Csv.to_transaction_limited(&mut Cursor::new(CSV_DATA), Some(2));
+        let over_limit =
+            YPFormatSupported::Csv.to_transaction_limited(&mut Cursor::new(CSV_DATA), Some(1));
+
+        // Assert
+        assert_eq!(at_limit.unwrap().len(), 2);
+        assert!(matches!(
+            over_limit,
+            Err(ParseError::RecordLimitExceeded { limit: 1 })
+        ));
+    }
+
+    #[test]
+    fn test_assert_file_matches() {
+        // Arrange
+        let expected = YPFormatSupported::Csv
+            .to_transaction(&mut Cursor::new(CSV_DATA))
+            .unwrap();
+        let mut file = Vec::new();
+        YPFormatSupported::Binary
+            .convert_transactions(&mut file, &expected)
+            .unwrap();
+        let mut changed = expected.clone();
+        changed[1].amount += 1;
+
+        // Act
+        let matched =
+            assert_file_matches(&mut file.as_slice(), YPFormatSupported::Binary, &expected);
+        let mismatched =
+            assert_file_matches(&mut file.as_slice(), YPFormatSupported::Binary, &changed);
+
+        // Assert
+        assert!(matched.is_ok());
+        let diffs = mismatched.unwrap_err();
+        assert_eq!(diffs.len(), 1);
+        assert!(matches!(
+            diffs[0],
+            diff::RecordDiff::Changed { index: 1, .. }
+        ));
+    }
+
+    #[test]
+    fn test_estimate_output_size_matches_output() {
+        // Arrange
+        let mut records = testing::generate(50, 11);
+        records[0].description = Some("With \"quotes\", commas".to_string());
+        records[1].description = None;
+        records[2].description = Some("Line\nbreak\ttab \\ slash \"q\"".to_string());
+
+        for format in [
+            YPFormatSupported::Csv,
+            YPFormatSupported::Text,
+            YPFormatSupported::Binary,
+        ] {
+            // Act
+            let estimate = estimate_output_size(&records, format);
+            let mut output = Vec::new();
+            format.convert_transactions(&mut output, &records).unwrap();
+
+            // Assert
+            assert_eq!(estimate, output.len(), "{format}");
+        }
+    }
+
+    #[test]
+    fn test_content_hash_ignores_line_endings() {
+        // Arrange
+        let crlf_data = CSV_DATA.replace('\n', "\r\n");
+
+        // Act
+        let hash_lf = content_hash(&mut Cursor::new(CSV_DATA), YPFormatSupported::Csv).unwrap();
+        let hash_crlf = content_hash(&mut Cursor::new(crlf_data), YPFormatSupported::Csv).unwrap();
+
+        // Assert
+        assert_eq!(hash_lf, hash_crlf);
+    }
+
+    #[test]
+    fn test_content_hash_same_across_formats() {
+        // Arrange
+        let transactions = YPFormatSupported::Csv
+            .to_transaction(&mut Cursor::new(CSV_DATA))
+            .unwrap();
+        let mut bin_buffer = Vec::new();
+        YPFormatSupported::Binary
+            .convert_transactions(&mut bin_buffer, &transactions)
+            .unwrap();
+
+        // Act
+        let hash_csv = content_hash(&mut Cursor::new(CSV_DATA), YPFormatSupported::Csv).unwrap();
+        let hash_bin =
+            content_hash(&mut Cursor::new(bin_buffer), YPFormatSupported::Binary).unwrap();
+
+        // Assert
+        assert_eq!(hash_csv, hash_bin);
+    }
+
+    #[test]
+    fn test_content_hash_detects_changes() {
+        // Arrange
+        let changed = CSV_DATA.replace("50000", "50001");
+
+        // Act
+        let original = content_hash(&mut Cursor::new(CSV_DATA), YPFormatSupported::Csv).unwrap();
+        let modified = content_hash(&mut Cursor::new(changed), YPFormatSupported::Csv).unwrap();
+
+        // Assert
+        assert_ne!(original, modified);
+    }
 }
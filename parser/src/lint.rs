@@ -0,0 +1,550 @@
+//! Диагностика качества данных.
+//!
+//! В отличие от ошибок парсинга ([`ParseError`]), выявленные здесь замечания не прерывают
+//! обработку: данные корректны с точки зрения формата, но выглядят подозрительно.
+
+use crate::YPFormatSupported;
+use crate::errors::ParseError;
+use crate::format::options::ReadOptions;
+use crate::models::{
+    TxType, YPBankBinFormat, YPBankCsvFormat, YPBankTextFormat, YPBankTransaction,
+};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::io::Read;
+
+/// Предупреждение о качестве данных для отдельной записи.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Warning {
+    /// Порядковый номер записи в источнике (с нуля).
+    pub index: usize,
+
+    /// Имя поля, к которому относится предупреждение (в верхнем регистре).
+    pub field: &'static str,
+
+    /// Описание замечания.
+    pub message: String,
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Запись {}, поле {}: {}",
+            self.index, self.field, self.message
+        )
+    }
+}
+
+/// Считывает данные в указанном формате и дополнительно собирает предупреждения о качестве
+/// данных.
+///
+/// Предупреждения не прерывают разбор. Сейчас отмечаются:
+/// * депозиты с ненулевым `FROM_USER_ID`;
+/// * списания с ненулевым `TO_USER_ID`;
+/// * присутствующее, но пустое описание.
+///
+/// ## Пример
+///
+/// ```no_run
+/// use std::fs::File;
+/// use parser::YPFormatSupported;
+/// use parser::lint::read_with_warnings;
+///
+/// let mut file = File::open("data.txt").unwrap();
+/// let (transactions, warnings) = read_with_warnings(&mut file, YPFormatSupported::Text).unwrap();
+///
+/// for warning in warnings {
+///     println!("{warning}");
+/// }
+/// ```
+///
+/// ## Returns
+///
+/// Кортеж из вектора транзакций и вектора предупреждений, либо [`ParseError`], если данные
+/// не удалось разобрать.
+pub fn read_with_warnings<R: Read>(
+    readers: &mut R,
+    format: YPFormatSupported,
+) -> Result<(Vec<YPBankTransaction>, Vec<Warning>), ParseError> {
+    let transactions = format.to_transaction(readers)?;
+    let warnings = transactions
+        .iter()
+        .enumerate()
+        .flat_map(|(index, tx)| collect_warnings(index, tx))
+        .collect();
+
+    Ok((transactions, warnings))
+}
+
+/// Позиция записи в источнике.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordPosition {
+    /// Номер строки (с единицы) для текстовых форматов: строка данных в CSV или заголовок блока
+    /// в `txt`.
+    Line(usize),
+
+    /// Порядковый номер записи (с нуля) для бинарного формата.
+    Record(usize),
+}
+
+impl Display for RecordPosition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecordPosition::Line(line) => write!(f, "строка {line}"),
+            RecordPosition::Record(index) => write!(f, "запись {index}"),
+        }
+    }
+}
+
+/// Отчёт о повторяющемся идентификаторе транзакции.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateReport {
+    /// Повторяющийся `TX_ID`.
+    pub tx_id: u64,
+
+    /// Все позиции, где встречается идентификатор, в порядке следования.
+    pub positions: Vec<RecordPosition>,
+}
+
+/// Находит повторяющиеся `TX_ID` в источнике и сообщает, где они встречаются.
+///
+/// Функция диагностическая: данные не изменяются и читаются потоком. Для `csv` и `txt` позиции
+/// указываются номерами строк (в `txt` — строкой заголовка записи, комментарии пропускаются),
+/// для бинарного формата — порядковыми номерами записей. Отчёты упорядочены по первому
+/// появлению идентификатора.
+///
+/// ## Пример
+///
+/// ```no_run
+/// use std::fs::File;
+/// use parser::YPFormatSupported;
+/// use parser::lint::duplicate_ids;
+///
+/// let mut file = File::open("data.csv").unwrap();
+/// for report in duplicate_ids(&mut file, YPFormatSupported::Csv).unwrap() {
+///     println!("TX_ID {} повторяется: {:?}", report.tx_id, report.positions);
+/// }
+/// ```
+pub fn duplicate_ids<R: Read>(
+    readers: &mut R,
+    format: YPFormatSupported,
+) -> Result<Vec<DuplicateReport>, ParseError> {
+    let mut order: Vec<u64> = Vec::new();
+    let mut seen: HashMap<u64, Vec<RecordPosition>> = HashMap::new();
+    for record in record_keys(readers, format) {
+        let (position, tx_id, _) = record?;
+        let entry = seen.entry(tx_id).or_default();
+        if entry.is_empty() {
+            order.push(tx_id);
+        }
+        entry.push(position);
+    }
+
+    Ok(order
+        .into_iter()
+        .filter_map(|tx_id| {
+            let positions = seen.remove(&tx_id)?;
+            (positions.len() > 1).then_some(DuplicateReport { tx_id, positions })
+        })
+        .collect())
+}
+
+/// Требование к порядку меток времени для [`check_monotonic_timestamps`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimestampOrder {
+    /// Метки времени не убывают: равные значения допустимы.
+    #[default]
+    NonDecreasing,
+    /// Метки времени строго возрастают.
+    Strict,
+}
+
+/// Проверяет, что записи в источнике упорядочены по `TIMESTAMP`.
+///
+/// Записи читаются потоком, проверка останавливается на первой записи, нарушающей порядок:
+/// возвращается ошибка [`ParseError::ParseError`]. В сообщении указываются порядковый номер
+/// записи (с нуля), её позиция в источнике и пара меток времени. Для `csv` и `txt` номер строки
+/// записи передаётся и в поле `line` ошибки.
+///
+/// ## Пример
+///
+/// ```no_run
+/// use std::fs::File;
+/// use parser::YPFormatSupported;
+/// use parser::lint::{TimestampOrder, check_monotonic_timestamps};
+///
+/// let mut file = File::open("data.bin").unwrap();
+/// check_monotonic_timestamps(&mut file, YPFormatSupported::Binary, TimestampOrder::Strict)
+///     .unwrap();
+/// ```
+pub fn check_monotonic_timestamps<R: Read>(
+    readers: &mut R,
+    format: YPFormatSupported,
+    order: TimestampOrder,
+) -> Result<(), ParseError> {
+    let mut previous: Option<u64> = None;
+    for (index, record) in record_keys(readers, format).enumerate() {
+        let (position, _, timestamp) = record?;
+        let violated = previous.is_some_and(|previous| match order {
+            TimestampOrder::NonDecreasing => timestamp < previous,
+            TimestampOrder::Strict => timestamp <= previous,
+        });
+
+        if let (true, Some(previous)) = (violated, previous) {
+            let line = match position {
+                RecordPosition::Line(line) => line,
+                RecordPosition::Record(_) => 0,
+            };
+            return Err(ParseError::parse_err(
+                format!(
+                    "Нарушен порядок TIMESTAMP в записи {index} ({position}): \
+                     {timestamp} после {previous}"
+                ),
+                line,
+                0,
+            ));
+        }
+        previous = Some(timestamp);
+    }
+
+    Ok(())
+}
+
+/// Потоковое чтение позиций записей источника вместе с их `TX_ID` и `TIMESTAMP`.
+fn record_keys<'a, R: Read>(
+    readers: &'a mut R,
+    format: YPFormatSupported,
+) -> Box<dyn Iterator<Item = Result<(RecordPosition, u64, u64), ParseError>> + 'a> {
+    match format {
+        YPFormatSupported::Binary => Box::new(YPBankBinFormat::read_iter(readers).enumerate().map(
+            |(index, record)| {
+                record.map(|record| {
+                    (
+                        RecordPosition::Record(index),
+                        record.tx_id,
+                        record.timestamp,
+                    )
+                })
+            },
+        )),
+        YPFormatSupported::Csv => Box::new(
+            YPBankCsvFormat::read_iter_positioned(readers, ReadOptions::default()).map(|record| {
+                record.map(|(line, record)| {
+                    (RecordPosition::Line(line), record.tx_id, record.timestamp)
+                })
+            }),
+        ),
+        YPFormatSupported::Text => Box::new(
+            YPBankTextFormat::read_iter_positioned(readers, ReadOptions::default()).map(|record| {
+                record.map(|(line, record)| {
+                    (RecordPosition::Line(line), record.tx_id, record.timestamp)
+                })
+            }),
+        ),
+    }
+}
+
+/// Собирает предупреждения для одной транзакции.
+fn collect_warnings(index: usize, tx: &YPBankTransaction) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+
+    if tx.tx_type == TxType::Deposit && tx.from_user_id != 0 {
+        warnings.push(Warning {
+            index,
+            field: "FROM_USER_ID",
+            message: format!(
+                "у депозита указан отправитель {}, ожидается 0",
+                tx.from_user_id
+            ),
+        });
+    }
+
+    if tx.tx_type == TxType::Withdrawal && tx.to_user_id != 0 {
+        warnings.push(Warning {
+            index,
+            field: "TO_USER_ID",
+            message: format!(
+                "у списания указан получатель {}, ожидается 0",
+                tx.to_user_id
+            ),
+        });
+    }
+
+    if tx.description.as_deref() == Some("") {
+        warnings.push(Warning {
+            index,
+            field: "DESCRIPTION",
+            message: "описание присутствует, но пустое".to_string(),
+        });
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TxStatus;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_deposit_with_stray_from_user_id() {
+        // Arrange
+        let input = "# Record 1 (DEPOSIT)\n\
+                     TX_TYPE: DEPOSIT\n\
+                     FROM_USER_ID: 1001\n\
+                     TO_USER_ID: 1003\n\
+                     TIMESTAMP: 1633046401\n\
+                     DESCRIPTION: \"Terminal deposit\"\n\
+                     TX_ID: 9876543210000000\n\
+                     AMOUNT: 100000\n\
+                     STATUS: SUCCESS\n";
+
+        // Act
+        let (transactions, warnings) =
+            read_with_warnings(&mut Cursor::new(input), YPFormatSupported::Text).unwrap();
+
+        // Assert
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0].from_user_id, 1001);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].index, 0);
+        assert_eq!(warnings[0].field, "FROM_USER_ID");
+    }
+
+    #[test]
+    fn test_clean_data_has_no_warnings() {
+        // Arrange
+        let input = "TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION\n\
+                     123456789,TRANSFER,1001,1002,50000,1633046400,SUCCESS,\"Test transaction\"\n";
+
+        // Act
+        let (transactions, warnings) =
+            read_with_warnings(&mut Cursor::new(input), YPFormatSupported::Csv).unwrap();
+
+        // Assert
+        assert_eq!(transactions.len(), 1);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_empty_description_warning() {
+        // Arrange
+        let input = "TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION\n\
+                     123456789,WITHDRAWAL,1001,0,50000,1633046400,SUCCESS,\"\"\n";
+
+        // Act
+        let (_, warnings) =
+            read_with_warnings(&mut Cursor::new(input), YPFormatSupported::Csv).unwrap();
+
+        // Assert
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].field, "DESCRIPTION");
+    }
+
+    #[test]
+    fn test_duplicate_ids_csv_lines() {
+        // Arrange
+        let input = "TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION\n\
+                     1,TRANSFER,1001,1002,500,1633046400,SUCCESS,\"First\"\n\
+                     2,TRANSFER,1001,1002,600,1633046401,SUCCESS,\"Second\"\n\
+                     1,TRANSFER,1001,1002,500,1633046402,SUCCESS,\"Repeat\"\n";
+
+        // Act
+        let reports = duplicate_ids(&mut Cursor::new(input), YPFormatSupported::Csv).unwrap();
+
+        // Assert
+        assert_eq!(
+            reports,
+            vec![DuplicateReport {
+                tx_id: 1,
+                positions: vec![RecordPosition::Line(2), RecordPosition::Line(4)],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_ids_csv_multiline_record() {
+        // Arrange
+        let input = "TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION\n\
+                     1,TRANSFER,1001,1002,500,1633046400,SUCCESS,\"First\n\
+                     line\"\n\
+                     1,TRANSFER,1001,1002,500,1633046402,SUCCESS,\"Repeat\"\n";
+
+        // Act
+        let reports = duplicate_ids(&mut Cursor::new(input), YPFormatSupported::Csv).unwrap();
+
+        // Assert
+        assert_eq!(
+            reports[0].positions,
+            vec![RecordPosition::Line(2), RecordPosition::Line(4)]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_ids_text_and_binary() {
+        // Arrange
+        let block = |tx_id: u64| {
+            format!(
+                "# Record {tx_id} (DEPOSIT)\n\
+                 TX_TYPE: DEPOSIT\n\
+                 FROM_USER_ID: 0\n\
+                 TO_USER_ID: 1003\n\
+                 TIMESTAMP: 1633046401\n\
+                 DESCRIPTION: \"Deposit\"\n\
+                 TX_ID: {tx_id}\n\
+                 AMOUNT: 100\n\
+                 STATUS: SUCCESS\n\n"
+            )
+        };
+        let text = format!("{}{}{}", block(7), block(7), block(8));
+        let transactions = YPFormatSupported::Text
+            .to_transaction(&mut text.as_bytes())
+            .unwrap();
+        let mut binary = Vec::new();
+        YPFormatSupported::Binary
+            .convert_transactions(&mut binary, &transactions)
+            .unwrap();
+
+        // Act
+        let text_reports = duplicate_ids(&mut Cursor::new(text), YPFormatSupported::Text).unwrap();
+        let bin_reports =
+            duplicate_ids(&mut Cursor::new(binary), YPFormatSupported::Binary).unwrap();
+
+        // Assert
+        assert_eq!(text_reports.len(), 1);
+        assert_eq!(
+            text_reports[0].positions,
+            vec![RecordPosition::Line(1), RecordPosition::Line(11)]
+        );
+        assert_eq!(
+            bin_reports[0].positions,
+            vec![RecordPosition::Record(0), RecordPosition::Record(1)]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_ids_text_leading_comment() {
+        // Arrange
+        let block = |tx_id: u64| {
+            format!(
+                "# Record {tx_id} (DEPOSIT)\n\
+                 TX_TYPE: DEPOSIT\n\
+                 FROM_USER_ID: 0\n\
+                 TO_USER_ID: 1003\n\
+                 TIMESTAMP: 1633046401\n\
+                 DESCRIPTION: \"Deposit\"\n\
+                 TX_ID: {tx_id}\n\
+                 AMOUNT: 100\n\
+                 STATUS: SUCCESS\n\n"
+            )
+        };
+        let text = format!("# exported by X\n{}{}", block(7), block(7));
+
+        // Act
+        let reports = duplicate_ids(&mut Cursor::new(text), YPFormatSupported::Text).unwrap();
+
+        // Assert
+        assert_eq!(
+            reports[0].positions,
+            vec![RecordPosition::Line(2), RecordPosition::Line(12)]
+        );
+    }
+
+    #[test]
+    fn test_check_monotonic_timestamps() {
+        // Arrange
+        let input = |timestamps: [u64; 3]| {
+            let rows: String = timestamps
+                .iter()
+                .enumerate()
+                .map(|(i, ts)| format!("{i},DEPOSIT,0,1001,100,{ts},SUCCESS,\"\"\n"))
+                .collect();
+            format!(
+                "TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION\n{rows}"
+            )
+        };
+        let check = |data: String, order| {
+            check_monotonic_timestamps(&mut Cursor::new(data), YPFormatSupported::Csv, order)
+        };
+
+        // Act & Assert
+        assert!(check(input([10, 20, 30]), TimestampOrder::Strict).is_ok());
+        assert!(check(input([10, 10, 30]), TimestampOrder::NonDecreasing).is_ok());
+
+        let equal = check(input([10, 10, 30]), TimestampOrder::Strict).unwrap_err();
+        assert!(
+            equal
+                .to_string()
+                .contains("записи 1 (строка 3): 10 после 10")
+        );
+
+        let out_of_order = check(input([10, 30, 20]), TimestampOrder::NonDecreasing).unwrap_err();
+        assert!(
+            out_of_order
+                .to_string()
+                .contains("записи 2 (строка 4): 20 после 30")
+        );
+        assert!(matches!(
+            out_of_order,
+            ParseError::ParseError { line: 4, .. }
+        ));
+    }
+
+    #[test]
+    fn test_check_monotonic_timestamps_stops_at_first_violation() {
+        // Arrange: после нарушения порядка идут данные, которые не разбираются
+        let csv = "TX_ID,TX_TYPE,FROM_USER_ID,TO_USER_ID,AMOUNT,TIMESTAMP,STATUS,DESCRIPTION\n\
+                   1,DEPOSIT,0,1001,100,20,SUCCESS,\"\"\n\
+                   2,DEPOSIT,0,1001,100,10,SUCCESS,\"\"\n\
+                   not a record\n";
+        let first = YPBankBinFormat {
+            tx_id: 1,
+            tx_type: TxType::Deposit,
+            from_user_id: 0,
+            to_user_id: 1001,
+            amount: 100,
+            timestamp: 20,
+            status: TxStatus::Success,
+            desc_len: 0,
+            description: None,
+            raw_description: None,
+        };
+        let records = vec![
+            first.clone(),
+            YPBankBinFormat {
+                tx_id: 2,
+                timestamp: 10,
+                ..first
+            },
+        ];
+        let mut bin = Vec::new();
+        YPBankBinFormat::write_to(&mut bin, &records).unwrap();
+        bin.extend(b"garbage");
+
+        // Act
+        let csv_result = check_monotonic_timestamps(
+            &mut Cursor::new(csv),
+            YPFormatSupported::Csv,
+            TimestampOrder::NonDecreasing,
+        );
+        let bin_result = check_monotonic_timestamps(
+            &mut Cursor::new(bin),
+            YPFormatSupported::Binary,
+            TimestampOrder::NonDecreasing,
+        );
+
+        // Assert
+        assert!(matches!(
+            csv_result,
+            Err(ParseError::ParseError { line: 3, .. })
+        ));
+        assert!(
+            bin_result
+                .unwrap_err()
+                .to_string()
+                .contains("записи 1 (запись 1)")
+        );
+    }
+}
@@ -1,7 +1,10 @@
 //! Общие модели представления данных для чтения/записи, парсинга.
 
 use crate::errors::ParseError;
+use crate::traits::TxKind;
+use crate::utils::{signed_to_unsigned, try_get_timestamp, unsigned_to_signed};
 use parser_macros::{TxDisplay, YPBankFields};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 
@@ -15,7 +18,7 @@ use std::fmt::{Display, Formatter};
 ///
 /// Кроме того, в бинарном формате это поле со знаком (отрицательное для списаний), а в csv
 /// и txt беззнаковое. В универсальной структуре используется знаковое поле, соответственно,
-/// исходя из типа операции преобразуется и знак.
+/// исходя из типа операции преобразуется и знак (см. [`unsigned_to_signed`]).
 ///
 /// ## Примеры
 ///
@@ -45,14 +48,7 @@ macro_rules! impl_try_from_yp_format_to_transaction {
             type Error = ParseError;
 
             fn try_from(source: $source_type) -> Result<Self, ParseError> {
-                let mut amount: i64 = source
-                    .amount
-                    .try_into()
-                    .map_err(|_| ParseError::over_flow_size("u64", "i64", source.amount))?;
-
-                if matches!(source.tx_type, TxType::Transfer | TxType::Withdrawal) && amount > 0 {
-                    amount = -amount;
-                }
+                let amount = unsigned_to_signed(source.amount, &source.tx_type)?;
 
                 Ok(YPBankTransaction {
                     tx_id: source.tx_id,
@@ -77,7 +73,8 @@ macro_rules! impl_try_from_yp_format_to_transaction {
 ///
 /// Знаковое поле `amount` применяется только в бинарном формате, а в csv и txt беззнаковый `u64`.
 /// Для обеспечения единообразия данных, универсальная структура применяет знаковое поле, аналогично
-/// формату `bin`. При преобразовании значение поля приводится к типу целевой структуры.
+/// формату `bin`. При преобразовании значение поля приводится к типу целевой структуры
+/// (см. [`signed_to_unsigned`]).
 ///
 /// Возможно для макроса ложное предупреждение `PyCharm`.
 macro_rules! impl_try_from_transaction_to_yp_format {
@@ -91,7 +88,7 @@ macro_rules! impl_try_from_transaction_to_yp_format {
                     None => "".to_string(),
                 };
 
-                let amount: u64 = value.amount.unsigned_abs();
+                let amount = signed_to_unsigned(value.amount)?;
 
                 Ok($dest_type {
                     tx_id: value.tx_id,
@@ -113,19 +110,16 @@ macro_rules! impl_try_from_transaction_to_yp_format {
 macro_rules! get_field_in_map {
     ($map:expr, $key:expr, $ty:ty) => {
         $map.get($key)
-            .ok_or(ParseError::IncorrectField {
-                key: $key.to_string(),
-            })?
+            .ok_or_else(|| ParseError::incorrect_field($key))?
             .parse::<$ty>()
-            .map_err(|_| ParseError::IncorrectField {
-                key: $key.to_string(),
-            })?
+            .map_err(|_| ParseError::incorrect_field($key))?
     };
 }
 
 /// Перечисление возможных типов транзакций.
 #[repr(u8)]
-#[derive(Debug, TxDisplay, Clone, PartialEq)]
+#[derive(Debug, TxDisplay, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TxType {
     /// Пополнение счёта (внесение на депозит).
     Deposit = 0,
@@ -137,7 +131,8 @@ pub enum TxType {
 
 /// Перечисление возможных типов финансовых операций.
 #[repr(u8)]
-#[derive(Debug, TxDisplay, Clone, PartialEq)]
+#[derive(Debug, TxDisplay, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TxStatus {
     /// Успешная транзакция.
     Success = 0,
@@ -147,9 +142,23 @@ pub enum TxStatus {
     Pending = 2,
 }
 
+/// Политика обработки транзакций с нулевой суммой при валидации
+/// ([`YPBankTransaction::validate_with`]).
+///
+/// Парсеры всех форматов принимают нулевую сумму, и она встречается в легитимных записях
+/// (например, при обновлении только статуса операции), поэтому по умолчанию она допустима.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ZeroAmountPolicy {
+    /// Транзакция с нулевой суммой считается ошибочной.
+    Reject,
+    /// Нулевая сумма допустима.
+    #[default]
+    Allow,
+}
+
 /// Универсальная структура представления данных для записи/чтения, позволяющая парсить
 /// исходные сведения, а также при извлечении их из хранения.
-#[derive(Debug, Clone, PartialEq, YPBankFields)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, YPBankFields, Serialize, Deserialize)]
 pub struct YPBankTransaction {
     /// ID операции.
     pub tx_id: u64,
@@ -177,9 +186,321 @@ pub struct YPBankTransaction {
     pub description: Option<String>,
 }
 
+impl YPBankTransaction {
+    /// Начинает сборку транзакции, см. [`YPBankTransactionBuilder`].
+    pub fn builder(tx_id: u64, tx_type: TxType) -> YPBankTransactionBuilder {
+        YPBankTransactionBuilder::new(tx_id, tx_type)
+    }
+
+    /// Проверка бизнес-правил транзакции с политиками по умолчанию.
+    ///
+    /// Подробнее в [`YPBankTransaction::validate_with`].
+    pub fn validate(&self) -> Result<(), ParseError> {
+        self.validate_with(ZeroAmountPolicy::default())
+    }
+
+    /// Проверка бизнес-правил транзакции.
+    ///
+    /// Правила соответствуют обнулению участников при записи в бинарный формат:
+    ///
+    /// * у пополнения нет отправителя (`from_user_id == 0`), сумма неотрицательна;
+    /// * у списания нет получателя (`to_user_id == 0`), сумма неположительна;
+    /// * у перевода оба участника ненулевые и различны, сумма неположительна;
+    /// * нулевая сумма допустима или нет в зависимости от политики `zero_amount`.
+    ///
+    /// ## Args
+    ///
+    /// * `zero_amount` — политика обработки нулевой суммы, см. [`ZeroAmountPolicy`].
+    ///
+    /// ## Returns
+    ///
+    /// Пустой `Result`, если нарушений нет, либо [`ParseError::ParseError`] с описанием
+    /// первого обнаруженного нарушения.
+    pub fn validate_with(&self, zero_amount: ZeroAmountPolicy) -> Result<(), ParseError> {
+        if self.amount == 0 && zero_amount == ZeroAmountPolicy::Reject {
+            return Err(ParseError::parse_err(
+                format!("Нулевая сумма операции (TX_ID {})", self.tx_id),
+                0,
+                0,
+            ));
+        }
+
+        let violation = match self.tx_type {
+            TxType::Deposit if self.from_user_id != 0 => Some("у пополнения указан отправитель"),
+            TxType::Deposit if self.amount < 0 => Some("отрицательная сумма пополнения"),
+            TxType::Withdrawal if self.to_user_id != 0 => Some("у списания указан получатель"),
+            TxType::Withdrawal if self.amount > 0 => Some("положительная сумма списания"),
+            TxType::Transfer if self.from_user_id == 0 || self.to_user_id == 0 => {
+                Some("у перевода не указан отправитель или получатель")
+            }
+            TxType::Transfer if self.from_user_id == self.to_user_id => {
+                Some("отправитель и получатель перевода совпадают")
+            }
+            TxType::Transfer if self.amount > 0 => Some("положительная сумма перевода"),
+            _ => None,
+        };
+
+        match violation {
+            Some(violation) => Err(ParseError::parse_err(
+                format!(
+                    "Нарушено правило операции (TX_ID {}): {violation}",
+                    self.tx_id
+                ),
+                0,
+                0,
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Строгое преобразование в структуру формата (например, [`YPBankCsvFormat`] или
+    /// [`YPBankTextFormat`]).
+    ///
+    /// Обычное преобразование (`try_into`) записывает модуль суммы, поэтому положительная сумма
+    /// перевода или списания неотличима от корректной. Здесь знак суммы сначала сверяется
+    /// с типом операции: для перевода и списания сумма неположительна, для пополнения —
+    /// неотрицательна.
+    ///
+    /// ## Returns
+    ///
+    /// Структура формата либо [`ParseError::ParseError`], если знак суммы не соответствует
+    /// типу операции.
+    pub fn try_into_strict<T>(self) -> Result<T, ParseError>
+    where
+        T: TryFrom<YPBankTransaction, Error = ParseError>,
+    {
+        let consistent = match self.tx_type {
+            TxType::Deposit => self.amount >= 0,
+            TxType::Transfer | TxType::Withdrawal => self.amount <= 0,
+        };
+        if !consistent {
+            return Err(ParseError::parse_err(
+                format!(
+                    "Знак суммы {} не соответствует типу операции {} (TX_ID {})",
+                    self.amount, self.tx_type, self.tx_id
+                ),
+                0,
+                0,
+            ));
+        }
+
+        T::try_from(self)
+    }
+
+    /// Проверяет, затрагивает ли транзакция указанного пользователя.
+    ///
+    /// Учитывается тип операции: пополнение зачисляет средства только `to_user_id`, списание
+    /// затрагивает только `from_user_id`, перевод — обоих участников. Служебный идентификатор `0`
+    /// никогда не считается участником операции.
+    ///
+    /// ## Args
+    ///
+    /// * `user_id` — идентификатор пользователя.
+    pub fn involves_user(&self, user_id: u64) -> bool {
+        if user_id == 0 {
+            return false;
+        }
+
+        match self.tx_type {
+            TxType::Deposit => self.to_user_id == user_id,
+            TxType::Withdrawal => self.from_user_id == user_id,
+            TxType::Transfer => self.from_user_id == user_id || self.to_user_id == user_id,
+        }
+    }
+
+    /// Объединяет две записи об одной и той же операции из разных источников.
+    ///
+    /// Записи должны совпадать по `tx_id` и всем обязательным полям. Отсутствующее или пустое
+    /// описание дополняется описанием другой записи; два разных непустых описания считаются
+    /// конфликтом.
+    ///
+    /// ## Args
+    ///
+    /// * `other` — запись о той же операции из другого источника.
+    ///
+    /// ## Returns
+    ///
+    /// Объединённая запись либо [`ParseError::ParseError`] с именем первого конфликтующего поля.
+    pub fn merge(&self, other: &Self) -> Result<Self, ParseError> {
+        let conflicts = [
+            ("TX_ID", self.tx_id != other.tx_id),
+            ("TX_TYPE", self.tx_type != other.tx_type),
+            ("FROM_USER_ID", self.from_user_id != other.from_user_id),
+            ("TO_USER_ID", self.to_user_id != other.to_user_id),
+            ("AMOUNT", self.amount != other.amount),
+            ("TIMESTAMP", self.timestamp != other.timestamp),
+            ("STATUS", self.status != other.status),
+        ];
+        if let Some((field, _)) = conflicts.iter().find(|(_, conflict)| *conflict) {
+            return Err(ParseError::parse_err(
+                format!("Конфликт поля {field} при объединении TX_ID {}", self.tx_id),
+                0,
+                0,
+            ));
+        }
+
+        let own = self.description.as_deref().filter(|d| !d.is_empty());
+        let others = other.description.as_deref().filter(|d| !d.is_empty());
+        let description = match (own, others) {
+            (Some(own), Some(others)) if own != others => {
+                return Err(ParseError::parse_err(
+                    format!(
+                        "Конфликт поля DESCRIPTION при объединении TX_ID {}",
+                        self.tx_id
+                    ),
+                    0,
+                    0,
+                ));
+            }
+            (Some(description), _) | (None, Some(description)) => Some(description.to_string()),
+            (None, None) => self
+                .description
+                .clone()
+                .or_else(|| other.description.clone()),
+        };
+
+        Ok(Self {
+            description,
+            ..self.clone()
+        })
+    }
+
+    /// Каноническое байтовое представление транзакции, не зависящее от исходного формата.
+    ///
+    /// Все поля кодируются в big-endian, перечисления — своими числовыми значениями. Отсутствующее
+    /// и пустое описание считаются равнозначными, так как форматы `csv` и `txt` не различают их,
+    /// а бинарный формат хранит пустое описание как отсутствующее.
+    pub(crate) fn canonical_bytes(&self) -> Vec<u8> {
+        let description = self.description.as_deref().unwrap_or_default().as_bytes();
+
+        let mut bytes = Vec::with_capacity(50 + description.len());
+        bytes.extend(self.tx_id.to_be_bytes());
+        bytes.push(self.tx_type.clone().as_u8());
+        bytes.extend(self.from_user_id.to_be_bytes());
+        bytes.extend(self.to_user_id.to_be_bytes());
+        bytes.extend(self.amount.to_be_bytes());
+        bytes.extend(self.timestamp.to_be_bytes());
+        bytes.push(self.status.clone().as_u8());
+        bytes.extend((description.len() as u64).to_be_bytes());
+        bytes.extend(description);
+
+        bytes
+    }
+}
+
+/// Пошаговая сборка [`YPBankTransaction`] без перечисления всех полей.
+///
+/// Значения по умолчанию: участники `0`, сумма `0`, время — текущее (в момент
+/// [`YPBankTransactionBuilder::build`]), статус [`TxStatus::Pending`], описание отсутствует.
+///
+/// ## Пример
+///
+/// ```
+/// use parser::models::{TxStatus, TxType, YPBankTransaction};
+///
+/// let tx = YPBankTransaction::builder(1, TxType::Transfer)
+///     .from_user_id(1001)
+///     .to_user_id(1002)
+///     .amount(-500)
+///     .status(TxStatus::Success)
+///     .description("Rent")
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(tx.description.as_deref(), Some("Rent"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct YPBankTransactionBuilder {
+    tx_id: u64,
+    tx_type: TxType,
+    from_user_id: u64,
+    to_user_id: u64,
+    amount: i64,
+    timestamp: Option<u64>,
+    status: TxStatus,
+    description: Option<String>,
+}
+
+impl YPBankTransactionBuilder {
+    /// Создаёт сборщик транзакции с обязательными `tx_id` и `tx_type`.
+    pub fn new(tx_id: u64, tx_type: TxType) -> Self {
+        Self {
+            tx_id,
+            tx_type,
+            from_user_id: 0,
+            to_user_id: 0,
+            amount: 0,
+            timestamp: None,
+            status: TxStatus::Pending,
+            description: None,
+        }
+    }
+
+    /// ID отправителя средств.
+    pub fn from_user_id(mut self, from_user_id: u64) -> Self {
+        self.from_user_id = from_user_id;
+        self
+    }
+
+    /// ID получателя средств.
+    pub fn to_user_id(mut self, to_user_id: u64) -> Self {
+        self.to_user_id = to_user_id;
+        self
+    }
+
+    /// Знаковая сумма операции, см. [`YPBankTransaction::amount`].
+    pub fn amount(mut self, amount: i64) -> Self {
+        self.amount = amount;
+        self
+    }
+
+    /// Время операции (в секундах от начала эпохи UNIX).
+    pub fn timestamp(mut self, timestamp: u64) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    /// Статус операции.
+    pub fn status(mut self, status: TxStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Описание операции.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Собирает транзакцию и проверяет её бизнес-правила ([`YPBankTransaction::validate`]).
+    ///
+    /// Если время не задано, берётся текущее; при ошибке системных часов возвращается ошибка
+    /// [`try_get_timestamp`].
+    pub fn build(self) -> Result<YPBankTransaction, ParseError> {
+        let timestamp = match self.timestamp {
+            Some(timestamp) => timestamp,
+            None => try_get_timestamp()?,
+        };
+
+        let transaction = YPBankTransaction {
+            tx_id: self.tx_id,
+            tx_type: self.tx_type,
+            from_user_id: self.from_user_id,
+            to_user_id: self.to_user_id,
+            amount: self.amount,
+            timestamp,
+            status: self.status,
+            description: self.description,
+        };
+        transaction.validate()?;
+
+        Ok(transaction)
+    }
+}
+
 impl_try_from_yp_format_to_transaction!(YPBankCsvFormat);
 impl_try_from_yp_format_to_transaction!(YPBankTextFormat);
-impl_try_from_yp_format_to_transaction!(YPBankBinFormat);
+impl_try_from_yp_format_to_transaction!(YPBankJsonFormat);
 
 /// Текстовый файл с разделителями-запятыми (`CSV`), предназначенный для хранения
 /// данных о транзакциях. Файл имеет строгую структуру: обязательная строка заголовка
@@ -257,6 +578,76 @@ impl YPBankCsvFormat {
     }
 }
 
+/// Формат `JSON`: массив объектов, по одному на транзакцию. Ключи объекта — имена полей
+/// в нижнем регистре, перечисления записываются строками в верхнем регистре, как в `csv` и `txt`.
+///
+/// ## Пример
+///
+/// ```json
+/// [
+///   {
+///     "tx_id": 1001,
+///     "tx_type": "DEPOSIT",
+///     "from_user_id": 0,
+///     "to_user_id": 501,
+///     "amount": 50000,
+///     "timestamp": 1672531200000,
+///     "status": "SUCCESS",
+///     "description": "Initial account funding"
+///   }
+/// ]
+/// ```
+#[derive(Debug, YPBankFields, PartialEq, Clone, Serialize)]
+pub struct YPBankJsonFormat {
+    /// ID операции.
+    pub tx_id: u64,
+
+    /// Тип операции, определяется из вариантов, предусмотренных [`TxType`].
+    #[serde(serialize_with = "crate::format::json::serialize_display")]
+    pub tx_type: TxType,
+
+    /// ID отправителя средств.
+    pub from_user_id: u64,
+
+    /// ID получателя средств.
+    pub to_user_id: u64,
+
+    /// Сумма пополнения. Беззнаковая. Операция определяется по полю `tx_type`.
+    pub amount: u64,
+
+    /// Время операции (в секундах от начала эпохи UNIX).
+    pub timestamp: u64,
+
+    /// Статус операции. Значения предусмотрены перечислением [`TxStatus`].
+    #[serde(serialize_with = "crate::format::json::serialize_display")]
+    pub status: TxStatus,
+
+    /// Описание операции.
+    pub description: String,
+}
+
+impl_try_from_transaction_to_yp_format!(YPBankJsonFormat);
+
+impl YPBankJsonFormat {
+    /// Создаёт экземпляр структуры на основе данных из `HashMap`.
+    ///
+    /// Структура `fields`:
+    /// * ключ — имя поля в верхнем регистре
+    /// * значение — значение поля
+    pub fn new_from_map(fields: &HashMap<String, String>) -> Result<Self, ParseError> {
+        Ok(Self {
+            tx_id: get_field_in_map!(fields, "TX_ID", u64),
+            tx_type: get_field_in_map!(fields, "TX_TYPE", TxType),
+            from_user_id: get_field_in_map!(fields, "FROM_USER_ID", u64),
+            to_user_id: get_field_in_map!(fields, "TO_USER_ID", u64),
+            amount: get_field_in_map!(fields, "AMOUNT", u64),
+            timestamp: get_field_in_map!(fields, "TIMESTAMP", u64),
+            status: get_field_in_map!(fields, "STATUS", TxStatus),
+            description: get_field_in_map!(fields, "DESCRIPTION", String),
+        })
+    }
+}
+
 /// Бинарный формат YPBankBin — это компактное, бинарное представление тех же данных
 /// о транзакциях, которые описаны в текстовом формате `YPBankText`.
 ///
@@ -268,11 +659,14 @@ impl YPBankCsvFormat {
 /// ## Структура файла:
 ///
 /// ```plain
-/// [ЗАГОЛОВОК][ТЕЛО][ЗАГОЛОВОК][ТЕЛО]...
+/// [ЗАГОЛОВОК ФАЙЛА][ЗАГОЛОВОК][ТЕЛО][ЗАГОЛОВОК][ТЕЛО]...
 /// ```
 ///
+/// Заголовок файла (версия формата и число записей) необязателен, см. [`crate::format::bin`].
+///
 /// Наличие значения `MAGIC` в начале каждой записи позволяет читателю повторно
-/// синхронизироваться в случае потери границы записи или повреждения данных.
+/// синхронизироваться в случае потери границы записи или повреждения данных, см.
+/// [`YPBankBinFormat::read_from_lossy`].
 #[derive(Debug, YPBankFields, PartialEq, Clone)]
 pub struct YPBankBinFormat {
     /// ID операции.
@@ -297,18 +691,67 @@ pub struct YPBankBinFormat {
     /// Статус операции. Значения предусмотрены перечислением [`TxStatus`].
     pub status: TxStatus,
 
-    /// Длина следующего описания `description` в кодировке UTF-8.
+    /// Длина описания в байтах: `description` в кодировке UTF-8 либо `raw_description`.
+    /// Служебное поле: не входит в [`YPBankBinFormat::fields`]. При записи должно совпадать
+    /// с длиной описания, иначе запись отклоняется.
+    #[yp(skip)]
     pub desc_len: u32,
 
     /// Необязательное текстовое описание. Если описание отсутствует,
-    /// `DESC_LEN` равен `0`.
+    /// `DESC_LEN` равен `0`. Пустая строка на диске не отличается от отсутствующего описания
+    /// и читается как `None`.
     pub description: Option<String>,
+
+    /// Описание из произвольных байтов, не обязательно UTF-8. Служебное поле: не входит
+    /// в [`YPBankBinFormat::fields`]. На диске хранится в base64 с флагом в `DESC_LEN`, см.
+    /// [`crate::format::bin`]. Задаётся вместо `description`: заданные оба описания при
+    /// записи отклоняются.
+    #[yp(skip)]
+    pub raw_description: Option<Vec<u8>>,
+}
+
+impl TryFrom<YPBankBinFormat> for YPBankTransaction {
+    type Error = ParseError;
+
+    /// Сумма в бинарном формате уже знаковая. Положительные суммы переводов и списаний
+    /// приводятся к отрицательным, как и при чтении из `csv` и `txt`.
+    ///
+    /// Описание из байтов `raw_description` переносится, только если оно является строкой
+    /// UTF-8, иначе возвращается [`ParseError::ParseBinaryError`].
+    fn try_from(source: YPBankBinFormat) -> Result<Self, ParseError> {
+        let description = match source.raw_description {
+            Some(bytes) => Some(String::from_utf8(bytes).map_err(|_| {
+                ParseError::parse_bin_error(format!(
+                    "Описание записи TX_ID={} не является строкой UTF-8",
+                    source.tx_id
+                ))
+            })?),
+            None => source.description,
+        };
+        let amount = match source.tx_type {
+            TxType::Transfer | TxType::Withdrawal if source.amount > 0 => -source.amount,
+            _ => source.amount,
+        };
+
+        Ok(YPBankTransaction {
+            tx_id: source.tx_id,
+            tx_type: source.tx_type,
+            from_user_id: source.from_user_id,
+            to_user_id: source.to_user_id,
+            amount,
+            timestamp: source.timestamp,
+            status: source.status,
+            description,
+        })
+    }
 }
 
 impl TryFrom<YPBankTransaction> for YPBankBinFormat {
     type Error = ParseError;
     fn try_from(value: YPBankTransaction) -> Result<Self, Self::Error> {
-        let desc_len = match &value.description {
+        // Пустое описание на диске неотличимо от отсутствующего, см. `format::bin`.
+        let description = value.description.filter(|d| !d.is_empty());
+        let desc_len = match &description {
             Some(d) => { u32::try_from(d.len()) }
                 .map_err(|_| ParseError::over_flow_size("usize", "u32", d))?,
             None => 0,
@@ -323,7 +766,8 @@ impl TryFrom<YPBankTransaction> for YPBankBinFormat {
             timestamp: value.timestamp,
             status: value.status,
             desc_len,
-            description: value.description,
+            description,
+            raw_description: None,
         })
     }
 }
@@ -338,7 +782,8 @@ impl TryFrom<YPBankTransaction> for YPBankBinFormat {
 /// - Каждое поле встречается ровно один раз.
 /// - Записи о транзакциях разделяются пустыми строками.
 /// - Файл может содержать однострочные комментарии, которые начинаются с "#";
-///   эти строки игнорируются при парсинге.
+///   эти строки игнорируются при парсинге, в том числе между полями записи. Заголовком
+///   записи считается только строка вида `# Record N (TYPE)`.
 ///
 /// ## Пример содержимого файла:
 /// ```plain
@@ -411,6 +856,55 @@ impl YPBankTextFormat {
     }
 }
 
+/// Запись формата `txt` с пользовательскими перечислениями типа и статуса операции.
+///
+/// Повторяет [`YPBankTextFormat`], но `tx_type` и `status` могут быть любыми перечислениями,
+/// реализующими [`TxKind`] (например, с банковскими типами операций). По умолчанию используются
+/// [`TxType`] и [`TxStatus`]. Чтение — [`YPBankTextRecord::read_from`].
+#[derive(Debug, PartialEq, Clone)]
+pub struct YPBankTextRecord<K: TxKind = TxType, S: TxKind = TxStatus> {
+    /// ID операции.
+    pub tx_id: u64,
+
+    /// Тип операции.
+    pub tx_type: K,
+
+    /// ID отправителя средств.
+    pub from_user_id: u64,
+
+    /// ID получателя средств.
+    pub to_user_id: u64,
+
+    /// Сумма операции.
+    pub amount: u64,
+
+    /// Время операции (в секундах от начала эпохи UNIX).
+    pub timestamp: u64,
+
+    /// Статус операции.
+    pub status: S,
+
+    /// Текстовое описание операции.
+    pub description: String,
+}
+
+impl<K: TxKind, S: TxKind> YPBankTextRecord<K, S> {
+    /// Создаёт экземпляр структуры на основе данных из `HashMap`, как
+    /// [`YPBankTextFormat::new_from_map`].
+    pub fn new_from_map(fields_map: &HashMap<String, String>) -> Result<Self, ParseError> {
+        Ok(Self {
+            tx_id: get_field_in_map!(fields_map, "TX_ID", u64),
+            tx_type: get_field_in_map!(fields_map, "TX_TYPE", K),
+            from_user_id: get_field_in_map!(fields_map, "FROM_USER_ID", u64),
+            to_user_id: get_field_in_map!(fields_map, "TO_USER_ID", u64),
+            amount: get_field_in_map!(fields_map, "AMOUNT", u64),
+            timestamp: get_field_in_map!(fields_map, "TIMESTAMP", u64),
+            status: get_field_in_map!(fields_map, "STATUS", S),
+            description: get_field_in_map!(fields_map, "DESCRIPTION", String),
+        })
+    }
+}
+
 #[cfg(test)]
 mod conversion_tests {
     use super::*;
@@ -495,6 +989,7 @@ mod conversion_tests {
             status: TxStatus::Pending,
             desc_len: 0,
             description: None,
+            raw_description: None,
         };
 
         // Act: преобразуем бинарную запись в универсальную транзакцию
@@ -526,6 +1021,26 @@ mod conversion_tests {
         assert_eq!(csv_record.description, "Test transaction".to_string());
     }
 
+    #[test]
+    fn test_try_into_strict_rejects_wrong_sign() {
+        // Arrange
+        let transaction = create_test_transaction();
+        let wrong_sign = YPBankTransaction {
+            amount: 50000,
+            ..create_test_transaction()
+        };
+
+        // Act
+        let strict: YPBankCsvFormat = transaction.try_into_strict().unwrap();
+        let rejected = wrong_sign.clone().try_into_strict::<YPBankTextFormat>();
+        let lenient: YPBankCsvFormat = wrong_sign.try_into().unwrap();
+
+        // Assert
+        assert_eq!(strict.amount, 50000);
+        assert!(matches!(rejected, Err(ParseError::ParseError { .. })));
+        assert_eq!(lenient.amount, strict.amount);
+    }
+
     #[test]
     fn test_transaction_to_binary_conversion() {
         // Arrange: создаем универсальную транзакцию с пустым описанием
@@ -675,3 +1190,424 @@ mod conversion_tests {
         assert_eq!(csv_record.description, "".to_string()); // Пустая строка
     }
 }
+
+#[cfg(test)]
+mod validation_tests {
+    use super::*;
+
+    fn create_zero_amount_transaction() -> YPBankTransaction {
+        YPBankTransaction {
+            tx_id: 1234567890000001,
+            tx_type: TxType::Deposit,
+            from_user_id: 0,
+            to_user_id: 1002,
+            amount: 0,
+            timestamp: 1633046400,
+            status: TxStatus::Success,
+            description: Some("Status update".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_zero_amount_allowed_by_default() {
+        // Arrange
+        let transaction = create_zero_amount_transaction();
+
+        // Act & Assert
+        assert!(transaction.validate().is_ok());
+        assert!(transaction.validate_with(ZeroAmountPolicy::Allow).is_ok());
+    }
+
+    #[test]
+    fn test_zero_amount_rejected_by_policy() {
+        // Arrange
+        let transaction = create_zero_amount_transaction();
+
+        // Act
+        let result = transaction.validate_with(ZeroAmountPolicy::Reject);
+
+        // Assert
+        assert!(matches!(result, Err(ParseError::ParseError { .. })));
+    }
+
+    #[test]
+    fn test_validate_happy_path() {
+        // Arrange
+        let deposit = YPBankTransaction {
+            amount: 500,
+            ..create_zero_amount_transaction()
+        };
+        let withdrawal = YPBankTransaction {
+            tx_type: TxType::Withdrawal,
+            from_user_id: 1001,
+            to_user_id: 0,
+            amount: -500,
+            ..create_zero_amount_transaction()
+        };
+        let transfer = YPBankTransaction {
+            tx_type: TxType::Transfer,
+            from_user_id: 1001,
+            amount: -500,
+            ..create_zero_amount_transaction()
+        };
+
+        // Act & Assert
+        for transaction in [deposit, withdrawal, transfer] {
+            assert!(transaction.validate().is_ok(), "{transaction:?}");
+        }
+    }
+
+    #[test]
+    fn test_validate_type_violations() {
+        // Arrange
+        let base = YPBankTransaction {
+            amount: 500,
+            ..create_zero_amount_transaction()
+        };
+        let transfer = YPBankTransaction {
+            tx_type: TxType::Transfer,
+            from_user_id: 1001,
+            to_user_id: 1002,
+            amount: -500,
+            ..base.clone()
+        };
+        let withdrawal = YPBankTransaction {
+            tx_type: TxType::Withdrawal,
+            from_user_id: 1001,
+            to_user_id: 0,
+            amount: -500,
+            ..base.clone()
+        };
+        let test_cases = vec![
+            (
+                YPBankTransaction {
+                    from_user_id: 1001,
+                    ..base.clone()
+                },
+                "у пополнения указан отправитель",
+            ),
+            (
+                YPBankTransaction {
+                    amount: -500,
+                    ..base.clone()
+                },
+                "отрицательная сумма пополнения",
+            ),
+            (
+                YPBankTransaction {
+                    to_user_id: 1002,
+                    ..withdrawal.clone()
+                },
+                "у списания указан получатель",
+            ),
+            (
+                YPBankTransaction {
+                    amount: 500,
+                    ..withdrawal
+                },
+                "положительная сумма списания",
+            ),
+            (
+                YPBankTransaction {
+                    from_user_id: 0,
+                    ..transfer.clone()
+                },
+                "у перевода не указан отправитель или получатель",
+            ),
+            (
+                YPBankTransaction {
+                    to_user_id: 0,
+                    ..transfer.clone()
+                },
+                "у перевода не указан отправитель или получатель",
+            ),
+            (
+                YPBankTransaction {
+                    to_user_id: 1001,
+                    ..transfer.clone()
+                },
+                "отправитель и получатель перевода совпадают",
+            ),
+            (
+                YPBankTransaction {
+                    amount: 500,
+                    ..transfer
+                },
+                "положительная сумма перевода",
+            ),
+        ];
+
+        for (transaction, expected) in test_cases {
+            // Act
+            let result = transaction.validate();
+
+            // Assert
+            assert!(
+                matches!(
+                    &result,
+                    Err(ParseError::ParseError { message, .. }) if message.ends_with(expected)
+                ),
+                "{expected}: {result:?}"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod involvement_tests {
+    use super::*;
+    use crate::testing::transaction;
+
+    #[test]
+    fn test_deposit_involves_only_recipient() {
+        // Arrange
+        let tx = transaction(1, TxType::Deposit, 100);
+
+        // Act & Assert
+        assert!(tx.involves_user(1002));
+        assert!(!tx.involves_user(0));
+        assert!(!tx.involves_user(1001));
+    }
+
+    #[test]
+    fn test_withdrawal_involves_only_sender() {
+        // Arrange
+        let tx = transaction(1, TxType::Withdrawal, -100);
+
+        // Act & Assert
+        assert!(tx.involves_user(1001));
+        assert!(!tx.involves_user(0));
+        assert!(!tx.involves_user(1002));
+    }
+
+    #[test]
+    fn test_transfer_involves_both_endpoints() {
+        // Arrange
+        let tx = transaction(1, TxType::Transfer, -100);
+
+        // Act & Assert
+        assert!(tx.involves_user(1001));
+        assert!(tx.involves_user(1002));
+        assert!(!tx.involves_user(1003));
+    }
+}
+
+#[cfg(test)]
+mod builder_tests {
+    use super::*;
+    use crate::utils::get_timestamp;
+
+    #[test]
+    fn test_build_deposit_defaults() {
+        // Arrange
+        let before = get_timestamp();
+
+        // Act
+        let deposit = YPBankTransaction::builder(1, TxType::Deposit)
+            .to_user_id(1001)
+            .amount(500)
+            .build()
+            .unwrap();
+
+        // Assert
+        assert_eq!(deposit.from_user_id, 0);
+        assert_eq!(deposit.status, TxStatus::Pending);
+        assert_eq!(deposit.description, None);
+        assert!(deposit.timestamp >= before && deposit.timestamp <= get_timestamp());
+    }
+
+    #[test]
+    fn test_build_transfer() {
+        // Act
+        let transfer = YPBankTransaction::builder(2, TxType::Transfer)
+            .from_user_id(1001)
+            .to_user_id(1002)
+            .amount(-300)
+            .timestamp(1633046400)
+            .status(TxStatus::Success)
+            .description("Rent")
+            .build()
+            .unwrap();
+
+        // Assert
+        assert_eq!(
+            transfer,
+            YPBankTransaction {
+                tx_id: 2,
+                tx_type: TxType::Transfer,
+                from_user_id: 1001,
+                to_user_id: 1002,
+                amount: -300,
+                timestamp: 1633046400,
+                status: TxStatus::Success,
+                description: Some("Rent".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_build_rejects_invalid_transactions() {
+        // Act
+        let deposit_with_sender = YPBankTransaction::builder(1, TxType::Deposit)
+            .from_user_id(1001)
+            .to_user_id(1002)
+            .amount(500)
+            .build();
+        let transfer_to_self = YPBankTransaction::builder(2, TxType::Transfer)
+            .from_user_id(1001)
+            .to_user_id(1001)
+            .amount(-300)
+            .build();
+        let transfer_without_users = YPBankTransaction::builder(3, TxType::Transfer)
+            .amount(-300)
+            .build();
+
+        // Assert
+        assert!(matches!(
+            deposit_with_sender,
+            Err(ParseError::ParseError { .. })
+        ));
+        assert!(matches!(
+            transfer_to_self,
+            Err(ParseError::ParseError { .. })
+        ));
+        assert!(matches!(
+            transfer_without_users,
+            Err(ParseError::ParseError { .. })
+        ));
+    }
+}
+
+#[cfg(test)]
+mod merge_tests {
+    use super::*;
+    use crate::testing::transaction;
+
+    #[test]
+    fn test_merge_fills_missing_description() {
+        // Arrange
+        let without = transaction(1001, TxType::Transfer, -500);
+        let with = YPBankTransaction {
+            description: Some("Rent".to_string()),
+            ..without.clone()
+        };
+        let empty = YPBankTransaction {
+            description: Some(String::new()),
+            ..without.clone()
+        };
+
+        // Act
+        let merged = without.merge(&with).unwrap();
+        let merged_back = with.merge(&empty).unwrap();
+
+        // Assert
+        assert_eq!(merged, with);
+        assert_eq!(merged_back, with);
+    }
+
+    #[test]
+    fn test_merge_conflicting_fields() {
+        // Arrange
+        let record = YPBankTransaction {
+            description: Some("Rent".to_string()),
+            ..transaction(1001, TxType::Transfer, -500)
+        };
+        let other_amount = transaction(1001, TxType::Transfer, -600);
+        let other_description = YPBankTransaction {
+            description: Some("Salary".to_string()),
+            ..record.clone()
+        };
+
+        // Act & Assert
+        assert!(matches!(
+            record.merge(&other_amount),
+            Err(ParseError::ParseError { .. })
+        ));
+        assert!(matches!(
+            record.merge(&other_description),
+            Err(ParseError::ParseError { .. })
+        ));
+    }
+}
+
+#[cfg(test)]
+mod derive_tests {
+    use super::*;
+
+    #[derive(YPBankFields)]
+    #[allow(dead_code)]
+    struct RecordWithInternalField {
+        tx_id: u64,
+        #[yp(skip)]
+        checksum: u32,
+        description: String,
+    }
+
+    #[test]
+    fn test_yp_skip_excludes_field() {
+        // Act & Assert
+        assert_eq!(RecordWithInternalField::fields(), ["TX_ID", "DESCRIPTION"]);
+        assert!(RecordWithInternalField::has_field_from_str("tx_id"));
+        assert!(!RecordWithInternalField::has_field_from_str("checksum"));
+    }
+
+    #[derive(YPBankFields)]
+    #[allow(dead_code)]
+    struct LegacyRecord {
+        #[yp(rename = "TXID")]
+        tx_id: u64,
+        #[yp(rename = "Memo")]
+        description: String,
+        status: u8,
+    }
+
+    #[test]
+    fn test_yp_rename_overrides_field_name() {
+        // Act & Assert
+        assert_eq!(LegacyRecord::fields(), ["TXID", "MEMO", "STATUS"]);
+        assert!(LegacyRecord::has_field_from_str("txid"));
+        assert!(LegacyRecord::has_field_from_str("memo"));
+        assert!(!LegacyRecord::has_field_from_str("TX_ID"));
+        assert!(!LegacyRecord::has_field_from_str("DESCRIPTION"));
+    }
+
+    #[test]
+    fn test_bin_fields_exclude_desc_len() {
+        // Act & Assert
+        assert!(!YPBankBinFormat::has_field_from_str("DESC_LEN"));
+        assert_eq!(YPBankBinFormat::fields(), YPBankTransaction::fields());
+    }
+
+    #[test]
+    fn test_tx_type_as_pascal() {
+        // Arrange
+        let cases = [
+            (TxType::Deposit, "Deposit", "DEPOSIT"),
+            (TxType::Transfer, "Transfer", "TRANSFER"),
+            (TxType::Withdrawal, "Withdrawal", "WITHDRAWAL"),
+        ];
+
+        for (tx_type, pascal, upper) in cases {
+            // Act & Assert
+            assert_eq!(tx_type.as_pascal(), pascal);
+            assert_eq!(tx_type.to_string(), upper);
+            assert_eq!(pascal.parse::<TxType>(), Ok(tx_type.clone()));
+            assert_eq!(upper.parse::<TxType>(), Ok(tx_type));
+        }
+        assert_eq!(TxStatus::Pending.as_pascal(), "Pending");
+    }
+
+    #[test]
+    fn test_all_variants() {
+        // Act
+        let tx_types: Vec<String> = TxType::all().iter().map(ToString::to_string).collect();
+
+        // Assert
+        assert_eq!(TxType::all().len(), 3);
+        assert_eq!(tx_types, ["DEPOSIT", "TRANSFER", "WITHDRAWAL"]);
+        assert_eq!(
+            TxStatus::all(),
+            [TxStatus::Success, TxStatus::Failure, TxStatus::Pending]
+        );
+    }
+}
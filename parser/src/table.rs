@@ -0,0 +1,177 @@
+//! Табличное представление набора транзакций для просмотра в консоли.
+//!
+//! [`format_table`] выравнивает поля записей по колонкам под строкой заголовка. Числовые
+//! колонки выравниваются по правому краю, текстовые — по левому. Длинные описания обрезаются
+//! до заданной ширины с многоточием в конце, см. [`format_table_with`].
+
+use crate::diff::record_fields;
+use crate::models::YPBankTransaction;
+
+/// Ширина колонки `DESCRIPTION` по умолчанию (в символах).
+pub const DEFAULT_DESCRIPTION_WIDTH: usize = 32;
+
+/// Разделитель колонок.
+const COLUMN_SEPARATOR: &str = "  ";
+
+/// Символ, которым завершается обрезанное описание.
+const ELLIPSIS: char = '…';
+
+/// Колонки, выравниваемые по правому краю (порядок — как в [`record_fields`]).
+const RIGHT_ALIGNED: [bool; 8] = [true, false, true, true, true, true, false, false];
+
+/// Формирует таблицу транзакций с шириной описания [`DEFAULT_DESCRIPTION_WIDTH`].
+///
+/// ## Пример
+///
+/// ```
+/// use parser::models::{TxStatus, TxType, YPBankTransaction};
+/// use parser::table::format_table;
+///
+/// let deposit = YPBankTransaction {
+///     tx_id: 7,
+///     tx_type: TxType::Deposit,
+///     from_user_id: 0,
+///     to_user_id: 1001,
+///     amount: 500,
+///     timestamp: 1633046400,
+///     status: TxStatus::Success,
+///     description: Some("Salary".to_string()),
+/// };
+///
+/// let table = format_table(&[deposit]);
+/// let lines: Vec<&str> = table.lines().collect();
+/// assert_eq!(
+///     lines,
+///     [
+///         "TX_ID  TX_TYPE  FROM_USER_ID  TO_USER_ID  AMOUNT   TIMESTAMP  STATUS   DESCRIPTION",
+///         "    7  DEPOSIT             0        1001     500  1633046400  SUCCESS  Salary",
+///     ]
+/// );
+/// ```
+pub fn format_table(txs: &[YPBankTransaction]) -> String {
+    format_table_with(txs, DEFAULT_DESCRIPTION_WIDTH)
+}
+
+/// Формирует таблицу транзакций: строка заголовка и по строке на каждую запись.
+///
+/// Описания длиннее `description_width` символов обрезаются, последним символом становится
+/// многоточие. Для пустого набора возвращается только заголовок. Пробелы в конце строк
+/// не выводятся.
+pub fn format_table_with(txs: &[YPBankTransaction], description_width: usize) -> String {
+    let header = YPBankTransaction::fields().map(str::to_string);
+    let rows: Vec<[String; 8]> = txs
+        .iter()
+        .map(|tx| {
+            let mut row = record_fields(tx).map(|(_, value)| value);
+            row[7] = truncate(&row[7], description_width);
+            row
+        })
+        .collect();
+
+    let mut widths = header.clone().map(|name| name.chars().count());
+    for row in &rows {
+        for (width, value) in widths.iter_mut().zip(row) {
+            *width = (*width).max(value.chars().count());
+        }
+    }
+
+    let mut table = String::new();
+    for row in std::iter::once(&header).chain(&rows) {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(widths)
+            .zip(RIGHT_ALIGNED)
+            .map(|((value, width), right)| match right {
+                true => format!("{value:>width$}"),
+                false => format!("{value:<width$}"),
+            })
+            .collect();
+        table.push_str(cells.join(COLUMN_SEPARATOR).trim_end());
+        table.push('\n');
+    }
+
+    table
+}
+
+/// Обрезает строку до `width` символов, заменяя последний символ многоточием.
+fn truncate(value: &str, width: usize) -> String {
+    if value.chars().count() <= width {
+        return value.to_string();
+    }
+
+    let mut truncated: String = value.chars().take(width.saturating_sub(1)).collect();
+    if width > 0 {
+        truncated.push(ELLIPSIS);
+    }
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{TxStatus, TxType};
+    use crate::testing::transaction;
+
+    #[test]
+    fn test_format_table_aligns_columns() {
+        // Arrange
+        let txs = [
+            YPBankTransaction {
+                status: TxStatus::Pending,
+                description: Some("Rent".to_string()),
+                ..transaction(1, TxType::Transfer, -5)
+            },
+            YPBankTransaction {
+                status: TxStatus::Failure,
+                ..transaction(123456789, TxType::Withdrawal, -100000)
+            },
+        ];
+
+        // Act
+        let table = format_table(&txs);
+
+        // Assert
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "    TX_ID  TX_TYPE     FROM_USER_ID  TO_USER_ID   AMOUNT   TIMESTAMP  STATUS   DESCRIPTION",
+                "        1  TRANSFER            1001        1002       -5  1633046400  PENDING  Rent",
+                "123456789  WITHDRAWAL          1001           0  -100000  1633046400  FAILURE",
+            ]
+        );
+        let status_column = lines[0].find("STATUS").unwrap();
+        assert!(
+            lines[1..]
+                .iter()
+                .all(|line| line[status_column..].starts_with(['P', 'F']))
+        );
+    }
+
+    #[test]
+    fn test_format_table_truncates_description() {
+        // Arrange
+        let txs = [YPBankTransaction {
+            description: Some("Оплата аренды за октябрь".to_string()),
+            ..transaction(1, TxType::Transfer, -5)
+        }];
+
+        // Act
+        let table = format_table_with(&txs, 10);
+
+        // Assert
+        assert!(table.lines().nth(1).unwrap().ends_with("  Оплата ар…"));
+    }
+
+    #[test]
+    fn test_format_table_empty() {
+        // Act
+        let table = format_table(&[]);
+
+        // Assert
+        assert_eq!(
+            table,
+            "TX_ID  TX_TYPE  FROM_USER_ID  TO_USER_ID  AMOUNT  TIMESTAMP  STATUS  DESCRIPTION\n"
+        );
+    }
+}
@@ -0,0 +1,172 @@
+//! Генерация тестовых наборов транзакций.
+//!
+//! Позволяет получать большие воспроизводимые наборы данных для нагрузочных тестов и фаззинга
+//! без ручной подготовки файлов.
+
+use crate::models::{TxStatus, TxType, YPBankTransaction};
+
+/// Начальное значение `TX_ID` для сгенерированных транзакций.
+const FIRST_TX_ID: u64 = 1_000_000_000_000_000;
+/// Начальная метка времени для сгенерированных транзакций.
+const FIRST_TIMESTAMP: u64 = 1_633_036_800;
+
+/// Генерирует `n` псевдослучайных, но воспроизводимых транзакций.
+///
+/// Одинаковый `seed` всегда даёт одинаковый результат. Транзакции корректны: идентификаторы
+/// уникальны, метки времени не убывают, у пополнений нет отправителя, у списаний нет
+/// получателя, а знак суммы соответствует типу операции.
+///
+/// ## Пример
+///
+/// ```
+/// use parser::testing::generate;
+///
+/// let fixture = generate(1000, 42);
+/// assert_eq!(fixture, generate(1000, 42));
+/// ```
+pub fn generate(n: usize, seed: u64) -> Vec<YPBankTransaction> {
+    let mut rng = SplitMix64(seed);
+    let mut timestamp = FIRST_TIMESTAMP;
+
+    (0..n as u64)
+        .map(|i| {
+            let tx_type = match rng.next_below(3) {
+                0 => TxType::Deposit,
+                1 => TxType::Transfer,
+                _ => TxType::Withdrawal,
+            };
+            let status = match rng.next_below(10) {
+                0 => TxStatus::Failure,
+                1 => TxStatus::Pending,
+                _ => TxStatus::Success,
+            };
+
+            let user_a = rng.next_below(10_000) + 1;
+            let user_b = (user_a + rng.next_below(9_999)) % 10_000 + 1;
+            let (from_user_id, to_user_id) = match tx_type {
+                TxType::Deposit => (0, user_a),
+                TxType::Transfer => (user_a, user_b),
+                TxType::Withdrawal => (user_a, 0),
+            };
+
+            let magnitude = (rng.next_below(1_000_000) + 1) as i64;
+            let amount = match tx_type {
+                TxType::Deposit => magnitude,
+                _ => -magnitude,
+            };
+
+            timestamp += rng.next_below(60);
+            let description = (rng.next_below(4) != 0).then(|| format!("Record number {}", i + 1));
+
+            YPBankTransaction {
+                tx_id: FIRST_TX_ID + i,
+                tx_type,
+                from_user_id,
+                to_user_id,
+                amount,
+                timestamp,
+                status,
+                description,
+            }
+        })
+        .collect()
+}
+
+/// Одна транзакция для тестов с заданными `tx_id`, типом и суммой.
+///
+/// Участники соответствуют типу операции: у перевода отправитель `1001` и получатель `1002`,
+/// у пополнения нет отправителя, у списания — получателя. Статус [`TxStatus::Success`], время
+/// `1633046400` (1 октября 2021 года), описание отсутствует. Знак суммы не проверяется. Прочие
+/// поля задаются синтаксисом обновления структуры.
+///
+/// ## Пример
+///
+/// ```
+/// use parser::models::{TxType, YPBankTransaction};
+/// use parser::testing::transaction;
+///
+/// let tx = YPBankTransaction {
+///     description: Some("Rent".to_string()),
+///     ..transaction(1, TxType::Transfer, -500)
+/// };
+/// assert!(tx.validate().is_ok());
+/// ```
+pub fn transaction(tx_id: u64, tx_type: TxType, amount: i64) -> YPBankTransaction {
+    let (from_user_id, to_user_id) = match tx_type {
+        TxType::Deposit => (0, 1002),
+        TxType::Transfer => (1001, 1002),
+        TxType::Withdrawal => (1001, 0),
+    };
+
+    YPBankTransaction {
+        tx_id,
+        tx_type,
+        from_user_id,
+        to_user_id,
+        amount,
+        timestamp: 1_633_046_400,
+        status: TxStatus::Success,
+        description: None,
+    }
+}
+
+/// Простой генератор псевдослучайных чисел SplitMix64.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Число в диапазоне `0..bound`.
+    fn next_below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_is_reproducible() {
+        // Act
+        let first = generate(500, 7);
+        let second = generate(500, 7);
+        let other = generate(500, 8);
+
+        // Assert
+        assert_eq!(first.len(), 500);
+        assert_eq!(first, second);
+        assert_ne!(first, other);
+    }
+
+    #[test]
+    fn test_generate_respects_type_rules() {
+        // Act
+        let transactions = generate(1000, 2025);
+
+        // Assert
+        for tx in &transactions {
+            assert!(tx.validate().is_ok());
+            match tx.tx_type {
+                TxType::Deposit => assert!(tx.from_user_id == 0 && tx.amount > 0),
+                TxType::Transfer => {
+                    assert!(tx.from_user_id != 0 && tx.to_user_id != 0);
+                    assert_ne!(tx.from_user_id, tx.to_user_id);
+                    assert!(tx.amount < 0);
+                }
+                TxType::Withdrawal => assert!(tx.to_user_id == 0 && tx.amount < 0),
+            }
+        }
+        assert!(
+            transactions
+                .windows(2)
+                .all(|w| w[0].timestamp <= w[1].timestamp)
+        );
+    }
+}
@@ -2,19 +2,24 @@
 
 use crate::MAX_SIZE_CSV_TXT_BYTES;
 use crate::errors::ParseError;
+use crate::format::options::{ReadOptions, WriteOptions};
+use std::fmt::Display;
 use std::io::{BufReader, Read, Write};
+use std::str::FromStr;
+
+pub use parser_macros::TxDisplay;
 
 /// Читает и записывает данные банковских операций в различных форматах.
 ///
 /// Этот типаж определяет общий интерфейс для работы с различными форматами
 /// банковских данных (CSV, JSON, XML и т.д.). Каждый формат должен реализовать
-/// логику парсинга в [`YPBankIO::read_executor`].
+/// логику парсинга в [`YPBankIO::read_executor_with`] и записи в [`YPBankIO::write_to_with`].
 ///
 /// ## Методы по умолчанию
 ///
 /// Типаж предоставляет реализацию [`YPBankIO::read_from`] по умолчанию, которая:
 /// 1. Читает все данные из `reader` в строку
-/// 2. Передаёт строку в [`YPBankIO::read_executor`]
+/// 2. Передаёт строку в [`YPBankIO::read_executor_with`]
 /// 3. Проверяет, что результат не пустой
 ///
 /// Другие методы декларативны, и должны быть определены.
@@ -28,6 +33,29 @@ pub trait YPBankIO {
     /// методу [`read_executor`]. Переопределите его, если нужна
     /// специальная логика чтения.
     fn read_from<R: Read>(reader: &mut R) -> Result<Vec<Self::DataFormat>, ParseError> {
+        Self::read_from_limited(reader, None)
+    }
+
+    /// Читает данные как [`YPBankIO::read_from`], но не более `max_records` записей.
+    ///
+    /// Если записей оказалось больше, возвращается [`ParseError::RecordLimitExceeded`].
+    /// `None` снимает ограничение.
+    fn read_from_limited<R: Read>(
+        reader: &mut R,
+        max_records: Option<usize>,
+    ) -> Result<Vec<Self::DataFormat>, ParseError> {
+        let options = ReadOptions {
+            max_records,
+            ..ReadOptions::default()
+        };
+        Self::read_from_with(reader, &options)
+    }
+
+    /// Читает данные как [`YPBankIO::read_from`] с заданными настройками [`ReadOptions`].
+    fn read_from_with<R: Read>(
+        reader: &mut R,
+        options: &ReadOptions,
+    ) -> Result<Vec<Self::DataFormat>, ParseError> {
         let mut buffer = String::new();
         let mut buf_reader = BufReader::new(reader);
         buf_reader
@@ -38,20 +66,84 @@ pub trait YPBankIO {
             return Err(ParseError::lim_exceed(buffer.len(), MAX_SIZE_CSV_TXT_BYTES));
         }
 
-        let transaction = Self::read_executor(buffer)?;
+        let transaction = Self::read_executor_with(buffer, options)?;
+        if let Some(limit) = options
+            .max_records
+            .filter(|&limit| transaction.len() > limit)
+        {
+            return Err(ParseError::record_limit(limit));
+        }
+
         if transaction.is_empty() {
-            return Err(ParseError::EmptyData);
+            return Err(ParseError::empty_data());
         }
 
         Ok(transaction)
     }
 
+    /// Парсит строку с данными в вектор записей с настройками по умолчанию.
+    fn read_executor(buffer: String) -> Result<Vec<Self::DataFormat>, ParseError> {
+        Self::read_executor_with(buffer, &ReadOptions::default())
+    }
+
     /// Парсит строку с данными в вектор записей.
     ///
     /// Этот метод должен быть реализован для каждого формата.
     /// Он содержит специфичную для формата логику парсинга.
-    fn read_executor(buffer: String) -> Result<Vec<Self::DataFormat>, ParseError>;
+    fn read_executor_with(
+        buffer: String,
+        options: &ReadOptions,
+    ) -> Result<Vec<Self::DataFormat>, ParseError>;
 
     /// Записывает вектор записей в writer.
-    fn write_to<W: Write>(writer: W, records: &[Self::DataFormat]) -> Result<(), ParseError>;
+    ///
+    /// `writer` можно передать по ссылке (`&mut writer`, так как `&mut W` тоже реализует
+    /// [`Write`]) и продолжить запись в него после вызова.
+    fn write_to<W: Write>(writer: W, records: &[Self::DataFormat]) -> Result<(), ParseError> {
+        Self::write_to_with(writer, records, &WriteOptions::default())
+    }
+
+    /// Записывает вектор записей в writer с заданными настройками [`WriteOptions`].
+    ///
+    /// Реализация обязана сбросить буферы (`flush`) перед возвратом, а ошибку сброса вернуть
+    /// вызывающему: иначе данные могут не попасть в целевой `writer`.
+    fn write_to_with<W: Write>(
+        writer: W,
+        records: &[Self::DataFormat],
+        options: &WriteOptions,
+    ) -> Result<(), ParseError>;
+}
+
+/// Перечисление вида операции (тип или статус транзакции).
+///
+/// Реализуется derive-макросом [`TxDisplay`] для [`TxType`](crate::models::TxType),
+/// [`TxStatus`](crate::models::TxStatus) и пользовательских перечислений, что позволяет
+/// подключать собственные типы операций без изменения библиотеки (см.
+/// [`YPBankTextRecord`](crate::models::YPBankTextRecord)).
+///
+/// ## Пример
+///
+/// ```
+/// use parser::traits::{TxDisplay, TxKind};
+///
+/// #[repr(u8)]
+/// #[derive(Debug, TxDisplay, Clone, PartialEq)]
+/// enum BankTxType {
+///     Deposit = 0,
+///     Fee = 10,
+/// }
+///
+/// assert_eq!(BankTxType::Fee.as_u8(), 10);
+/// assert_eq!(<BankTxType as TxKind>::from_u8(0), Some(BankTxType::Deposit));
+/// assert_eq!("FEE".parse::<BankTxType>(), Ok(BankTxType::Fee));
+/// ```
+///
+/// Значения вариантов не должны повторяться: [`TxDisplay`] сообщает о повторе ошибкой
+/// компиляции с именами обоих вариантов (см. `tests/ui/duplicate_discriminant.rs`).
+pub trait TxKind: Sized + Clone + Display + FromStr {
+    /// Числовой код варианта.
+    fn as_u8(&self) -> u8;
+
+    /// Вариант по числовому коду, `None` для неизвестного кода.
+    fn from_u8(value: u8) -> Option<Self>;
 }
@@ -1,13 +1,338 @@
 //! Вспомогательные универсальные утилиты библиотеки.
 
+use crate::errors::ParseError;
+use crate::models::TxType;
+use std::collections::HashMap;
 use std::time::SystemTime;
 
 /// Предоставляет количество секунд от начала эпохи UNIX, на основе системного времени.
 ///
-/// В случае возникновения ошибки паникует.
+/// Паникует, если системные часы установлены раньше начала эпохи. Вариант без паники —
+/// [`try_get_timestamp`].
 pub fn get_timestamp() -> u64 {
+    try_get_timestamp().expect("Системное время раньше начала эпохи UNIX")
+}
+
+/// Предоставляет количество секунд от начала эпохи UNIX, на основе системного времени.
+///
+/// Если системные часы установлены раньше начала эпохи, возвращается
+/// [`ParseError::IOError`].
+pub fn try_get_timestamp() -> Result<u64, ParseError> {
     SystemTime::now()
         .duration_since(SystemTime::UNIX_EPOCH)
-        .unwrap()
-        .as_secs()
+        .map(|duration| duration.as_secs())
+        .map_err(|err| {
+            ParseError::io_error(
+                std::io::Error::other(err),
+                "Системное время раньше начала эпохи UNIX",
+            )
+        })
+}
+
+/// Преобразует знаковую сумму универсальной структуры в беззнаковую (для `csv` и `txt`).
+///
+/// Знак отбрасывается: направление операции определяется её типом. Значение `i64::MIN` не
+/// допускается, так как его модуль не может быть преобразован обратно в `i64` при чтении, и
+/// возвращается ошибка [`ParseError::OverflowSize`].
+///
+/// ## Пример
+///
+/// ```
+/// use parser::utils::signed_to_unsigned;
+///
+/// assert_eq!(signed_to_unsigned(-500).unwrap(), 500);
+/// assert!(signed_to_unsigned(i64::MIN).is_err());
+/// ```
+pub fn signed_to_unsigned(amount: i64) -> Result<u64, ParseError> {
+    if amount == i64::MIN {
+        return Err(ParseError::over_flow_size("i64", "u64", amount));
+    }
+
+    Ok(amount.unsigned_abs())
+}
+
+/// Преобразует беззнаковую сумму (из `csv` и `txt`) в знаковую для универсальной структуры.
+///
+/// Для переводов и списаний сумма становится отрицательной, для пополнений остаётся
+/// положительной. Значения больше `i64::MAX` приводят к ошибке [`ParseError::OverflowSize`].
+///
+/// ## Пример
+///
+/// ```
+/// use parser::models::TxType;
+/// use parser::utils::unsigned_to_signed;
+///
+/// assert_eq!(unsigned_to_signed(500, &TxType::Withdrawal).unwrap(), -500);
+/// assert_eq!(unsigned_to_signed(500, &TxType::Deposit).unwrap(), 500);
+/// ```
+pub fn unsigned_to_signed(amount: u64, tx_type: &TxType) -> Result<i64, ParseError> {
+    let amount =
+        i64::try_from(amount).map_err(|_| ParseError::over_flow_size("u64", "i64", amount))?;
+
+    match tx_type {
+        TxType::Transfer | TxType::Withdrawal => Ok(-amount),
+        TxType::Deposit => Ok(amount),
+    }
+}
+
+/// Соглашение о записи метаданных в описании транзакции (например, `invoice=123; ref=ABC`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MetadataConvention {
+    /// Разделитель пар «ключ-значение».
+    pub pair_separator: char,
+    /// Разделитель ключа и значения внутри пары.
+    pub key_value_delimiter: char,
+}
+
+impl Default for MetadataConvention {
+    /// Пары разделяются `;`, ключ и значение — `=`.
+    fn default() -> Self {
+        Self {
+            pair_separator: ';',
+            key_value_delimiter: '=',
+        }
+    }
+}
+
+impl MetadataConvention {
+    /// Разбирает метаданные из описания по заданному соглашению.
+    ///
+    /// Пробелы вокруг ключей и значений отбрасываются. Фрагменты без разделителя ключа
+    /// и значения, а также с пустым ключом пропускаются. При повторе ключа сохраняется
+    /// последнее значение.
+    pub fn parse(&self, description: &str) -> HashMap<String, String> {
+        description
+            .split(self.pair_separator)
+            .filter_map(|pair| pair.split_once(self.key_value_delimiter))
+            .map(|(key, value)| (key.trim(), value.trim()))
+            .filter(|(key, _)| !key.is_empty())
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+}
+
+/// Разбирает метаданные из описания по соглашению по умолчанию
+/// (см. [`MetadataConvention::default`]).
+///
+/// ## Пример
+///
+/// ```
+/// use parser::utils::parse_description_metadata;
+///
+/// let metadata = parse_description_metadata("invoice=123; ref=ABC");
+/// assert_eq!(metadata["invoice"], "123");
+/// assert_eq!(metadata["ref"], "ABC");
+/// ```
+pub fn parse_description_metadata(description: &str) -> HashMap<String, String> {
+    MetadataConvention::default().parse(description)
+}
+
+/// Форматирует время в секундах от начала эпохи UNIX как строку ISO-8601 в UTC
+/// (`YYYY-MM-DDTHH:MM:SSZ`).
+///
+/// ## Пример
+///
+/// ```
+/// use parser::utils::format_iso8601;
+///
+/// assert_eq!(format_iso8601(0), "1970-01-01T00:00:00Z");
+/// assert_eq!(format_iso8601(1633046400), "2021-10-01T00:00:00Z");
+/// ```
+pub fn format_iso8601(timestamp: u64) -> String {
+    let days = timestamp / 86_400;
+    let seconds = timestamp % 86_400;
+
+    // Преобразование количества дней в гражданскую дату (алгоритм Говарда Хиннанта).
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        seconds / 3_600,
+        seconds % 3_600 / 60,
+        seconds % 60
+    )
+}
+
+/// Разбирает строку ISO-8601 в UTC (`YYYY-MM-DDTHH:MM:SSZ`, как у [`format_iso8601`])
+/// в секунды от начала эпохи UNIX.
+///
+/// Возвращает `None`, если строка в другом виде, дата не существует или предшествует эпохе.
+///
+/// ## Пример
+///
+/// ```
+/// use parser::utils::parse_iso8601;
+///
+/// assert_eq!(parse_iso8601("1970-01-01T00:00:00Z"), Some(0));
+/// assert_eq!(parse_iso8601("2021-10-01T00:00:00Z"), Some(1633046400));
+/// assert_eq!(parse_iso8601("2021-02-29T00:00:00Z"), None);
+/// ```
+pub fn parse_iso8601(value: &str) -> Option<u64> {
+    let bytes = value.as_bytes();
+    let layout_ok = bytes.len() == 20
+        && value.is_ascii()
+        && [
+            (4, b'-'),
+            (7, b'-'),
+            (10, b'T'),
+            (13, b':'),
+            (16, b':'),
+            (19, b'Z'),
+        ]
+        .iter()
+        .all(|&(i, separator)| bytes[i] == separator);
+    if !layout_ok {
+        return None;
+    }
+
+    let number = |from: usize, to: usize| -> Option<u64> {
+        let part = &value[from..to];
+        if !part.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        part.parse().ok()
+    };
+    let (year, month, day) = (number(0, 4)?, number(5, 7)?, number(8, 10)?);
+    let (hour, minute, second) = (number(11, 13)?, number(14, 16)?, number(17, 19)?);
+
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let month_days = match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        1..=12 => 31,
+        _ => return None,
+    };
+    if year < 1970 || day == 0 || day > month_days || hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+
+    // Обратное к format_iso8601 преобразование даты в количество дней (алгоритм Говарда
+    // Хиннанта): год отсчитывается от марта.
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let year_of_era = year % 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+
+    Some(days * 86_400 + hour * 3_600 + minute * 60 + second)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_get_timestamp() {
+        // Act
+        let timestamp = try_get_timestamp().unwrap();
+
+        // Assert: 2020-09-13T12:26:40Z — заведомо прошедший момент.
+        assert!(timestamp > 1_600_000_000);
+        assert!(get_timestamp() >= timestamp);
+    }
+
+    #[test]
+    fn test_signed_to_unsigned() {
+        // Act & Assert
+        assert_eq!(signed_to_unsigned(0).unwrap(), 0);
+        assert_eq!(signed_to_unsigned(-1500).unwrap(), 1500);
+        assert_eq!(signed_to_unsigned(i64::MAX).unwrap(), i64::MAX as u64);
+        assert_eq!(signed_to_unsigned(-i64::MAX).unwrap(), i64::MAX as u64);
+        assert!(matches!(
+            signed_to_unsigned(i64::MIN),
+            Err(ParseError::OverflowSize { .. })
+        ));
+    }
+
+    #[test]
+    fn test_unsigned_to_signed() {
+        // Act & Assert
+        assert_eq!(unsigned_to_signed(0, &TxType::Withdrawal).unwrap(), 0);
+        assert_eq!(unsigned_to_signed(1500, &TxType::Deposit).unwrap(), 1500);
+        assert_eq!(unsigned_to_signed(1500, &TxType::Transfer).unwrap(), -1500);
+        assert_eq!(
+            unsigned_to_signed(i64::MAX as u64, &TxType::Withdrawal).unwrap(),
+            -i64::MAX
+        );
+        assert!(matches!(
+            unsigned_to_signed(i64::MAX as u64 + 1, &TxType::Deposit),
+            Err(ParseError::OverflowSize { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_description_metadata() {
+        // Act
+        let metadata = parse_description_metadata("invoice=123; ref=ABC");
+
+        // Assert
+        assert_eq!(metadata.len(), 2);
+        assert_eq!(metadata.get("invoice").map(String::as_str), Some("123"));
+        assert_eq!(metadata.get("ref").map(String::as_str), Some("ABC"));
+    }
+
+    #[test]
+    fn test_parse_description_metadata_custom_convention() {
+        // Arrange
+        let convention = MetadataConvention {
+            pair_separator: '|',
+            key_value_delimiter: ':',
+        };
+
+        // Act
+        let metadata = convention.parse("invoice: 123 | note | :empty | ref:ABC");
+
+        // Assert
+        assert_eq!(metadata.len(), 2);
+        assert_eq!(metadata["invoice"], "123");
+        assert_eq!(metadata["ref"], "ABC");
+    }
+
+    #[test]
+    fn test_format_iso8601() {
+        // Act & Assert
+        assert_eq!(format_iso8601(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_iso8601(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(
+            format_iso8601(1_633_046_400 + 3_723),
+            "2021-10-01T01:02:03Z"
+        );
+        assert_eq!(format_iso8601(4_102_444_799), "2099-12-31T23:59:59Z");
+    }
+
+    #[test]
+    fn test_parse_iso8601() {
+        // Act & Assert
+        assert_eq!(parse_iso8601("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(parse_iso8601("2021-10-01T00:00:00Z"), Some(1_633_046_400));
+        assert_eq!(parse_iso8601("2000-02-29T00:00:00Z"), Some(951_782_400));
+        for timestamp in [0, 59, 951_782_400, 1_633_046_400 + 3_723, 4_102_444_799] {
+            assert_eq!(parse_iso8601(&format_iso8601(timestamp)), Some(timestamp));
+        }
+        for invalid in [
+            "",
+            "1633046400",
+            "2021-10-01 00:00:00Z",
+            "2021-10-01T00:00:00",
+            "2021-02-29T00:00:00Z",
+            "2021-13-01T00:00:00Z",
+            "2021-10-01T24:00:00Z",
+            "1969-12-31T23:59:59Z",
+            "2021-1O-01T00:00:00Z",
+            "+021-10-01T00:00:00Z",
+        ] {
+            assert_eq!(parse_iso8601(invalid), None, "{invalid}");
+        }
+    }
 }
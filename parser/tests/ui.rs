@@ -0,0 +1,7 @@
+//! Проверка ошибок компиляции derive-макросов.
+
+#[test]
+fn ui() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
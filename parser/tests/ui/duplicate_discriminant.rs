@@ -0,0 +1,10 @@
+use parser::traits::TxDisplay;
+
+#[repr(u8)]
+#[derive(Debug, TxDisplay, Clone, PartialEq)]
+enum BankTxType {
+    Deposit = 1,
+    Fee = 1,
+}
+
+fn main() {}